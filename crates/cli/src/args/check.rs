@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+
+use crate::*;
+
+#[derive(Args)]
+pub struct CheckArgs {
+    /// Read config from provided path
+    #[arg(long, short, display_order = 0, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Required unless it can be inferred from the file arguments.
+    #[arg(long, short, display_order = 20)]
+    pub format: Option<Format>,
+    /// Files, directories or globs to check. Only files matched by a creation rule are checked.
+    #[arg(value_hint = ValueHint::AnyPath, default_value = ".")]
+    pub paths: Vec<PathBuf>,
+    /// Creation rules are read from the merged config.
+    #[arg(skip)]
+    pub merged_config: Config,
+}
+
+impl ConfigArg for CheckArgs {
+    fn config_path(&self) -> Option<&Path> {
+        self.config.as_deref()
+    }
+
+    // Paths spread over several directories are left to the current directory.
+    fn input_path(&self) -> Option<&Path> {
+        match self.paths.as_slice() {
+            [path] => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl MergeConfig for CheckArgs {
+    fn merge_config(&mut self, config: Config) {
+        self.merged_config = config;
+    }
+}
@@ -24,6 +24,18 @@ pub enum CliSubcommand {
     Keys(KeysSubcommand),
     /// Make a config the single source of configuration truth for an encrypted rops file
     Refresh(RefreshArgs),
+    /// Verify that files matched by creation rules are encrypted as configured, without accessing any private keys. Exits nonzero on drift
+    Check(CheckArgs),
+    /// Report whether files are encrypted, partially encrypted, plaintext or malformed. Exits nonzero unless all are encrypted
+    #[command(name = "filestatus")]
+    FileStatus(FileStatusArgs),
+    /// Print the metadata of an encrypted rops file without accessing any private keys
+    Inspect(InspectArgs),
+    /// Compare the decrypted key paths and metadata of two encrypted rops files
+    Diff(DiffArgs),
+    /// Git integration for keeping rops files encrypted in the repository and decrypted in the working tree
+    #[command(subcommand)]
+    Git(GitSubcommand),
 }
 
 impl ConfigArg for CliArgs {
@@ -34,6 +46,26 @@ impl ConfigArg for CliArgs {
             CliSubcommand::Edit(sub_command) => sub_command.config_path(),
             CliSubcommand::Keys(sub_command) => sub_command.config_path(),
             CliSubcommand::Refresh(sub_command) => sub_command.config_path(),
+            CliSubcommand::Check(sub_command) => sub_command.config_path(),
+            CliSubcommand::FileStatus(sub_command) => sub_command.config_path(),
+            CliSubcommand::Inspect(sub_command) => sub_command.config_path(),
+            CliSubcommand::Diff(sub_command) => sub_command.config_path(),
+            CliSubcommand::Git(sub_command) => sub_command.config_path(),
+        }
+    }
+
+    fn input_path(&self) -> Option<&std::path::Path> {
+        match &self.cmd {
+            CliSubcommand::Encrypt(sub_command) => sub_command.input_path(),
+            CliSubcommand::Decrypt(sub_command) => sub_command.input_path(),
+            CliSubcommand::Edit(sub_command) => sub_command.input_path(),
+            CliSubcommand::Keys(sub_command) => sub_command.input_path(),
+            CliSubcommand::Refresh(sub_command) => sub_command.input_path(),
+            CliSubcommand::Check(sub_command) => sub_command.input_path(),
+            CliSubcommand::FileStatus(sub_command) => sub_command.input_path(),
+            CliSubcommand::Inspect(sub_command) => sub_command.input_path(),
+            CliSubcommand::Diff(sub_command) => sub_command.input_path(),
+            CliSubcommand::Git(sub_command) => sub_command.input_path(),
         }
     }
 }
@@ -46,6 +78,11 @@ impl MergeConfig for CliArgs {
             CliSubcommand::Edit(sub_command) => sub_command.merge_config(config),
             CliSubcommand::Keys(sub_command) => sub_command.merge_config(config),
             CliSubcommand::Refresh(sub_command) => sub_command.merge_config(config),
+            CliSubcommand::Check(sub_command) => sub_command.merge_config(config),
+            CliSubcommand::FileStatus(sub_command) => sub_command.merge_config(config),
+            CliSubcommand::Inspect(sub_command) => sub_command.merge_config(config),
+            CliSubcommand::Diff(sub_command) => sub_command.merge_config(config),
+            CliSubcommand::Git(sub_command) => sub_command.merge_config(config),
         }
     }
 }
@@ -8,19 +8,30 @@ use crate::*;
 pub struct DecryptArgs {
     #[command(flatten)]
     pub input_args: InputArgs,
+    /// Defaults to the input format
+    #[arg(long, display_order = 21)]
+    pub output_format: Option<Format>,
     #[arg(long, short, requires = "file", action(ArgAction::SetTrue), display_order = 0)]
     /// Decrypt file in place rather than printing the result to stdout, metadata excluded
     pub in_place: Option<bool>,
+    /// List the files which would change, and why, without writing them.
+    #[arg(long, requires = "file", action(ArgAction::SetTrue), display_order = 1)]
+    pub dry_run: Option<bool>,
 }
 
 impl ConfigArg for DecryptArgs {
     fn config_path(&self) -> Option<&Path> {
         self.input_args.config_path()
     }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_args.input_path()
+    }
 }
 
 impl MergeConfig for DecryptArgs {
-    fn merge_config(&mut self, _config: Config) {
-        // todo!()
+    fn merge_config(&mut self, config: Config) {
+        self.input_args.merge_format(config.decrypt.format);
+        self.output_format = self.output_format.or(config.decrypt.output_format);
     }
 }
@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+
+use clap::{ArgAction, Args, ValueHint};
+
+use crate::*;
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Print the values of changed key paths rather than masking them
+    #[arg(long, action(ArgAction::SetTrue), display_order = 10)]
+    pub show_values: Option<bool>,
+    /// Required unless it can be inferred from the file arguments.
+    #[arg(long, short, display_order = 20)]
+    pub format: Option<Format>,
+    /// Path to the original encrypted rops file.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub old_file: PathBuf,
+    /// Path to the changed encrypted rops file.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub new_file: PathBuf,
+}
+
+impl ConfigArg for DiffArgs {
+    fn config_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl MergeConfig for DiffArgs {
+    fn merge_config(&mut self, _config: Config) {}
+}
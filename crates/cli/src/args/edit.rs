@@ -1,10 +1,35 @@
+use std::path::Path;
+
+use clap::Args;
+
 use crate::*;
 
-// TEMP: make into struct and derive Args once more fields are added.
-pub type EditArgs = InputArgs;
+#[derive(Args)]
+pub struct EditArgs {
+    #[command(flatten)]
+    pub input_args: InputArgs,
+    /// Defaults to the input format
+    #[arg(long, display_order = 21)]
+    pub output_format: Option<Format>,
+    /// Keep the ciphertexts of unchanged values, defaults to true. Disable to re-encrypt every value with a new nonce.
+    #[arg(long, value_name = "BOOL", display_order = 22)]
+    pub preserve_nonces: Option<bool>,
+}
+
+impl ConfigArg for EditArgs {
+    fn config_path(&self) -> Option<&Path> {
+        self.input_args.config_path()
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_args.input_path()
+    }
+}
 
 impl MergeConfig for EditArgs {
-    fn merge_config(&mut self, _config: Config) {
-        // todo!()
+    fn merge_config(&mut self, config: Config) {
+        self.input_args.merge_format(config.edit.format);
+        self.output_format = self.output_format.or(config.edit.output_format);
+        self.preserve_nonces = self.preserve_nonces.or(config.edit.preserve_nonces);
     }
 }
@@ -1,10 +1,10 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{ArgAction, Args};
 
 use crate::*;
 
-#[derive(Args)]
+#[derive(Clone, Args)]
 pub struct EncryptArgs {
     #[command(flatten)]
     pub integration_keys: IntegrationKeys,
@@ -13,32 +13,75 @@ pub struct EncryptArgs {
     /// Requires a partial encryption setting
     #[arg(long, display_order = 11, requires = "partial_encryption", action(ArgAction::SetTrue))]
     pub mac_only_encrypted: Option<bool>,
+    /// Encrypt nulls, empty strings and empty collections. Files with encrypted nulls or empty collections can't be decrypted by SOPS.
+    #[arg(long, display_order = 11, action(ArgAction::SetTrue))]
+    pub encrypt_empty_values: Option<bool>,
+    /// Use a single data key for all documents of a multi-document file, as done by SOPS.
+    #[arg(long, display_order = 12, action(ArgAction::SetTrue))]
+    pub shared_data_key: Option<bool>,
+    /// Defaults to AES256-GCM, the only cipher supported by SOPS.
+    #[arg(long, display_order = 13)]
+    pub cipher: Option<CipherVariant>,
+    /// MAC hasher, defaults to SHA512, the only hasher supported by SOPS.
+    #[arg(long, display_order = 14)]
+    pub hasher: Option<HasherVariant>,
     #[command(flatten)]
     pub input_args: InputArgs,
+    /// Defaults to the input format
+    #[arg(long, display_order = 21)]
+    pub output_format: Option<Format>,
     #[arg(long, short, requires = "file", action(ArgAction::SetTrue), display_order = 0)]
     /// Encrypt file in place rather than printing the result to stdout.
     pub in_place: Option<bool>,
+    /// List the files which would change, and why, without writing them.
+    #[arg(long, requires = "file", action(ArgAction::SetTrue), display_order = 1)]
+    pub dry_run: Option<bool>,
+    /// Set by a matching creation rule with a destination, written to unless encrypting in place.
+    #[arg(skip)]
+    pub destination: Option<PathBuf>,
 }
 
 impl ConfigArg for EncryptArgs {
     fn config_path(&self) -> Option<&Path> {
         self.input_args.config_path()
     }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_args.input_path()
+    }
 }
 
 impl MergeConfig for EncryptArgs {
     fn merge_config(&mut self, config: Config) {
+        self.input_args.merge_format(config.encrypt.format);
+        self.shared_data_key = self.shared_data_key.or(config.encrypt.shared_data_key);
+        self.cipher = self.cipher.or(config.encrypt.cipher);
+        self.hasher = self.hasher.or(config.encrypt.hasher);
+
+        // Merged separately for each file of a batch.
+        if self.input_args.file.as_deref().is_some_and(Cli::is_batch_path) {
+            return;
+        }
+
         // stdin regarded as empty path
         let file_path = self.input_args.file.as_deref().unwrap_or(Path::new(""));
-        let file_path = file_path.to_string_lossy();
         for creation_rule in config.creation_rules {
-            if creation_rule.path_regex.is_match(&file_path) {
+            if creation_rule.is_match(file_path) {
+                if self.input_args.file.is_some() {
+                    self.destination = creation_rule.destination_path(file_path);
+                }
+
                 self.integration_keys.merge(creation_rule.integration_keys);
+                self.output_format = self.output_format.or(creation_rule.output_format);
 
                 if self.mac_only_encrypted.is_none() {
                     self.mac_only_encrypted = creation_rule.mac_only_encrypted;
                 }
 
+                if self.encrypt_empty_values.is_none() {
+                    self.encrypt_empty_values = creation_rule.encrypt_empty_values;
+                }
+
                 if self.partial_encryption_args.is_none() {
                     if let Some(partial_encryption_config) = creation_rule.partial_encryption {
                         self.partial_encryption_args = Some(partial_encryption_config.into());
@@ -48,6 +91,8 @@ impl MergeConfig for EncryptArgs {
                 break;
             }
         }
+
+        self.output_format = self.output_format.or(config.encrypt.output_format);
     }
 }
 
@@ -63,8 +108,15 @@ mod mock {
                 integration_keys: MockTestUtil::mock(),
                 partial_encryption_args: None,
                 mac_only_encrypted: None,
+                encrypt_empty_values: None,
+                shared_data_key: None,
+                cipher: None,
+                hasher: None,
                 input_args: MockTestUtil::mock(),
+                output_format: None,
                 in_place: None,
+                dry_run: None,
+                destination: None,
             }
         }
     }
@@ -107,6 +159,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn merges_destination_from_config() {
+        let mut encrypted_args = EncryptArgs::mock();
+        let mut config = Config::mock();
+        config.creation_rules[0].destination = Some("enc/{file_name}".to_string());
+        config.creation_rules[0].output_format = Some(Format::Yaml);
+        config.encrypt.output_format = Some(Format::Json);
+        encrypted_args.merge_config(config);
+        assert_eq!(Some(PathBuf::from("enc/rops_file.toml")), encrypted_args.destination);
+        assert_eq!(Some(Format::Yaml), encrypted_args.output_format);
+    }
+
     #[test]
     fn merges_mac_only_encrypted_from_config() {
         let mut encrypted_args = EncryptArgs::mock();
@@ -114,4 +178,12 @@ mod test {
         encrypted_args.merge_config(Config::mock_other());
         assert!(encrypted_args.mac_only_encrypted.is_some());
     }
+
+    #[test]
+    fn merges_encrypt_empty_values_from_config() {
+        let mut encrypted_args = EncryptArgs::mock();
+        assert!(encrypted_args.encrypt_empty_values.is_none());
+        encrypted_args.merge_config(Config::mock_other());
+        assert_eq!(Some(true), encrypted_args.encrypt_empty_values);
+    }
 }
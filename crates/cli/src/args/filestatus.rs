@@ -0,0 +1,29 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+
+use crate::*;
+
+#[derive(Args)]
+pub struct FileStatusArgs {
+    /// Required unless it can be inferred from the file arguments.
+    #[arg(long, short)]
+    pub format: Option<Format>,
+    /// Files to inspect, e.g. the staged files passed by a pre-commit hook.
+    #[arg(value_hint = ValueHint::FilePath, required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl ConfigArg for FileStatusArgs {
+    fn config_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl MergeConfig for FileStatusArgs {
+    fn merge_config(&mut self, _config: Config) {}
+}
@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand, ValueHint};
+
+use crate::*;
+
+#[derive(Subcommand)]
+pub enum GitSubcommand {
+    /// Git clean filter, encrypts plaintext from stdin. Values left unchanged since the committed revision keep their ciphertext
+    FilterClean(GitFilterArgs),
+    /// Git smudge filter, decrypts a rops file from stdin
+    FilterSmudge(GitFilterArgs),
+    /// Git diff textconv driver, prints the decrypted content of a rops file
+    Textconv(GitFilterArgs),
+    /// Git merge driver, three-way merges the decrypted key paths and overwrites the current version with the re-encrypted result
+    MergeDriver(GitMergeDriverArgs),
+    /// Register the rops filter, diff and merge driver in .git/config and apply them to path patterns in .gitattributes
+    Install(GitInstallArgs),
+}
+
+#[derive(Args)]
+pub struct GitFilterArgs {
+    /// Read config from provided path
+    #[arg(long, short, display_order = 0, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Required unless it can be inferred from the file argument.
+    #[arg(long, short, display_order = 20)]
+    pub format: Option<Format>,
+    /// Path of the file relative to the repository root. (Substituted by git with '%f'.)
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct GitMergeDriverArgs {
+    /// Required unless it can be inferred from the path argument.
+    #[arg(long, short, display_order = 20)]
+    pub format: Option<Format>,
+    /// Path of the file relative to the repository root. (Substituted by git with '%P'.)
+    #[arg(long, value_hint = ValueHint::FilePath, display_order = 21)]
+    pub path: Option<PathBuf>,
+    /// Common ancestor version. (Substituted by git with '%O'.)
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub base: PathBuf,
+    /// Current version, overwritten by the merge result. (Substituted by git with '%A'.)
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub ours: PathBuf,
+    /// Version being merged in. (Substituted by git with '%B'.)
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub theirs: PathBuf,
+}
+
+#[derive(Args)]
+pub struct GitInstallArgs {
+    /// Path patterns of rops files, '*.enc.yaml' for example
+    #[arg(required = true)]
+    pub patterns: Vec<String>,
+}
+
+impl ConfigArg for GitSubcommand {
+    fn config_path(&self) -> Option<&Path> {
+        match self {
+            GitSubcommand::FilterClean(filter_args) | GitSubcommand::FilterSmudge(filter_args) | GitSubcommand::Textconv(filter_args) => {
+                filter_args.config.as_deref()
+            }
+            GitSubcommand::MergeDriver(_) | GitSubcommand::Install(_) => None,
+        }
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        match self {
+            GitSubcommand::FilterClean(filter_args) | GitSubcommand::FilterSmudge(filter_args) | GitSubcommand::Textconv(filter_args) => {
+                Some(&filter_args.file)
+            }
+            GitSubcommand::MergeDriver(_) | GitSubcommand::Install(_) => None,
+        }
+    }
+}
+
+impl MergeConfig for GitSubcommand {
+    // Encryption settings are merged by `filter-clean` itself, once it hands over to `encrypt`.
+    fn merge_config(&mut self, config: Config) {
+        match self {
+            GitSubcommand::FilterClean(filter_args) => filter_args.merge_format(config.encrypt.format),
+            GitSubcommand::FilterSmudge(filter_args) | GitSubcommand::Textconv(filter_args) => {
+                filter_args.merge_format(config.decrypt.format)
+            }
+            GitSubcommand::MergeDriver(_) | GitSubcommand::Install(_) => {}
+        }
+    }
+}
+
+impl GitFilterArgs {
+    pub fn merge_format(&mut self, configured_format: Option<Format>) {
+        if Cli::get_format(Some(&self.file), self.format).is_err() {
+            self.format = configured_format;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rops::test_utils::*;
+
+    use super::*;
+
+    fn filter_args(file: &str) -> GitFilterArgs {
+        GitFilterArgs {
+            config: None,
+            format: None,
+            file: PathBuf::from(file),
+        }
+    }
+
+    fn merged_format(git_subcommand: fn(GitFilterArgs) -> GitSubcommand, file: &str) -> Option<Format> {
+        let mut config = Config::mock();
+        config.encrypt.format = Some(Format::Toml);
+        config.decrypt.format = Some(Format::Json);
+
+        let mut git_subcommand = git_subcommand(filter_args(file));
+        git_subcommand.merge_config(config);
+
+        match git_subcommand {
+            GitSubcommand::FilterClean(filter_args) | GitSubcommand::FilterSmudge(filter_args) | GitSubcommand::Textconv(filter_args) => {
+                filter_args.format
+            }
+            GitSubcommand::MergeDriver(_) | GitSubcommand::Install(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn merges_filter_formats_from_config() {
+        assert_eq!(Some(Format::Toml), merged_format(GitSubcommand::FilterClean, "secrets"));
+        assert_eq!(Some(Format::Json), merged_format(GitSubcommand::FilterSmudge, "secrets"));
+        assert_eq!(Some(Format::Json), merged_format(GitSubcommand::Textconv, "secrets"));
+    }
+
+    #[test]
+    fn prefers_inferred_filter_format() {
+        assert_eq!(None, merged_format(GitSubcommand::FilterSmudge, "secrets.yaml"));
+    }
+}
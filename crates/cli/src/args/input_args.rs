@@ -4,23 +4,42 @@ use clap::{Args, ValueHint};
 
 use crate::*;
 
-#[derive(Args)]
+#[derive(Clone, Args)]
 pub struct InputArgs {
     /// Read config from provided path
     #[arg(long, short, display_order = 0, value_name = "PATH")]
     pub config: Option<PathBuf>,
     /// Required if no file argument is found to infer by extension
-    #[arg(long, short, display_order = 20)]
+    #[arg(long, short, visible_alias = "input-format", display_order = 20)]
     pub format: Option<Format>,
-    /// Input may alternatively be supplied through stdin
+    /// Input may alternatively be supplied through stdin. Directories and globs are walked for files matched by the config creation rules.
     #[arg(value_hint = ValueHint::FilePath)]
     pub file: Option<PathBuf>,
+    /// Configured format of batch files for which none can be inferred by extension.
+    #[arg(skip)]
+    pub fallback_format: Option<Format>,
+}
+
+impl InputArgs {
+    /// Applies a configured input format if none can be inferred from the file argument. Directories
+    /// and globs instead fall back to it for each file, as their formats are inferred one by one.
+    pub fn merge_format(&mut self, configured_format: Option<Format>) {
+        if self.file.as_deref().is_some_and(Cli::is_batch_path) {
+            self.fallback_format = configured_format;
+        } else if Cli::get_format(self.file.as_deref(), self.format).is_err() {
+            self.format = configured_format;
+        }
+    }
 }
 
 impl ConfigArg for InputArgs {
     fn config_path(&self) -> Option<&Path> {
         self.config.as_deref()
     }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
 }
 
 #[cfg(feature = "test-utils")]
@@ -35,6 +54,7 @@ mod mock {
                 config: None,
                 format: None,
                 file: Some("rops_file.toml".into()),
+                fallback_format: None,
             }
         }
     }
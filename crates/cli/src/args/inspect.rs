@@ -0,0 +1,32 @@
+use std::path::{Path, PathBuf};
+
+use clap::{ArgAction, Args, ValueHint};
+
+use crate::*;
+
+#[derive(Args)]
+pub struct InspectArgs {
+    /// Print a JSON line per document rather than a table
+    #[arg(long, action(ArgAction::SetTrue), display_order = 10)]
+    pub json: Option<bool>,
+    /// Required unless it can be inferred from the file argument.
+    #[arg(long, short, display_order = 20)]
+    pub format: Option<Format>,
+    /// Path to an encrypted rops file, reads from stdin if omitted.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: Option<PathBuf>,
+}
+
+impl ConfigArg for InspectArgs {
+    fn config_path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl MergeConfig for InspectArgs {
+    fn merge_config(&mut self, _config: Config) {}
+}
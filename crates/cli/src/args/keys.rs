@@ -1,6 +1,6 @@
 use std::path::{Path, PathBuf};
 
-use clap::{Args, Subcommand, ValueHint};
+use clap::{ArgAction, Args, Subcommand, ValueHint};
 
 use crate::*;
 
@@ -10,6 +10,8 @@ pub enum KeysSubcommand {
     Add(KeyInputArgs),
     /// Remove integration key IDs of an encrypted rops file and rotate the data key.
     Remove(KeyInputArgs),
+    /// List the integration keys which may decrypt an encrypted rops file. No private key is needed.
+    List(InspectArgs),
 }
 
 #[derive(Args)]
@@ -19,7 +21,10 @@ pub struct KeyInputArgs {
     /// Required unless it can be inferred from the file argument.
     #[arg(long, short)]
     pub format: Option<Format>,
-    /// Path to an encrypted rops file.
+    /// List the files which would change, and why, without writing them.
+    #[arg(long, action(ArgAction::SetTrue))]
+    pub dry_run: Option<bool>,
+    /// Path to an encrypted rops file, or a directory or glob of them.
     #[arg(value_hint = ValueHint::FilePath)]
     pub file: PathBuf,
 }
@@ -28,10 +33,21 @@ impl ConfigArg for KeysSubcommand {
     fn config_path(&self) -> Option<&Path> {
         None
     }
+
+    fn input_path(&self) -> Option<&Path> {
+        match self {
+            KeysSubcommand::Add(key_args) | KeysSubcommand::Remove(key_args) => Some(&key_args.file),
+            KeysSubcommand::List(_) => None,
+        }
+    }
 }
 
 impl MergeConfig for KeysSubcommand {
-    fn merge_config(&mut self, _config: Config) {
-        // todo!()
+    fn merge_config(&mut self, config: Config) {
+        if let KeysSubcommand::Add(key_args) = self {
+            if key_args.integration_keys.is_empty() {
+                key_args.integration_keys = config.keys.add;
+            }
+        }
     }
 }
@@ -4,6 +4,9 @@ use crate::*;
 
 pub trait ConfigArg {
     fn config_path(&self) -> Option<&Path>;
+
+    /// File, directory or glob from which configs are searched for, the current directory if none.
+    fn input_path(&self) -> Option<&Path>;
 }
 
 pub trait MergeConfig {
@@ -1,8 +1,9 @@
 use clap::{Args, ValueEnum};
 use regex::Regex;
 use rops::file::metadata::PartialEncryptionConfig;
+use serde::Deserialize;
 
-#[derive(Default, Args)]
+#[derive(Clone, Default, Args)]
 #[group(id = "partial_encryption", multiple = false)]
 pub struct PartialEncryptionArgs {
     /// Encrypt values matching key suffix
@@ -50,10 +51,19 @@ impl From<PartialEncryptionConfig> for PartialEncryptionArgs {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+#[serde(rename_all = "lowercase")]
 pub enum Format {
     #[value(alias = "yml")]
+    #[serde(alias = "yml")]
     Yaml,
     Json,
     Toml,
 }
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_possible_value().expect("no skipped format variants").get_name())
+    }
+}
@@ -16,6 +16,21 @@ pub use keys::{KeyInputArgs, KeysSubcommand};
 mod refresh;
 pub use refresh::RefreshArgs;
 
+mod check;
+pub use check::CheckArgs;
+
+mod filestatus;
+pub use filestatus::FileStatusArgs;
+
+mod inspect;
+pub use inspect::InspectArgs;
+
+mod diff;
+pub use diff::DiffArgs;
+
+mod git;
+pub use git::{GitFilterArgs, GitInstallArgs, GitMergeDriverArgs, GitSubcommand};
+
 mod input_args;
 pub use input_args::InputArgs;
 
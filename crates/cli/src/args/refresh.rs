@@ -11,16 +11,26 @@ pub struct RefreshArgs {
     /// Refresh the file in place rather than printing the result to stdout.
     #[arg(long, short, requires = "file", action(ArgAction::SetTrue), display_order = 0)]
     pub in_place: Option<bool>,
+    /// List the files which would change, and why, without writing them.
+    #[arg(long, requires = "file", action(ArgAction::SetTrue), display_order = 1)]
+    pub dry_run: Option<bool>,
+    /// Creation rules are read from the merged config.
+    #[arg(skip)]
+    pub merged_config: Config,
 }
 
 impl ConfigArg for RefreshArgs {
     fn config_path(&self) -> Option<&Path> {
         self.input_args.config_path()
     }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_args.input_path()
+    }
 }
 
 impl MergeConfig for RefreshArgs {
-    fn merge_config(&mut self, _config: Config) {
-        // todo!()
+    fn merge_config(&mut self, config: Config) {
+        self.merged_config = config;
     }
 }
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use indexmap::IndexSet;
+use rayon::prelude::*;
+
+use crate::*;
+
+const GLOB_CHARACTERS: [char; 3] = ['*', '?', '['];
+
+pub(crate) enum BatchOutcome {
+    /// Output to be written in place of the file, or to its destination if set, along with why the
+    /// file changes.
+    Changed {
+        output: String,
+        reason: String,
+        destination: Option<PathBuf>,
+    },
+    Unchanged {
+        reason: String,
+    },
+}
+
+impl BatchOutcome {
+    pub fn unchanged(reason: impl Into<String>) -> Self {
+        Self::Unchanged { reason: reason.into() }
+    }
+}
+
+impl Cli {
+    /// Returns the file path if it is to be processed in batch. Directories and globs always are,
+    /// as are single files during a dry run.
+    pub fn batch_path(file_path: Option<&Path>, dry_run: Option<bool>) -> Option<&Path> {
+        file_path.filter(|file_path| dry_run.unwrap_or_default() || Self::is_batch_path(file_path))
+    }
+
+    pub fn is_batch_path(file_path: &Path) -> bool {
+        file_path.is_dir() || (!file_path.exists() && file_path.to_string_lossy().contains(GLOB_CHARACTERS))
+    }
+
+    /// Applies `file_fn` in parallel on each file of a directory or glob, and prints a report of the
+    /// outcomes. The fallback format is used for files of which the format can't be inferred.
+    pub fn run_batch(
+        input_path: &Path,
+        explicit_format: Option<Format>,
+        fallback_format: Option<Format>,
+        in_place: Option<bool>,
+        dry_run: Option<bool>,
+        file_fn: impl Fn(&Path, Format, &str) -> anyhow::Result<BatchOutcome> + Sync,
+    ) -> anyhow::Result<()> {
+        let dry_run = dry_run.unwrap_or_default();
+
+        if !dry_run && !in_place.unwrap_or_default() {
+            bail!("directory and glob inputs require either --in-place or --dry-run")
+        }
+
+        let file_paths = Self::collect_file_paths(input_path)?;
+
+        let outcomes = file_paths
+            .par_iter()
+            .map(|file_path| {
+                let format = Self::get_format(Some(file_path), explicit_format).or_else(|error| fallback_format.ok_or(error))?;
+                let input_string = std::fs::read_to_string(file_path)?;
+                let outcome = file_fn(file_path, format, &input_string)?;
+
+                if let (BatchOutcome::Changed { output, destination, .. }, false) = (&outcome, dry_run) {
+                    Self::write_destination(destination.as_deref().unwrap_or(file_path), output)?;
+                }
+
+                Ok(outcome)
+            })
+            .collect::<Vec<anyhow::Result<BatchOutcome>>>();
+
+        let (mut changed_count, mut unchanged_count, mut failed_count) = (0, 0, 0);
+
+        for (file_path, outcome) in file_paths.iter().zip(outcomes) {
+            let file_path = file_path.display();
+
+            match outcome {
+                Ok(BatchOutcome::Changed { reason, destination, .. }) => {
+                    changed_count += 1;
+                    let file_path = match destination {
+                        Some(destination) => format!("{file_path} -> {}", destination.display()),
+                        None => file_path.to_string(),
+                    };
+                    match dry_run {
+                        true => println!("would change {file_path}: {reason}"),
+                        false => println!("changed {file_path}: {reason}"),
+                    }
+                }
+                Ok(BatchOutcome::Unchanged { reason }) => {
+                    unchanged_count += 1;
+                    println!("skipped {file_path}: {reason}");
+                }
+                Err(error) => {
+                    failed_count += 1;
+                    println!("failed {file_path}: {error}");
+                }
+            }
+        }
+
+        match dry_run {
+            true => println!("{changed_count} would change, {unchanged_count} skipped, {failed_count} failed"),
+            false => println!("{changed_count} changed, {unchanged_count} skipped, {failed_count} failed"),
+        }
+
+        if failed_count > 0 {
+            bail!("unable to process {failed_count} file(s)")
+        }
+
+        Ok(())
+    }
+
+    /// Files of a path which may be a directory or a glob. Hidden files and directories are skipped
+    /// when walking, as are files with an unrecognized extension.
+    pub fn collect_file_paths(input_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut file_paths = IndexSet::new();
+
+        if input_path.exists() {
+            walk(input_path, &mut file_paths);
+        } else {
+            let match_options = glob::MatchOptions {
+                require_literal_leading_dot: true,
+                ..Default::default()
+            };
+
+            for glob_path in glob::glob_with(&input_path.to_string_lossy(), match_options)? {
+                walk(&glob_path?, &mut file_paths);
+            }
+        }
+
+        return Ok(file_paths.into_iter().collect());
+
+        fn walk(path: &Path, file_paths: &mut IndexSet<PathBuf>) {
+            let walker = walkdir::WalkDir::new(path)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'));
+
+            for entry in walker.filter_map(Result::ok) {
+                if entry.file_type().is_file() && (entry.depth() == 0 || Cli::get_format(Some(entry.path()), None).is_ok()) {
+                    // Keeps paths walked from the current directory matchable by creation rules such as '^secrets/'.
+                    let file_path = entry.path().strip_prefix(".").unwrap_or(entry.path()).to_path_buf();
+                    file_paths.insert(file_path);
+                }
+            }
+        }
+    }
+}
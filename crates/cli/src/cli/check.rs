@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use anyhow::bail;
+use indexmap::{IndexMap, IndexSet};
+use rayon::prelude::*;
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, state::*, AnyRopsFile, RopsFile, RopsFileConvertError, RopsFileVisitor},
+};
+use serde::Serialize;
+
+use crate::*;
+
+#[derive(Serialize)]
+struct CheckReport<'a> {
+    path: &'a Path,
+    status: CheckStatus,
+    issues: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Ok,
+    Drift,
+}
+
+impl Cli {
+    /// Prints a JSON report line for each checked file, private keys are never accessed.
+    pub fn check(check_args: CheckArgs) -> anyhow::Result<()> {
+        let config = &check_args.merged_config;
+
+        let mut file_paths = IndexSet::new();
+        for path in &check_args.paths {
+            file_paths.extend(Self::collect_file_paths(path)?);
+        }
+
+        // Plaintext sources of destinations are expected to remain unencrypted, their destinations
+        // are checked against the creation rule of the source instead.
+        let destinations = config.find_destinations(&file_paths)?;
+
+        let mut checked_files = IndexMap::new();
+        for file_path in &file_paths {
+            if destinations.contains_key(file_path) {
+                continue;
+            }
+
+            let Some(creation_rule) = config.find_creation_rule(Some(file_path)) else {
+                continue;
+            };
+
+            match creation_rule.destination_path(file_path) {
+                Some(destination) if creation_rule.has_other_destination(file_path) => {
+                    checked_files.insert(destination, creation_rule);
+                }
+                _ => {
+                    checked_files.insert(file_path.clone(), creation_rule);
+                }
+            }
+        }
+
+        let checked_files = checked_files.into_iter().collect::<Vec<_>>();
+
+        let reports = checked_files
+            .par_iter()
+            .map(|(file_path, creation_rule)| {
+                let issues = check_file(file_path, check_args.format, creation_rule);
+
+                CheckReport {
+                    path: file_path,
+                    status: match issues.is_empty() {
+                        true => CheckStatus::Ok,
+                        false => CheckStatus::Drift,
+                    },
+                    issues,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut drift_count = 0;
+        for report in &reports {
+            if !report.issues.is_empty() {
+                drift_count += 1;
+            }
+
+            println!("{}", serde_json::to_string(report)?);
+        }
+
+        if drift_count > 0 {
+            bail!("{drift_count} file(s) drifted from the config")
+        }
+
+        return Ok(());
+
+        fn check_file(file_path: &Path, explicit_format: Option<Format>, creation_rule: &CreationRule) -> Vec<String> {
+            let check_result = Cli::get_format(Some(file_path), explicit_format)
+                .map_err(anyhow::Error::from)
+                .and_then(|format| Ok((format, std::fs::read_to_string(file_path)?)));
+
+            match check_result {
+                Ok((Format::Yaml, input_string)) => check_impl::<YamlFileFormat>(&input_string, creation_rule),
+                Ok((Format::Json, input_string)) => check_impl::<JsonFileFormat>(&input_string, creation_rule),
+                Ok((Format::Toml, input_string)) => check_impl::<TomlEditFileFormat>(&input_string, creation_rule),
+                Err(error) => vec![format!("unable to read file; {error}")],
+            }
+        }
+
+        fn check_impl<F: FileFormat>(input_str: &str, creation_rule: &CreationRule) -> Vec<String> {
+            let documents = F::split_documents(input_str);
+            let mut issues = Vec::new();
+
+            for (index, document_str) in documents.iter().enumerate() {
+                let prefix = match documents.len() > 1 {
+                    true => format!("document {index}: "),
+                    false => String::new(),
+                };
+
+                let Ok(any_rops_file) = document_str.parse::<AnyRopsFile<F>>() else {
+                    issues.push(format!("{prefix}not an encrypted rops file"));
+                    continue;
+                };
+
+                issues.extend(any_rops_file.visit(CheckVisitor {
+                    prefix: &prefix,
+                    creation_rule,
+                }));
+            }
+
+            return issues;
+
+            struct CheckVisitor<'a> {
+                prefix: &'a str,
+                creation_rule: &'a CreationRule,
+            }
+
+            impl<F: FileFormat> RopsFileVisitor<F> for CheckVisitor<'_> {
+                type Output = Vec<String>;
+
+                fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    let CheckVisitor { prefix, creation_rule } = self;
+                    let mut issues = Vec::new();
+
+                    for discrepancy in creation_rule.metadata_discrepancies(rops_file.metadata()) {
+                        issues.push(format!(
+                            "{prefix}{discrepancy} differ from creation rule '{}'",
+                            creation_rule.path_regex
+                        ));
+                    }
+
+                    // Conversion validates the map against the partial encryption settings.
+                    match rops_file.convert::<F>() {
+                        Ok(_) => (),
+                        // Error messages are omitted as they include the leaked value.
+                        Err(RopsFileConvertError::FormatToIntenrnalMap(
+                            FormatToInternalMapError::PlaintextWhenEncrypted(_) | FormatToInternalMapError::EncryptedRopsValue(_),
+                        )) => issues.push(format!("{prefix}plaintext value found where encryption is required")),
+                        Err(error) => issues.push(format!("{prefix}invalid structure; {error}")),
+                    }
+
+                    issues
+                }
+            }
+        }
+    }
+}
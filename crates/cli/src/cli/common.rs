@@ -5,6 +5,7 @@ use std::{
 
 use anyhow::bail;
 use clap::ValueEnum;
+use rops::file::{format::*, metadata::PartialEncryptionConfig, AnyRopsFile};
 
 use crate::*;
 
@@ -54,6 +55,61 @@ impl Cli {
         }
     }
 
+    /// Joins the documents of an output, rejecting several of them for formats which can't hold more than one.
+    pub fn join_output_documents<Fo: FileFormat>(documents: Vec<String>) -> Result<String, RopsCliError> {
+        match documents.len() > 1 && !Fo::MULTIPLE_DOCUMENTS {
+            true => Err(RopsCliError::MultipleDocuments(documents.len())),
+            false => Ok(Fo::join_documents(documents)),
+        }
+    }
+
+    /// Files written in place keep their extension, and may therefore not change format.
+    pub fn validate_in_place_format(in_place: Option<bool>, format: Format, output_format: Format) -> Result<(), RopsCliError> {
+        match in_place.unwrap_or_default() && format != output_format {
+            true => Err(RopsCliError::InPlaceFormatConversion(format, output_format)),
+            false => Ok(()),
+        }
+    }
+
+    /// Applies `document_fn` on each document of the input, multi-document YAML for example,
+    /// and joins the results back together.
+    pub fn map_documents<F: FileFormat>(
+        input_str: &str,
+        document_fn: impl FnMut(&str) -> anyhow::Result<String>,
+    ) -> anyhow::Result<String> {
+        F::split_documents(input_str)
+            .iter()
+            .map(String::as_str)
+            .map(document_fn)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(F::join_documents)
+    }
+
+    /// Whether each document parses as an encrypted rops file, using the cipher named in its MAC.
+    pub fn is_encrypted(format: Format, input_str: &str) -> bool {
+        return match format {
+            Format::Yaml => is_encrypted_impl::<YamlFileFormat>(input_str),
+            Format::Json => is_encrypted_impl::<JsonFileFormat>(input_str),
+            Format::Toml => is_encrypted_impl::<TomlEditFileFormat>(input_str),
+        };
+
+        fn is_encrypted_impl<F: FileFormat>(input_str: &str) -> bool {
+            F::split_documents(input_str)
+                .iter()
+                .all(|document_str| document_str.parse::<AnyRopsFile<F>>().is_ok())
+        }
+    }
+
+    pub fn display_partial_encryption(partial_encryption: Option<&PartialEncryptionConfig>) -> String {
+        match partial_encryption {
+            Some(PartialEncryptionConfig::EncryptedSuffix(suffix)) => format!("encrypted_suffix={suffix}"),
+            Some(PartialEncryptionConfig::EncryptedRegex(regex)) => format!("encrypted_regex={}", regex.as_str()),
+            Some(PartialEncryptionConfig::UnencryptedSuffix(suffix)) => format!("unencrypted_suffix={suffix}"),
+            Some(PartialEncryptionConfig::UnencryptedRegex(regex)) => format!("unencrypted_regex={}", regex.as_str()),
+            None => "none".to_string(),
+        }
+    }
+
     pub fn write_or_print(in_place: Option<bool>, explicit_file_path: Option<&Path>, output_str: &str) -> std::io::Result<()> {
         match in_place.unwrap_or_default() {
             true => {
@@ -67,6 +123,15 @@ impl Cli {
 
         Ok(())
     }
+
+    /// Writes the output, creating any missing parent directories of creation rule destinations.
+    pub fn write_destination(destination_path: &Path, output_str: &str) -> std::io::Result<()> {
+        if let Some(parent_directory) = destination_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent_directory)?;
+        }
+
+        std::fs::write(destination_path, output_str)
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,9 @@
-use rops::file::{format::*, state::*, RopsFile};
+use std::marker::PhantomData;
+
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, state::*, AnyRopsFile, RopsFile, RopsFileVisitor},
+};
 
 use crate::*;
 
@@ -6,9 +11,36 @@ impl Cli {
     pub fn decrypt(decrypt_args: DecryptArgs) -> anyhow::Result<()> {
         let input_args = decrypt_args.input_args;
         let explicit_file_path = input_args.file.as_deref();
+
+        if let Some(input_path) = Self::batch_path(explicit_file_path, decrypt_args.dry_run) {
+            return Self::run_batch(
+                input_path,
+                input_args.format,
+                input_args.fallback_format,
+                decrypt_args.in_place,
+                decrypt_args.dry_run,
+                |_, file_format, encrypted_string| {
+                    if !Self::is_encrypted(file_format, encrypted_string) {
+                        return Ok(BatchOutcome::unchanged("not encrypted"));
+                    }
+
+                    let output_format = decrypt_args.output_format.unwrap_or(file_format);
+                    Self::validate_in_place_format(decrypt_args.in_place, file_format, output_format)?;
+
+                    Ok(BatchOutcome::Changed {
+                        output: Self::decrypt_rops_file(file_format, output_format, encrypted_string)?,
+                        reason: "encrypted".to_string(),
+                        destination: None,
+                    })
+                },
+            );
+        }
+
         let format = Self::get_format(explicit_file_path, input_args.format)?;
+        let output_format = decrypt_args.output_format.unwrap_or(format);
+        Self::validate_in_place_format(decrypt_args.in_place, format, output_format)?;
         let input_string = Self::get_input_string(explicit_file_path, decrypt_args.in_place)?;
-        let decrypted_rops_file_string = decrypt_rops_file(format, &input_string)?;
+        let decrypted_rops_file_string = Self::decrypt_rops_file(format, output_format, &input_string)?;
 
         match decrypt_args.in_place.unwrap_or_default() {
             true => {
@@ -19,19 +51,46 @@ impl Cli {
             }
         }
 
-        return Ok(());
+        Ok(())
+    }
+
+    pub(crate) fn decrypt_rops_file(format: Format, output_format: Format, encrypted_rops_file_str: &str) -> anyhow::Result<String> {
+        return match format {
+            Format::Yaml => with_output_format::<YamlFileFormat>(output_format, encrypted_rops_file_str),
+            Format::Json => with_output_format::<JsonFileFormat>(output_format, encrypted_rops_file_str),
+            Format::Toml => with_output_format::<TomlEditFileFormat>(output_format, encrypted_rops_file_str),
+        };
+
+        fn with_output_format<F: FileFormat>(output_format: Format, encrypted_rops_file_str: &str) -> anyhow::Result<String> {
+            match output_format {
+                Format::Yaml => decrypt_rops_file_impl::<F, YamlFileFormat>(encrypted_rops_file_str),
+                Format::Json => decrypt_rops_file_impl::<F, JsonFileFormat>(encrypted_rops_file_str),
+                Format::Toml => decrypt_rops_file_impl::<F, TomlEditFileFormat>(encrypted_rops_file_str),
+            }
+        }
+
+        /// Each document is decrypted with the cipher named in its MAC.
+        fn decrypt_rops_file_impl<F: FileFormat, Fo: FileFormat>(encrypted_rops_file_str: &str) -> anyhow::Result<String> {
+            let decrypted_documents = F::split_documents(encrypted_rops_file_str)
+                .iter()
+                .map(|encrypted_document_str| {
+                    encrypted_document_str
+                        .parse::<AnyRopsFile<F>>()?
+                        .visit(DecryptVisitor::<Fo>(PhantomData))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            Cli::join_output_documents::<Fo>(decrypted_documents).map_err(Into::into)
+        }
+
+        struct DecryptVisitor<Fo>(PhantomData<Fo>);
 
-        fn decrypt_rops_file(format: Format, encrypted_rops_file_str: &str) -> anyhow::Result<String> {
-            return match format {
-                Format::Yaml => decrypt_rops_file_impl::<YamlFileFormat>(encrypted_rops_file_str),
-                Format::Json => decrypt_rops_file_impl::<JsonFileFormat>(encrypted_rops_file_str),
-                Format::Toml => decrypt_rops_file_impl::<TomlFileFormat>(encrypted_rops_file_str),
-            };
+        impl<F: FileFormat, Fo: FileFormat> RopsFileVisitor<F> for DecryptVisitor<Fo> {
+            type Output = anyhow::Result<String>;
 
-            fn decrypt_rops_file_impl<F: FileFormat>(encrypted_rops_file_str: &str) -> anyhow::Result<String> {
-                encrypted_rops_file_str
-                    .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, F>>()?
-                    .decrypt::<F>()
+            fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                rops_file
+                    .decrypt::<Fo>()
                     .map(|decrypted_rops_file| decrypted_rops_file.map().to_string())
                     .map_err(Into::into)
             }
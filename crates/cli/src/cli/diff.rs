@@ -0,0 +1,138 @@
+use std::{marker::PhantomData, path::Path};
+
+use anyhow::bail;
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, map::KeyPathDiff, metadata::MetadataDiff, state::*, AnyRopsFile, RopsFile, RopsFileDiff, RopsFileVisitor},
+};
+
+use crate::*;
+
+const MASKED_VALUE: &str = "***";
+
+impl Cli {
+    pub fn diff(diff_args: DiffArgs) -> anyhow::Result<()> {
+        let old_format = Self::get_format(Some(&diff_args.old_file), diff_args.format)?;
+        let new_format = Self::get_format(Some(&diff_args.new_file), diff_args.format)?;
+
+        let document_diffs = match old_format {
+            Format::Yaml => with_new_format::<YamlFileFormat>(new_format, &diff_args.old_file, &diff_args.new_file),
+            Format::Json => with_new_format::<JsonFileFormat>(new_format, &diff_args.old_file, &diff_args.new_file),
+            Format::Toml => with_new_format::<TomlEditFileFormat>(new_format, &diff_args.old_file, &diff_args.new_file),
+        }?;
+
+        let show_values = diff_args.show_values.unwrap_or_default();
+        let multiple_documents = document_diffs.len() > 1;
+
+        for (index, document_diff) in document_diffs.iter().enumerate() {
+            if multiple_documents && !document_diff.is_empty() {
+                println!("document {index}:");
+            }
+
+            print_diff(document_diff, show_values);
+        }
+
+        return Ok(());
+
+        fn with_new_format<F: FileFormat>(new_format: Format, old_path: &Path, new_path: &Path) -> anyhow::Result<Vec<RopsFileDiff>> {
+            match new_format {
+                Format::Yaml => diff_impl::<F, YamlFileFormat>(old_path, new_path),
+                Format::Json => diff_impl::<F, JsonFileFormat>(old_path, new_path),
+                Format::Toml => diff_impl::<F, TomlEditFileFormat>(old_path, new_path),
+            }
+        }
+
+        fn diff_impl<F: FileFormat, Fo: FileFormat>(old_path: &Path, new_path: &Path) -> anyhow::Result<Vec<RopsFileDiff>> {
+            let old_documents = F::split_documents(&std::fs::read_to_string(old_path)?);
+            let new_documents = Fo::split_documents(&std::fs::read_to_string(new_path)?);
+
+            if old_documents.len() != new_documents.len() {
+                bail!(
+                    "unable to compare files with different document counts, found {} and {}",
+                    old_documents.len(),
+                    new_documents.len()
+                )
+            }
+
+            old_documents
+                .iter()
+                .zip(&new_documents)
+                .map(|(old_document_str, new_document_str)| {
+                    old_document_str.parse::<AnyRopsFile<F>>()?.visit(OldDocumentVisitor::<Fo> {
+                        new_document_str,
+                        new_format: PhantomData,
+                    })
+                })
+                .collect()
+        }
+
+        // Each side is decrypted with its own data key, cipher and hasher.
+        struct OldDocumentVisitor<'a, Fo> {
+            new_document_str: &'a str,
+            new_format: PhantomData<Fo>,
+        }
+
+        impl<F: FileFormat, Fo: FileFormat> RopsFileVisitor<F> for OldDocumentVisitor<'_, Fo> {
+            type Output = anyhow::Result<RopsFileDiff>;
+
+            fn visit<C: Cipher, H: Hasher>(self, old_rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                self.new_document_str.parse::<AnyRopsFile<Fo>>()?.visit(NewDocumentVisitor {
+                    old_rops_file: old_rops_file.decrypt::<F>()?,
+                })
+            }
+        }
+
+        struct NewDocumentVisitor<H: Hasher, F: FileFormat> {
+            old_rops_file: RopsFile<DecryptedFile<H>, F>,
+        }
+
+        impl<H: Hasher, F: FileFormat, Fo: FileFormat> RopsFileVisitor<Fo> for NewDocumentVisitor<H, F> {
+            type Output = anyhow::Result<RopsFileDiff>;
+
+            fn visit<Cn: Cipher, Hn: Hasher>(self, new_rops_file: RopsFile<EncryptedFile<Cn, Hn>, Fo>) -> Self::Output {
+                self.old_rops_file.diff(new_rops_file.decrypt::<Fo>()?).map_err(Into::into)
+            }
+        }
+
+        fn print_diff(rops_file_diff: &RopsFileDiff, show_values: bool) {
+            let mask = |value: &str| match show_values {
+                true => value.to_string(),
+                false => MASKED_VALUE.to_string(),
+            };
+
+            for key_path_diff in &rops_file_diff.map {
+                match key_path_diff {
+                    KeyPathDiff::Added { key_path, value } => println!("+ {key_path}: {}", mask(value)),
+                    KeyPathDiff::Removed { key_path, value } => println!("- {key_path}: {}", mask(value)),
+                    KeyPathDiff::Changed {
+                        key_path,
+                        old_value,
+                        new_value,
+                    } => println!("~ {key_path}: {} -> {}", mask(old_value), mask(new_value)),
+                }
+            }
+
+            for metadata_diff in &rops_file_diff.metadata {
+                match metadata_diff {
+                    MetadataDiff::IntegrationKeyAdded { integration, key_id } => println!("+ sops.{integration}: {key_id}"),
+                    MetadataDiff::IntegrationKeyRemoved { integration, key_id } => println!("- sops.{integration}: {key_id}"),
+                    MetadataDiff::PartialEncryption { old, new } => println!(
+                        "~ sops.partial_encryption: {} -> {}",
+                        Cli::display_partial_encryption(old.as_ref()),
+                        Cli::display_partial_encryption(new.as_ref())
+                    ),
+                    MetadataDiff::MacOnlyEncrypted { old, new } => println!(
+                        "~ sops.mac_only_encrypted: {} -> {}",
+                        old.unwrap_or_default(),
+                        new.unwrap_or_default()
+                    ),
+                    MetadataDiff::EncryptEmptyValues { old, new } => println!(
+                        "~ sops.encrypt_empty_values: {} -> {}",
+                        old.unwrap_or_default(),
+                        new.unwrap_or_default()
+                    ),
+                }
+            }
+        }
+    }
+}
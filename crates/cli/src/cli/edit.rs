@@ -1,20 +1,80 @@
-use std::{io::IsTerminal, path::Path, process::Command};
+use std::{io::IsTerminal, marker::PhantomData, path::Path, process::Command};
 
-use anyhow::{bail, Context};
-use rops::file::{format::*, map::state::*, state::*, RopsFile};
+use anyhow::{anyhow, bail, Context};
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, map::state::*, state::*, AnyRopsFile, RopsFile, RopsFileVisitor},
+};
 
 use crate::*;
 
 impl Cli {
-    pub fn edit(input_args: InputArgs) -> anyhow::Result<()> {
-        let explicit_file_path = input_args.file.as_deref();
+    pub fn edit(edit_args: EditArgs) -> anyhow::Result<()> {
+        let explicit_file_path = edit_args.input_args.file.as_deref();
+        let format = Self::get_format(explicit_file_path, edit_args.input_args.format)?;
+        let output_format = edit_args.output_format.unwrap_or(format);
+        let preserve_nonces = edit_args.preserve_nonces.unwrap_or(true);
+        // Written back to the file unless stdin is piped.
+        let in_place = explicit_file_path.is_some() && std::io::stdin().is_terminal();
+        Self::validate_in_place_format(Some(in_place), format, output_format)?;
 
-        return match Self::get_format(explicit_file_path, input_args.format)? {
-            Format::Yaml => edit_encrypted_file::<YamlFileFormat>(explicit_file_path),
-            Format::Json => edit_encrypted_file::<JsonFileFormat>(explicit_file_path),
-            Format::Toml => edit_encrypted_file::<TomlFileFormat>(explicit_file_path),
+        let input_string = Cli::get_input_string(explicit_file_path, None)?;
+
+        return match format {
+            Format::Yaml => with_output_format::<YamlFileFormat>(output_format, &input_string, explicit_file_path, preserve_nonces),
+            Format::Json => with_output_format::<JsonFileFormat>(output_format, &input_string, explicit_file_path, preserve_nonces),
+            Format::Toml => with_output_format::<TomlEditFileFormat>(output_format, &input_string, explicit_file_path, preserve_nonces),
         };
 
+        fn with_output_format<F: temp_file_format::TempFileFormat>(
+            output_format: Format,
+            input_str: &str,
+            explicit_file_path: Option<&Path>,
+            preserve_nonces: bool,
+        ) -> anyhow::Result<()> {
+            match output_format {
+                Format::Yaml => with_cipher::<F, YamlFileFormat>(input_str, explicit_file_path, preserve_nonces),
+                Format::Json => with_cipher::<F, JsonFileFormat>(input_str, explicit_file_path, preserve_nonces),
+                Format::Toml => with_cipher::<F, TomlEditFileFormat>(input_str, explicit_file_path, preserve_nonces),
+            }
+        }
+
+        /// The first document determines the cipher and hasher of the remaining ones.
+        fn with_cipher<F: temp_file_format::TempFileFormat, Fo: FileFormat>(
+            input_str: &str,
+            explicit_file_path: Option<&Path>,
+            preserve_nonces: bool,
+        ) -> anyhow::Result<()> {
+            let mut document_strs = F::split_documents(input_str);
+            let first_document_str = document_strs.remove(0);
+
+            return first_document_str.parse::<AnyRopsFile<F>>()?.visit(EditVisitor::<Fo> {
+                remaining_document_strs: &document_strs,
+                explicit_file_path,
+                preserve_nonces,
+                output_format: PhantomData,
+            });
+
+            struct EditVisitor<'a, Fo> {
+                remaining_document_strs: &'a [String],
+                explicit_file_path: Option<&'a Path>,
+                preserve_nonces: bool,
+                output_format: PhantomData<Fo>,
+            }
+
+            impl<F: temp_file_format::TempFileFormat, Fo: FileFormat> RopsFileVisitor<F> for EditVisitor<'_, Fo> {
+                type Output = anyhow::Result<()>;
+
+                fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    let encrypted_documents = std::iter::once(Ok(rops_file))
+                        .chain(self.remaining_document_strs.iter().map(|document_str| document_str.parse()))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    edit_encrypted_file::<C, H, F, Fo>(encrypted_documents, self.explicit_file_path, self.preserve_nonces)
+                }
+            }
+        }
+
         // Nested to avoid it being misused for regular files which might use aliases.
         // (E.g 'yml' over 'yaml'.)
         #[rustfmt::skip]
@@ -23,32 +83,64 @@ impl Cli {
             pub trait TempFileFormat: FileFormat { const TEMP_EXTENSION: &'static str; }
             impl TempFileFormat for YamlFileFormat { const TEMP_EXTENSION: &'static str = "yaml"; }
             impl TempFileFormat for JsonFileFormat { const TEMP_EXTENSION: &'static str = "json"; }
-            impl TempFileFormat for TomlFileFormat { const TEMP_EXTENSION: &'static str = "toml"; }
+            impl TempFileFormat for TomlEditFileFormat { const TEMP_EXTENSION: &'static str = "toml"; }
         }
 
-        fn edit_encrypted_file<F: temp_file_format::TempFileFormat>(explicit_file_path: Option<&Path>) -> anyhow::Result<()> {
-            let (decrypted_rops_file, saved_parameters) = Cli::get_input_string(explicit_file_path, None)?
-                .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, F>>()?
-                .decrypt_and_save_parameters::<F>()?;
+        /// The temporary file is edited in the input format, `Fo` only determines the format of the result.
+        /// Re-encrypted values keep the cipher of the input.
+        fn edit_encrypted_file<C: Cipher, H: Hasher, F: temp_file_format::TempFileFormat, Fo: FileFormat>(
+            encrypted_documents: Vec<RopsFile<EncryptedFile<C, H>, F>>,
+            explicit_file_path: Option<&Path>,
+            preserve_nonces: bool,
+        ) -> anyhow::Result<()> {
+            // Rejected before editing, as the document count is kept.
+            if encrypted_documents.len() > 1 && !Fo::MULTIPLE_DOCUMENTS {
+                bail!(RopsCliError::MultipleDocuments(encrypted_documents.len()))
+            }
+
+            // Multi-document files are edited in one go, each document keeping its own metadata.
+            let decrypted_documents = encrypted_documents
+                .into_iter()
+                .map(|encrypted_rops_file| encrypted_rops_file.decrypt_and_save_parameters::<F>().map_err(Into::into))
+                .collect::<anyhow::Result<Vec<_>>>()?;
 
             let temp_file = tempfile::Builder::new()
                 .suffix(&format!(".{}", F::TEMP_EXTENSION))
                 // Create locally to avoid file being picked up by temporary resource cleaners.
                 .tempfile_in("./")?;
 
-            std::fs::write(temp_file.path(), decrypted_rops_file.map().to_string())?;
+            let decrypted_maps_string = F::join_documents(
+                decrypted_documents
+                    .iter()
+                    .map(|(decrypted_rops_file, _)| decrypted_rops_file.map().to_string())
+                    .collect(),
+            );
+
+            std::fs::write(temp_file.path(), decrypted_maps_string)?;
 
-            let optional_decrypted_map = edit_temp_file::<F>(temp_file.path())?;
+            let optional_decrypted_maps = edit_temp_file::<F>(temp_file.path(), decrypted_documents.len())?;
             drop(temp_file);
 
-            let Some(decrypted_map) = optional_decrypted_map else {
+            let Some(decrypted_maps) = optional_decrypted_maps else {
                 return Ok(());
             };
 
-            let encrypted_rops_file_string = decrypted_rops_file
-                .set_map(decrypted_map)?
-                .encrypt_with_saved_parameters::<_, F>(saved_parameters)?
-                .to_string();
+            let encrypted_rops_file_string = Fo::join_documents(
+                decrypted_documents
+                    .into_iter()
+                    .zip(decrypted_maps)
+                    .map(|((decrypted_rops_file, saved_parameters), decrypted_map)| {
+                        let decrypted_rops_file = decrypted_rops_file.set_map(decrypted_map)?;
+
+                        let encrypted_rops_file = match preserve_nonces {
+                            true => decrypted_rops_file.encrypt_with_saved_parameters::<_, Fo>(saved_parameters)?,
+                            false => decrypted_rops_file.encrypt::<C, Fo>()?,
+                        };
+
+                        Ok(encrypted_rops_file.to_string())
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+            );
 
             match std::io::stdin().lock().is_terminal() {
                 true => std::fs::write(
@@ -81,7 +173,10 @@ impl Cli {
             }
 
             /// Returns Ok(None) if operation was cancelled.
-            fn edit_temp_file<F: FileFormat>(temp_file_path: &Path) -> anyhow::Result<Option<RopsFileFormatMap<DecryptedMap, F>>> {
+            fn edit_temp_file<F: FileFormat>(
+                temp_file_path: &Path,
+                document_count: usize,
+            ) -> anyhow::Result<Option<Vec<RopsFileFormatMap<DecryptedMap, F>>>> {
                 let (editor_command, args) = select_editor()?;
                 let mut command = Command::new(editor_command);
                 command.args(args);
@@ -103,8 +198,8 @@ impl Cli {
 
                     let temp_file_string = std::fs::read_to_string(temp_file_path)?;
 
-                    match temp_file_string.parse() {
-                        Ok(decrypted_map) => break Ok(Some(decrypted_map)),
+                    match parse_documents(&temp_file_string, document_count) {
+                        Ok(decrypted_maps) => break Ok(Some(decrypted_maps)),
                         Err(err) => {
                             eprintln!("Unable to parse map: {err}");
                             eprintln!("Send SIGINT (usually Ctrl+C) to quit or any key to retry.");
@@ -121,6 +216,25 @@ impl Cli {
                     }
                 }
             }
+
+            fn parse_documents<F: FileFormat>(
+                temp_file_string: &str,
+                document_count: usize,
+            ) -> anyhow::Result<Vec<RopsFileFormatMap<DecryptedMap, F>>> {
+                let decrypted_maps = F::split_documents(temp_file_string)
+                    .iter()
+                    .map(|document_str| document_str.parse().map_err(Into::into))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                match decrypted_maps.len() == document_count {
+                    true => Ok(decrypted_maps),
+                    false => Err(anyhow!(
+                        "expected {} documents, found {}, adding or removing documents is not supported",
+                        document_count,
+                        decrypted_maps.len()
+                    )),
+                }
+            }
         }
     }
 }
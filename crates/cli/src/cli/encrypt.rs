@@ -1,4 +1,10 @@
-use rops::file::{builder::RopsFileBuilder, format::*};
+use rops::{
+    cryptography::{
+        cipher::{Cipher, AES256GCM, XCHACHA20POLY1305},
+        hasher::{Hasher, BLAKE3, SHA256, SHA512},
+    },
+    file::{builder::RopsFileBuilder, format::*},
+};
 
 use crate::*;
 
@@ -7,37 +13,140 @@ impl Cli {
         let explicit_file_path = encrypt_args.input_args.file.clone();
         let in_place = encrypt_args.in_place;
 
+        if let Some(input_path) = Self::batch_path(explicit_file_path.as_deref(), encrypt_args.dry_run) {
+            let config = Config::retrieve(encrypt_args.config_path(), encrypt_args.input_path())?;
+            // Rejects sources sharing a destination before any of them is written.
+            config.find_destinations(&Self::collect_file_paths(input_path)?)?;
+
+            return Self::run_batch(
+                input_path,
+                encrypt_args.input_args.format,
+                encrypt_args.input_args.fallback_format,
+                in_place,
+                encrypt_args.dry_run,
+                |file_path, file_format, plaintext_string| {
+                    let Some(creation_rule) = config.find_creation_rule(Some(file_path)) else {
+                        return Ok(BatchOutcome::unchanged("no matching creation rule"));
+                    };
+
+                    if Self::is_encrypted(file_format, plaintext_string) {
+                        return Ok(BatchOutcome::unchanged("already encrypted"));
+                    }
+
+                    let mut file_encrypt_args = encrypt_args.clone();
+                    file_encrypt_args.input_args.file = Some(file_path.to_path_buf());
+                    file_encrypt_args.merge_config(config.clone());
+
+                    let output_format = file_encrypt_args.output_format.unwrap_or(file_format);
+                    let destination = file_encrypt_args.destination.clone();
+                    if destination.is_none() {
+                        Self::validate_in_place_format(in_place, file_format, output_format)?;
+                    }
+
+                    Ok(BatchOutcome::Changed {
+                        output: Self::encrypt_rops_file(file_format, output_format, plaintext_string, file_encrypt_args)?,
+                        reason: format!("plaintext matched by creation rule '{}'", creation_rule.path_regex),
+                        destination,
+                    })
+                },
+            );
+        }
+
         let file_format = Self::get_format(explicit_file_path.as_deref(), encrypt_args.input_args.format)?;
+        let output_format = encrypt_args.output_format.unwrap_or(file_format);
         let plaintext_string = Self::get_input_string(explicit_file_path.as_deref(), in_place)?;
-        let encrypted_rops_file_string = encrypt_rops_file(file_format, &plaintext_string, encrypt_args)?;
+        let destination = encrypt_args.destination.clone().filter(|_| !in_place.unwrap_or_default());
+        Self::validate_in_place_format(in_place, file_format, output_format)?;
+        let encrypted_rops_file_string = Self::encrypt_rops_file(file_format, output_format, &plaintext_string, encrypt_args)?;
 
-        return Cli::write_or_print(in_place, explicit_file_path.as_deref(), &encrypted_rops_file_string).map_err(Into::into);
+        if let Some(destination) = destination {
+            return Cli::write_destination(&destination, &encrypted_rops_file_string).map_err(Into::into);
+        }
 
-        fn encrypt_rops_file(file_format: Format, plaintext_string: &str, encrypt_args: EncryptArgs) -> anyhow::Result<String> {
-            return match file_format {
-                Format::Yaml => encrypt_rops_file_impl::<YamlFileFormat>(plaintext_string, encrypt_args),
-                Format::Json => encrypt_rops_file_impl::<JsonFileFormat>(plaintext_string, encrypt_args),
-                Format::Toml => encrypt_rops_file_impl::<TomlFileFormat>(plaintext_string, encrypt_args),
-            };
+        Cli::write_or_print(in_place, explicit_file_path.as_deref(), &encrypted_rops_file_string).map_err(Into::into)
+    }
 
-            fn encrypt_rops_file_impl<F: FileFormat>(plaintext_str: &str, encrypt_args: EncryptArgs) -> anyhow::Result<String> {
-                let mut rops_file_builder = encrypt_args
-                    .integration_keys
-                    .add_to_builder(RopsFileBuilder::<F>::new(plaintext_str)?);
+    pub(crate) fn encrypt_rops_file(
+        file_format: Format,
+        output_format: Format,
+        plaintext_string: &str,
+        encrypt_args: EncryptArgs,
+    ) -> anyhow::Result<String> {
+        return match file_format {
+            Format::Yaml => with_output_format::<YamlFileFormat>(output_format, plaintext_string, encrypt_args),
+            Format::Json => with_output_format::<JsonFileFormat>(output_format, plaintext_string, encrypt_args),
+            Format::Toml => with_output_format::<TomlEditFileFormat>(output_format, plaintext_string, encrypt_args),
+        };
 
-                if let Some(partial_encryption_args) = encrypt_args.partial_encryption_args {
-                    rops_file_builder = rops_file_builder.with_partial_encryption(partial_encryption_args.into())
-                }
+        fn with_output_format<F: FileFormat>(
+            output_format: Format,
+            plaintext_str: &str,
+            encrypt_args: EncryptArgs,
+        ) -> anyhow::Result<String> {
+            match output_format {
+                Format::Yaml => with_cipher::<F, YamlFileFormat>(plaintext_str, encrypt_args),
+                Format::Json => with_cipher::<F, JsonFileFormat>(plaintext_str, encrypt_args),
+                Format::Toml => with_cipher::<F, TomlEditFileFormat>(plaintext_str, encrypt_args),
+            }
+        }
 
-                if encrypt_args.mac_only_encrypted.unwrap_or_default() {
-                    rops_file_builder = rops_file_builder.mac_only_encrypted()
-                }
+        fn with_cipher<F: FileFormat, Fo: FileFormat>(plaintext_str: &str, encrypt_args: EncryptArgs) -> anyhow::Result<String> {
+            match encrypt_args.cipher.unwrap_or_default() {
+                CipherVariant::Aes256Gcm => with_hasher::<AES256GCM, F, Fo>(plaintext_str, encrypt_args),
+                CipherVariant::XChaCha20Poly1305 => with_hasher::<XCHACHA20POLY1305, F, Fo>(plaintext_str, encrypt_args),
+            }
+        }
 
-                rops_file_builder
-                    .encrypt::<DefaultCipher, DefaultHasher>()
-                    .map(|rops_file| rops_file.to_string())
-                    .map_err(Into::into)
+        fn with_hasher<C: Cipher, F: FileFormat, Fo: FileFormat>(plaintext_str: &str, encrypt_args: EncryptArgs) -> anyhow::Result<String> {
+            match encrypt_args.hasher.unwrap_or_default() {
+                HasherVariant::Sha512 => encrypt_rops_file_impl::<C, SHA512, F, Fo>(plaintext_str, encrypt_args),
+                HasherVariant::Sha256 => encrypt_rops_file_impl::<C, SHA256, F, Fo>(plaintext_str, encrypt_args),
+                HasherVariant::Blake3 => encrypt_rops_file_impl::<C, BLAKE3, F, Fo>(plaintext_str, encrypt_args),
             }
         }
+
+        fn encrypt_rops_file_impl<C: Cipher, H: Hasher, F: FileFormat, Fo: FileFormat>(
+            plaintext_str: &str,
+            encrypt_args: EncryptArgs,
+        ) -> anyhow::Result<String> {
+            let rops_file_builders = F::split_documents(plaintext_str)
+                .iter()
+                .map(|plaintext_document_str| {
+                    let mut rops_file_builder = encrypt_args
+                        .integration_keys
+                        .clone()
+                        .add_to_builder(RopsFileBuilder::<F>::new(plaintext_document_str)?);
+
+                    if let Some(partial_encryption_args) = encrypt_args.partial_encryption_args.clone() {
+                        rops_file_builder = rops_file_builder.with_partial_encryption(partial_encryption_args.into())
+                    }
+
+                    if encrypt_args.mac_only_encrypted.unwrap_or_default() {
+                        rops_file_builder = rops_file_builder.mac_only_encrypted()
+                    }
+
+                    if encrypt_args.encrypt_empty_values.unwrap_or_default() {
+                        rops_file_builder = rops_file_builder.encrypt_empty_values()
+                    }
+
+                    Ok(rops_file_builder)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let rops_files = match encrypt_args.shared_data_key.unwrap_or_default() {
+                true => RopsFileBuilder::encrypt_with_shared_data_key::<C, H>(rops_file_builders)?,
+                false => rops_file_builders
+                    .into_iter()
+                    .map(RopsFileBuilder::encrypt::<C, H>)
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+
+            let converted_documents = rops_files
+                .into_iter()
+                .map(|rops_file| rops_file.convert::<Fo>().map(|converted_rops_file| converted_rops_file.to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Cli::join_output_documents::<Fo>(converted_documents).map_err(Into::into)
+        }
     }
 }
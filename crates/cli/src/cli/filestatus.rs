@@ -0,0 +1,33 @@
+use anyhow::bail;
+use rops::file::{format::*, RopsFileStatus};
+
+use crate::*;
+
+impl Cli {
+    pub fn filestatus(filestatus_args: FileStatusArgs) -> anyhow::Result<()> {
+        let mut unencrypted_count = 0;
+
+        for file_path in &filestatus_args.files {
+            let format = Self::get_format(Some(file_path), filestatus_args.format)?;
+            let input_string = std::fs::read_to_string(file_path)?;
+
+            let file_status = match format {
+                Format::Yaml => RopsFileStatus::detect_any::<YamlFileFormat>(&input_string),
+                Format::Json => RopsFileStatus::detect_any::<JsonFileFormat>(&input_string),
+                Format::Toml => RopsFileStatus::detect_any::<TomlEditFileFormat>(&input_string),
+            };
+
+            if file_status != RopsFileStatus::Encrypted {
+                unencrypted_count += 1;
+            }
+
+            println!("{file_status}: {}", file_path.display());
+        }
+
+        if unencrypted_count > 0 {
+            bail!("{unencrypted_count} file(s) not fully encrypted")
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{bail, Context};
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, map::KeyPathConflict, state::*, AnyRopsFile, RopsFile, RopsFileVisitor},
+};
+
+use crate::*;
+
+const GIT_CONFIG_ENTRIES: [(&str, &str); 6] = [
+    ("filter.rops.clean", "rops git filter-clean %f"),
+    ("filter.rops.smudge", "rops git filter-smudge %f"),
+    ("filter.rops.required", "true"),
+    ("diff.rops.textconv", "rops git textconv"),
+    ("merge.rops.name", "rops key path merge"),
+    ("merge.rops.driver", "rops git merge-driver --path %P %O %A %B"),
+];
+
+const GIT_ATTRIBUTES: &str = "filter=rops diff=rops merge=rops";
+
+impl Cli {
+    pub fn git(git_command: GitSubcommand) -> anyhow::Result<()> {
+        match git_command {
+            GitSubcommand::FilterClean(filter_args) => Self::git_filter_clean(filter_args),
+            GitSubcommand::FilterSmudge(filter_args) => Self::git_filter_smudge(filter_args),
+            GitSubcommand::Textconv(filter_args) => Self::git_textconv(filter_args),
+            GitSubcommand::MergeDriver(merge_args) => Self::git_merge_driver(merge_args),
+            GitSubcommand::Install(install_args) => Self::git_install(install_args),
+        }
+    }
+
+    fn git_filter_clean(filter_args: GitFilterArgs) -> anyhow::Result<()> {
+        let format = Self::get_format(Some(&filter_args.file), filter_args.format)?;
+        let plaintext_string = read_stdin()?;
+        let committed_string = committed_blob(&filter_args.file)?;
+
+        let encrypted_string = match format {
+            Format::Yaml => filter_clean_impl::<YamlFileFormat>(format, plaintext_string, committed_string, filter_args),
+            Format::Json => filter_clean_impl::<JsonFileFormat>(format, plaintext_string, committed_string, filter_args),
+            Format::Toml => filter_clean_impl::<TomlEditFileFormat>(format, plaintext_string, committed_string, filter_args),
+        }?;
+
+        return write_stdout(&encrypted_string);
+
+        fn filter_clean_impl<F: FileFormat>(
+            format: Format,
+            plaintext_string: String,
+            committed_string: Option<String>,
+            filter_args: GitFilterArgs,
+        ) -> anyhow::Result<String> {
+            // Smudging without access to the data key leaves the working tree file encrypted.
+            if Cli::is_encrypted(format, &plaintext_string) {
+                return Ok(plaintext_string);
+            }
+
+            let encrypt_args = || -> anyhow::Result<EncryptArgs> {
+                let mut encrypt_args = EncryptArgs {
+                    integration_keys: IntegrationKeys::default(),
+                    partial_encryption_args: None,
+                    mac_only_encrypted: None,
+                    encrypt_empty_values: None,
+                    shared_data_key: None,
+                    cipher: None,
+                    hasher: None,
+                    input_args: InputArgs {
+                        config: filter_args.config.clone(),
+                        format: Some(format),
+                        file: Some(filter_args.file.clone()),
+                        fallback_format: None,
+                    },
+                    output_format: None,
+                    in_place: None,
+                    dry_run: None,
+                    destination: None,
+                };
+                encrypt_args.merge_config(Config::retrieve(encrypt_args.config_path(), encrypt_args.input_path())?);
+                Ok(encrypt_args)
+            };
+
+            if let Some(committed_string) = committed_string {
+                let committed_documents = F::split_documents(&committed_string);
+                let plaintext_documents = F::split_documents(&plaintext_string);
+
+                // Saved parameters make unchanged values re-encrypt to identical ciphertexts. Committed
+                // documents which can't be decrypted, e.g. plaintext committed before the filter was
+                // installed, have none and are encrypted anew.
+                if committed_documents.len() == plaintext_documents.len() {
+                    let encrypted_documents = committed_documents
+                        .iter()
+                        .zip(&plaintext_documents)
+                        .map(|(committed_document_str, plaintext_document_str)| {
+                            let reencrypted_document = match committed_document_str.parse::<AnyRopsFile<F>>() {
+                                Ok(committed_rops_file) => committed_rops_file.visit(ReencryptVisitor { plaintext_document_str })?,
+                                Err(_) => None,
+                            };
+
+                            match reencrypted_document {
+                                Some(reencrypted_document) => Ok(reencrypted_document),
+                                None => Cli::encrypt_rops_file(format, format, plaintext_document_str, encrypt_args()?),
+                            }
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()?;
+
+                    return Ok(F::join_documents(encrypted_documents));
+                }
+            }
+
+            return Cli::encrypt_rops_file(format, format, &plaintext_string, encrypt_args()?);
+
+            struct ReencryptVisitor<'a> {
+                plaintext_document_str: &'a str,
+            }
+
+            impl<F: FileFormat> RopsFileVisitor<F> for ReencryptVisitor<'_> {
+                // `None` if the committed document can't be decrypted.
+                type Output = anyhow::Result<Option<String>>;
+
+                fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    let Ok((decrypted_rops_file, saved_parameters)) = rops_file.decrypt_and_save_parameters::<F>() else {
+                        return Ok(None);
+                    };
+
+                    Ok(Some(
+                        decrypted_rops_file
+                            .set_map(self.plaintext_document_str.parse()?)?
+                            .encrypt_with_saved_parameters::<_, F>(saved_parameters)?
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn git_filter_smudge(filter_args: GitFilterArgs) -> anyhow::Result<()> {
+        let format = Self::get_format(Some(&filter_args.file), filter_args.format)?;
+        write_stdout(&decrypt_or_pass_through(format, read_stdin()?))
+    }
+
+    fn git_textconv(filter_args: GitFilterArgs) -> anyhow::Result<()> {
+        let format = Self::get_format(Some(&filter_args.file), filter_args.format)?;
+        write_stdout(&decrypt_or_pass_through(format, std::fs::read_to_string(&filter_args.file)?))
+    }
+
+    fn git_merge_driver(merge_args: GitMergeDriverArgs) -> anyhow::Result<()> {
+        let format = Self::get_format(Some(merge_args.path.as_deref().unwrap_or(&merge_args.ours)), merge_args.format)?;
+
+        let (merged_string, document_conflicts) = match format {
+            Format::Yaml => merge_driver_impl::<YamlFileFormat>(&merge_args),
+            Format::Json => merge_driver_impl::<JsonFileFormat>(&merge_args),
+            Format::Toml => merge_driver_impl::<TomlEditFileFormat>(&merge_args),
+        }?;
+
+        // Conflicting key paths keep the current value, non-conflicting changes are therefore
+        // kept in the merge result regardless.
+        std::fs::write(&merge_args.ours, merged_string)?;
+
+        let conflict_count = document_conflicts.iter().map(Vec::len).sum::<usize>();
+        if conflict_count == 0 {
+            return Ok(());
+        }
+
+        let conflicts_path = conflicts_path(&merge_args)?;
+        write_conflicts(&conflicts_path, display_conflicts(&document_conflicts).as_bytes())?;
+
+        bail!(
+            "{conflict_count} conflicting key path(s), decrypted view with conflict markers written to {}",
+            conflicts_path.display()
+        );
+
+        fn merge_driver_impl<F: FileFormat>(merge_args: &GitMergeDriverArgs) -> anyhow::Result<(String, Vec<Vec<KeyPathConflict>>)> {
+            // Git passes an empty common ancestor when there is none, such as when both sides add the
+            // same file. Each document is then merged two-way, against an empty base map.
+            let base_string = match std::fs::read_to_string(&merge_args.base) {
+                Ok(base_string) if !base_string.trim().is_empty() => Some(base_string),
+                Ok(_) => None,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+                Err(error) => return Err(error.into()),
+            };
+            let ours_string = std::fs::read_to_string(&merge_args.ours)?;
+            let theirs_string = std::fs::read_to_string(&merge_args.theirs)?;
+
+            let ours_documents = F::split_documents(&ours_string);
+            let theirs_documents = F::split_documents(&theirs_string);
+            let base_documents = match &base_string {
+                Some(base_string) => F::split_documents(base_string).into_iter().map(Some).collect(),
+                None => vec![None; ours_documents.len()],
+            };
+
+            if base_documents.len() != ours_documents.len() || ours_documents.len() != theirs_documents.len() {
+                bail!("unable to merge versions with different document counts")
+            }
+
+            let mut merged_documents = Vec::with_capacity(ours_documents.len());
+            let mut document_conflicts = Vec::with_capacity(ours_documents.len());
+
+            for ((base_document_str, ours_document_str), theirs_document_str) in
+                base_documents.iter().zip(&ours_documents).zip(&theirs_documents)
+            {
+                let (merged_document, conflicts) = ours_document_str.parse::<AnyRopsFile<F>>()?.visit(MergeVisitor {
+                    base_document_str: base_document_str.as_deref(),
+                    ours_document_str,
+                    theirs_document_str,
+                })?;
+
+                merged_documents.push(merged_document);
+                document_conflicts.push(conflicts);
+            }
+
+            return Ok((F::join_documents(merged_documents), document_conflicts));
+
+            /// Base and theirs are expected to share the cipher and hasher of ours, which the merge result
+            /// is re-encrypted with.
+            struct MergeVisitor<'a> {
+                base_document_str: Option<&'a str>,
+                ours_document_str: &'a str,
+                theirs_document_str: &'a str,
+            }
+
+            impl<F: FileFormat> RopsFileVisitor<F> for MergeVisitor<'_> {
+                type Output = anyhow::Result<(String, Vec<KeyPathConflict>)>;
+
+                fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    let decrypt = |document_str: &str| -> anyhow::Result<RopsFile<DecryptedFile<H>, F>> {
+                        Ok(document_str.parse::<RopsFile<EncryptedFile<C, H>, F>>()?.decrypt::<F>()?)
+                    };
+
+                    let decrypted_rops_file = rops_file.decrypt::<F>()?;
+
+                    let base_rops_file = match self.base_document_str {
+                        Some(base_document_str) => decrypt(base_document_str)?,
+                        None => decrypt(self.ours_document_str)?.set_map(RopsFileFormatMap::empty())?,
+                    };
+
+                    let (merged_rops_file, conflicts) = decrypted_rops_file.merge(base_rops_file, decrypt(self.theirs_document_str)?)?;
+
+                    Ok((merged_rops_file.encrypt::<C, F>()?.to_string(), conflicts))
+                }
+            }
+        }
+
+        /// Kept out of the worktree, where it could end up being committed along with its plaintext
+        /// secrets. Written to `$GIT_DIR/rops/conflicts/<path>.conflicts`, or to a new temporary
+        /// directory when not run within a repository.
+        fn conflicts_path(merge_args: &GitMergeDriverArgs) -> anyhow::Result<PathBuf> {
+            let conflicts_dir = match run_git(&["rev-parse", "--absolute-git-dir"]) {
+                Ok(git_dir) => Path::new(git_dir.trim()).join("rops").join("conflicts"),
+                Err(_) => tempfile::Builder::new().prefix("rops-conflicts-").tempdir()?.keep(),
+            };
+
+            let relative_path = match &merge_args.path {
+                Some(path) if path.is_relative() => path.as_path(),
+                _ => Path::new(merge_args.ours.file_name().context("merged file path without file name")?),
+            };
+
+            let mut conflicts_path = conflicts_dir.join(relative_path).into_os_string();
+            conflicts_path.push(".conflicts");
+            Ok(conflicts_path.into())
+        }
+
+        fn write_conflicts(conflicts_path: &Path, conflicts: &[u8]) -> std::io::Result<()> {
+            if let Some(conflicts_dir) = conflicts_path.parent() {
+                let mut dir_builder = std::fs::DirBuilder::new();
+                dir_builder.recursive(true);
+
+                #[cfg(unix)]
+                std::os::unix::fs::DirBuilderExt::mode(&mut dir_builder, 0o700);
+
+                dir_builder.create(conflicts_dir)?;
+            }
+
+            let mut open_options = std::fs::OpenOptions::new();
+            open_options.write(true).create(true).truncate(true);
+
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut open_options, 0o600);
+
+            open_options.open(conflicts_path)?.write_all(conflicts)
+        }
+
+        fn display_conflicts(document_conflicts: &[Vec<KeyPathConflict>]) -> String {
+            let mut conflicts_string = String::new();
+
+            for (index, conflicts) in document_conflicts.iter().enumerate() {
+                if document_conflicts.len() > 1 && !conflicts.is_empty() {
+                    conflicts_string.push_str(&format!("# document {index}\n"));
+                }
+
+                for conflict in conflicts {
+                    let display_side = |value: &Option<String>| match value {
+                        Some(value) => format!("{}: {value}\n", conflict.key_path),
+                        None => String::new(),
+                    };
+
+                    conflicts_string.push_str("<<<<<<< ours\n");
+                    conflicts_string.push_str(&display_side(&conflict.ours));
+                    conflicts_string.push_str("||||||| base\n");
+                    conflicts_string.push_str(&display_side(&conflict.base));
+                    conflicts_string.push_str("=======\n");
+                    conflicts_string.push_str(&display_side(&conflict.theirs));
+                    conflicts_string.push_str(">>>>>>> theirs\n");
+                }
+            }
+
+            conflicts_string
+        }
+    }
+
+    fn git_install(install_args: GitInstallArgs) -> anyhow::Result<()> {
+        for (key, value) in GIT_CONFIG_ENTRIES {
+            run_git(&["config", key, value])?;
+        }
+
+        let top_level = run_git(&["rev-parse", "--show-toplevel"])?;
+        let attributes_path = Path::new(top_level.trim()).join(".gitattributes");
+
+        let mut attributes = match std::fs::read_to_string(&attributes_path) {
+            Ok(attributes) => attributes,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        for pattern in install_args.patterns {
+            let attributes_line = format!("{pattern} {GIT_ATTRIBUTES}");
+
+            if attributes.lines().any(|line| line.trim() == attributes_line) {
+                continue;
+            }
+
+            if !attributes.is_empty() && !attributes.ends_with('\n') {
+                attributes.push('\n');
+            }
+
+            attributes.push_str(&attributes_line);
+            attributes.push('\n');
+        }
+
+        std::fs::write(attributes_path, attributes).map_err(Into::into)
+    }
+}
+
+/// Failing to decrypt should not prevent git from checking out or diffing the file.
+fn decrypt_or_pass_through(format: Format, input_string: String) -> String {
+    if !Cli::is_encrypted(format, &input_string) {
+        return input_string;
+    }
+
+    match Cli::decrypt_rops_file(format, format, &input_string) {
+        Ok(decrypted_string) => decrypted_string,
+        Err(error) => {
+            eprintln!("rops: unable to decrypt, leaving file encrypted; {error}");
+            input_string
+        }
+    }
+}
+
+/// Returns `None` if the file has not been committed.
+fn committed_blob(file_path: &Path) -> anyhow::Result<Option<String>> {
+    let output = Command::new("git")
+        .args(["cat-file", "blob"])
+        .arg(format!("HEAD:{}", file_path.to_string_lossy()))
+        .output()
+        .context("unable to run git")?;
+
+    match output.status.success() {
+        true => Ok(Some(String::from_utf8(output.stdout)?)),
+        false => Ok(None),
+    }
+}
+
+fn run_git(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).output().context("unable to run git")?;
+
+    if !output.status.success() {
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim())
+    }
+
+    String::from_utf8(output.stdout).map_err(Into::into)
+}
+
+fn read_stdin() -> anyhow::Result<String> {
+    let mut input_string = String::new();
+    std::io::stdin().lock().read_to_string(&mut input_string)?;
+    Ok(input_string)
+}
+
+fn write_stdout(output_str: &str) -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(output_str.as_bytes())?;
+    stdout.flush().map_err(Into::into)
+}
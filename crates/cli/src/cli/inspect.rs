@@ -0,0 +1,168 @@
+use rops::{
+    cryptography::{
+        cipher::{Cipher, AES256GCM, XCHACHA20POLY1305},
+        hasher::{Hasher, BLAKE3, SHA256, SHA512},
+    },
+    file::{
+        format::*,
+        metadata::{state::EncryptedMetadata, IntegrationKeyInfo, PartialEncryptionConfig, RopsFileMetadata},
+        AnyRopsFile, AnyRopsFileFromStrError,
+    },
+};
+use serde::Serialize;
+
+use crate::*;
+
+const MISSING_VALUE: &str = "-";
+
+#[derive(Serialize)]
+struct MetadataReport {
+    integration_keys: Vec<IntegrationKeyInfo>,
+    last_modified: String,
+    partial_encryption: Option<PartialEncryptionConfig>,
+    mac_only_encrypted: bool,
+    encrypt_empty_values: bool,
+    cipher: &'static str,
+    hasher: &'static str,
+}
+
+impl Cli {
+    pub fn inspect(inspect_args: InspectArgs) -> anyhow::Result<()> {
+        let document_reports = Self::metadata_reports(&inspect_args)?;
+        let multiple_documents = document_reports.len() > 1;
+
+        for (index, report) in document_reports.iter().enumerate() {
+            if inspect_args.json.unwrap_or_default() {
+                println!("{}", serde_json::to_string(report)?);
+                continue;
+            }
+
+            if multiple_documents {
+                println!("{}document {index}:", if index > 0 { "\n" } else { "" });
+            }
+
+            println!("cipher: {}", report.cipher);
+            println!("hasher: {}", report.hasher);
+            println!("last modified: {}", report.last_modified);
+            println!(
+                "partial encryption: {}",
+                Self::display_partial_encryption(report.partial_encryption.as_ref())
+            );
+            println!("mac only encrypted: {}", report.mac_only_encrypted);
+            println!(
+                "encrypt empty values: {}",
+                match report.encrypt_empty_values {
+                    // Encrypted empty strings remain readable by SOPS.
+                    true => "true (nulls and empty collections unreadable by SOPS)",
+                    false => "false",
+                }
+            );
+            println!();
+            print_key_table(&report.integration_keys);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn list_keys(inspect_args: InspectArgs) -> anyhow::Result<()> {
+        let document_reports = Self::metadata_reports(&inspect_args)?;
+        let multiple_documents = document_reports.len() > 1;
+
+        for (index, report) in document_reports.iter().enumerate() {
+            if inspect_args.json.unwrap_or_default() {
+                println!("{}", serde_json::to_string(&report.integration_keys)?);
+                continue;
+            }
+
+            if multiple_documents {
+                println!("{}document {index}:", if index > 0 { "\n" } else { "" });
+            }
+
+            print_key_table(&report.integration_keys);
+        }
+
+        Ok(())
+    }
+
+    /// Only the metadata of each document is deserialized, the map is never decrypted. Its cipher
+    /// and hasher are read from the MAC and the `mac_hasher` entry respectively.
+    fn metadata_reports(inspect_args: &InspectArgs) -> anyhow::Result<Vec<MetadataReport>> {
+        let explicit_file_path = inspect_args.file.as_deref();
+        let format = Self::get_format(explicit_file_path, inspect_args.format)?;
+        let input_string = Self::get_input_string(explicit_file_path, None)?;
+
+        return match format {
+            Format::Yaml => metadata_reports_impl::<YamlFileFormat>(&input_string),
+            Format::Json => metadata_reports_impl::<JsonFileFormat>(&input_string),
+            Format::Toml => metadata_reports_impl::<TomlEditFileFormat>(&input_string),
+        };
+
+        fn metadata_reports_impl<F: FileFormat>(input_str: &str) -> anyhow::Result<Vec<MetadataReport>> {
+            F::split_documents(input_str)
+                .iter()
+                .map(|document_str| {
+                    let cipher_name = AnyRopsFile::<F>::detect_cipher_name(document_str)?;
+
+                    match cipher_name.as_str() {
+                        name if name == AES256GCM::NAME => metadata_report::<AES256GCM, F>(document_str),
+                        name if name == XCHACHA20POLY1305::NAME => metadata_report::<XCHACHA20POLY1305, F>(document_str),
+                        _ => Err(AnyRopsFileFromStrError::UnsupportedCipher(cipher_name).into()),
+                    }
+                })
+                .collect()
+        }
+
+        fn metadata_report<C: Cipher, F: FileFormat>(document_str: &str) -> anyhow::Result<MetadataReport> {
+            let hasher_name = AnyRopsFile::<F>::detect_hasher_name(document_str)?;
+
+            match hasher_name.as_str() {
+                name if name == SHA512::NAME => metadata_report_impl::<C, SHA512, F>(document_str),
+                name if name == SHA256::NAME => metadata_report_impl::<C, SHA256, F>(document_str),
+                name if name == BLAKE3::NAME => metadata_report_impl::<C, BLAKE3, F>(document_str),
+                _ => Err(AnyRopsFileFromStrError::UnsupportedHasher(hasher_name).into()),
+            }
+        }
+
+        fn metadata_report_impl<C: Cipher, H: Hasher, F: FileFormat>(document_str: &str) -> anyhow::Result<MetadataReport> {
+            let metadata = RopsFileMetadata::<EncryptedMetadata<C, H>>::from_rops_file_str::<F>(document_str)?;
+
+            Ok(MetadataReport {
+                integration_keys: metadata.integration_keys(),
+                last_modified: metadata.last_modified.to_string(),
+                partial_encryption: metadata.partial_encryption.clone(),
+                mac_only_encrypted: metadata.mac_only_encrypted.unwrap_or_default(),
+                encrypt_empty_values: metadata.encrypt_empty_values.unwrap_or_default(),
+                cipher: metadata.cipher_name(),
+                hasher: metadata.hasher_name(),
+            })
+        }
+    }
+}
+
+fn print_key_table(key_infos: &[IntegrationKeyInfo]) {
+    const HEADERS: [&str; 3] = ["INTEGRATION", "KEY ID", "CREATED AT"];
+
+    let rows = key_infos
+        .iter()
+        .map(|key_info| {
+            [
+                key_info.integration,
+                key_info.key_id.as_str(),
+                key_info.created_at.as_deref().unwrap_or(MISSING_VALUE),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let column_width = |column: usize| {
+        rows.iter()
+            .map(|row| row[column].len())
+            .chain([HEADERS[column].len()])
+            .max()
+            .unwrap_or_default()
+    };
+    let (integration_width, key_id_width) = (column_width(0), column_width(1));
+
+    for [integration, key_id, created_at] in [HEADERS].iter().chain(&rows) {
+        println!("{integration:integration_width$}  {key_id:key_id_width$}  {created_at}");
+    }
+}
@@ -1,5 +1,6 @@
 use rops::{
-    file::{format::*, state::*, RopsFile},
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{format::*, state::*, AnyRopsFile, RopsFile, RopsFileVisitor},
     integration::{AgeIntegration, AwsKmsIntegration},
 };
 
@@ -10,56 +11,187 @@ impl Cli {
         match key_command {
             KeysSubcommand::Add(key_args) => Self::add_keys(key_args),
             KeysSubcommand::Remove(key_args) => Self::remove_keys(key_args),
+            KeysSubcommand::List(inspect_args) => Self::list_keys(inspect_args),
         }
     }
 
     fn add_keys(key_args: KeyInputArgs) -> anyhow::Result<()> {
-        return match Self::get_format(Some(&key_args.file), key_args.format)? {
-            Format::Yaml => add_key_impl::<YamlFileFormat>(key_args),
-            Format::Json => add_key_impl::<JsonFileFormat>(key_args),
-            Format::Toml => add_key_impl::<TomlFileFormat>(key_args),
-        };
+        let keys = &key_args.integration_keys;
 
-        fn add_key_impl<F: FileFormat>(key_args: KeyInputArgs) -> anyhow::Result<()> {
-            let mut rops_file =
-                Cli::get_input_string(Some(&key_args.file), None)?.parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, F>>()?;
+        if let Some(input_path) = Self::batch_path(Some(&key_args.file), key_args.dry_run) {
+            return Self::run_batch(
+                input_path,
+                key_args.format,
+                None,
+                Some(true),
+                key_args.dry_run,
+                |_, file_format, encrypted_string| {
+                    if !Self::is_encrypted(file_format, encrypted_string) {
+                        return Ok(BatchOutcome::unchanged("not encrypted"));
+                    }
 
-            let keys = key_args.integration_keys;
-            rops_file.add_keys::<AgeIntegration>(keys.age)?;
-            rops_file.add_keys::<AwsKmsIntegration>(keys.aws_kms)?;
+                    let (contained_count, total_count) = count_contained_keys(file_format, encrypted_string, keys)?;
+                    if contained_count == total_count {
+                        return Ok(BatchOutcome::unchanged("already contains the keys"));
+                    }
 
-            std::fs::write(key_args.file, rops_file.to_string())?;
+                    Ok(BatchOutcome::Changed {
+                        output: add_keys(file_format, encrypted_string, keys)?,
+                        reason: format!("missing {} key(s)", total_count - contained_count),
+                        destination: None,
+                    })
+                },
+            );
+        }
+
+        let format = Self::get_format(Some(&key_args.file), key_args.format)?;
+        let input_string = Cli::get_input_string(Some(&key_args.file), None)?;
+        std::fs::write(&key_args.file, add_keys(format, &input_string, keys)?)?;
+
+        return Ok(());
+
+        fn add_keys(format: Format, input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<String> {
+            return match format {
+                Format::Yaml => add_key_impl::<YamlFileFormat>(input_str, keys),
+                Format::Json => add_key_impl::<JsonFileFormat>(input_str, keys),
+                Format::Toml => add_key_impl::<TomlEditFileFormat>(input_str, keys),
+            };
+
+            fn add_key_impl<F: FileFormat>(input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<String> {
+                Cli::map_documents::<F>(input_str, |document_str| {
+                    document_str.parse::<AnyRopsFile<F>>()?.visit(AddKeysVisitor(keys))
+                })
+            }
+
+            struct AddKeysVisitor<'a>(&'a IntegrationKeys);
+
+            impl<F: FileFormat> RopsFileVisitor<F> for AddKeysVisitor<'_> {
+                type Output = anyhow::Result<String>;
+
+                fn visit<C: Cipher, H: Hasher>(self, mut rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    rops_file.add_keys::<AgeIntegration>(self.0.age.iter().cloned())?;
+                    rops_file.add_keys::<AwsKmsIntegration>(self.0.aws_kms.iter().cloned())?;
 
-            Ok(())
+                    Ok(rops_file.to_string())
+                }
+            }
         }
     }
 
     fn remove_keys(key_args: KeyInputArgs) -> anyhow::Result<()> {
-        return match Self::get_format(Some(&key_args.file), key_args.format)? {
-            Format::Yaml => remove_key_impl::<YamlFileFormat>(key_args),
-            Format::Json => remove_key_impl::<JsonFileFormat>(key_args),
-            Format::Toml => remove_key_impl::<TomlFileFormat>(key_args),
-        };
-
-        fn remove_key_impl<F: FileFormat>(key_args: KeyInputArgs) -> anyhow::Result<()> {
-            let mut decrypted_rops_file = Cli::get_input_string(Some(&key_args.file), None)?
-                .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, F>>()?
-                .decrypt::<F>()?;
-
-            let keys = key_args.integration_keys;
-
-            // IMPROVEMENT: return error if any key not found?
-            for age_key in keys.age {
-                decrypted_rops_file.remove_integration_key::<AgeIntegration>(&age_key)?;
+        let keys = &key_args.integration_keys;
+
+        if let Some(input_path) = Self::batch_path(Some(&key_args.file), key_args.dry_run) {
+            return Self::run_batch(
+                input_path,
+                key_args.format,
+                None,
+                Some(true),
+                key_args.dry_run,
+                |_, file_format, encrypted_string| {
+                    if !Self::is_encrypted(file_format, encrypted_string) {
+                        return Ok(BatchOutcome::unchanged("not encrypted"));
+                    }
+
+                    let (contained_count, _) = count_contained_keys(file_format, encrypted_string, keys)?;
+                    if contained_count == 0 {
+                        return Ok(BatchOutcome::unchanged("contains none of the keys"));
+                    }
+
+                    Ok(BatchOutcome::Changed {
+                        output: remove_keys(file_format, encrypted_string, keys)?,
+                        reason: format!("contains {contained_count} of the key(s)"),
+                        destination: None,
+                    })
+                },
+            );
+        }
+
+        let format = Self::get_format(Some(&key_args.file), key_args.format)?;
+        let input_string = Cli::get_input_string(Some(&key_args.file), None)?;
+        std::fs::write(&key_args.file, remove_keys(format, &input_string, keys)?)?;
+
+        return Ok(());
+
+        fn remove_keys(format: Format, input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<String> {
+            return match format {
+                Format::Yaml => remove_key_impl::<YamlFileFormat>(input_str, keys),
+                Format::Json => remove_key_impl::<JsonFileFormat>(input_str, keys),
+                Format::Toml => remove_key_impl::<TomlEditFileFormat>(input_str, keys),
+            };
+
+            // Data keys are rotated separately for each document, any previously shared data key is
+            // therefore no longer shared after a removal.
+            fn remove_key_impl<F: FileFormat>(input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<String> {
+                Cli::map_documents::<F>(input_str, |document_str| {
+                    document_str.parse::<AnyRopsFile<F>>()?.visit(RemoveKeysVisitor(keys))
+                })
             }
 
-            for aws_key in keys.aws_kms {
-                decrypted_rops_file.remove_integration_key::<AwsKmsIntegration>(&aws_key)?;
+            struct RemoveKeysVisitor<'a>(&'a IntegrationKeys);
+
+            impl<F: FileFormat> RopsFileVisitor<F> for RemoveKeysVisitor<'_> {
+                type Output = anyhow::Result<String>;
+
+                fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                    let mut decrypted_rops_file = rops_file.decrypt::<F>()?;
+
+                    // IMPROVEMENT: return error if any key not found?
+                    for age_key in &self.0.age {
+                        decrypted_rops_file.remove_integration_key::<AgeIntegration>(age_key)?;
+                    }
+
+                    for aws_key in &self.0.aws_kms {
+                        decrypted_rops_file.remove_integration_key::<AwsKmsIntegration>(aws_key)?;
+                    }
+
+                    Ok(decrypted_rops_file.encrypt::<C, F>()?.to_string())
+                }
             }
+        }
+    }
+}
+
+/// Returns how many of the keys are contained by each document summed, along with the maximum sum.
+fn count_contained_keys(format: Format, input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<(usize, usize)> {
+    return match format {
+        Format::Yaml => count_contained_keys_impl::<YamlFileFormat>(input_str, keys),
+        Format::Json => count_contained_keys_impl::<JsonFileFormat>(input_str, keys),
+        Format::Toml => count_contained_keys_impl::<TomlEditFileFormat>(input_str, keys),
+    };
+
+    fn count_contained_keys_impl<F: FileFormat>(input_str: &str, keys: &IntegrationKeys) -> anyhow::Result<(usize, usize)> {
+        let documents = F::split_documents(input_str);
+        let mut contained_count = 0;
+
+        for document_str in &documents {
+            contained_count += document_str.parse::<AnyRopsFile<F>>()?.visit(CountContainedKeysVisitor(keys));
+        }
+
+        Ok((contained_count, (keys.age.len() + keys.aws_kms.len()) * documents.len()))
+    }
+
+    struct CountContainedKeysVisitor<'a>(&'a IntegrationKeys);
+
+    impl<F: FileFormat> RopsFileVisitor<F> for CountContainedKeysVisitor<'_> {
+        type Output = usize;
+
+        fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+            let CountContainedKeysVisitor(keys) = self;
+            let integration_metadata = &rops_file.metadata().intregation;
 
-            std::fs::write(key_args.file, decrypted_rops_file.encrypt::<DefaultCipher, F>()?.to_string())?;
+            let age_count = keys
+                .age
+                .iter()
+                .filter(|key_id| integration_metadata.age.contains_key(*key_id))
+                .count();
+            let aws_kms_count = keys
+                .aws_kms
+                .iter()
+                .filter(|key_id| integration_metadata.kms.contains_key(*key_id))
+                .count();
 
-            Ok(())
+            age_count + aws_kms_count
         }
     }
 }
@@ -1,8 +1,16 @@
 pub struct Cli;
 
+mod batch;
+pub(crate) use batch::BatchOutcome;
+
+mod check;
 mod common;
 mod decrypt;
+mod diff;
 mod edit;
 mod encrypt;
+mod filestatus;
+mod git;
+mod inspect;
 mod keys;
 mod refresh;
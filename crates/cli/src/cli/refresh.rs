@@ -1,4 +1,9 @@
-use rops::file::{builder::RopsFileBuilder, format::*, state::*, RopsFile};
+use std::borrow::Cow;
+
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::{builder::RopsFileBuilder, format::*, state::*, AnyRopsFile, RopsFile, RopsFileVisitor},
+};
 
 use crate::*;
 
@@ -6,57 +11,179 @@ impl Cli {
     pub fn refresh(refresh_args: RefreshArgs) -> anyhow::Result<()> {
         let explicit_file_path = refresh_args.input_args.file.clone();
         let in_place = refresh_args.in_place;
+        let config = &refresh_args.merged_config;
+
+        if let Some(input_path) = Self::batch_path(explicit_file_path.as_deref(), refresh_args.dry_run) {
+            let file_paths = Self::collect_file_paths(input_path)?;
+            let destinations = config.find_destinations(&file_paths)?;
+
+            return Self::run_batch(
+                input_path,
+                refresh_args.input_args.format,
+                refresh_args.input_args.fallback_format,
+                in_place,
+                refresh_args.dry_run,
+                |file_path, file_format, encrypted_string| {
+                    // Destinations are refreshed through their sources, so that no file is written twice.
+                    if let Some((source_path, _)) = destinations.get(file_path) {
+                        return Ok(BatchOutcome::unchanged(format!("refreshed through {}", source_path.display())));
+                    }
+
+                    let Some(creation_rule) = config.find_creation_rule(Some(file_path)) else {
+                        return Ok(BatchOutcome::unchanged("no matching creation rule"));
+                    };
+
+                    let destination = creation_rule
+                        .destination_path(file_path)
+                        .filter(|_| creation_rule.has_other_destination(file_path));
+
+                    let (file_format, encrypted_string) = match &destination {
+                        Some(destination) if !destination.exists() => {
+                            return Ok(BatchOutcome::unchanged(format!("not yet encrypted to {}", destination.display())))
+                        }
+                        Some(destination) => (
+                            Self::get_format(Some(destination), creation_rule.output_format)?,
+                            Cow::Owned(std::fs::read_to_string(destination)?),
+                        ),
+                        None => (file_format, Cow::Borrowed(encrypted_string)),
+                    };
+
+                    if !Self::is_encrypted(file_format, &encrypted_string) {
+                        return Ok(BatchOutcome::unchanged("not encrypted"));
+                    }
+
+                    let (refreshed_string, discrepancies) = refresh_rops_file(file_format, &encrypted_string, creation_rule)?;
+
+                    Ok(match discrepancies.is_empty() {
+                        true => BatchOutcome::unchanged(format!("implied by creation rule '{}'", creation_rule.path_regex)),
+                        false => BatchOutcome::Changed {
+                            output: refreshed_string,
+                            reason: format!(
+                                "{} differ from creation rule '{}'",
+                                discrepancies.join(", "),
+                                creation_rule.path_regex
+                            ),
+                            destination,
+                        },
+                    })
+                },
+            );
+        }
+
+        let creation_rule = config.find_creation_rule(explicit_file_path.as_deref());
+
+        // Files matched by a creation rule with a destination are refreshed through it.
+        let destination = explicit_file_path
+            .as_deref()
+            .zip(creation_rule)
+            .and_then(|(file_path, creation_rule)| creation_rule.destination_path(file_path));
+
+        let (file_path, file_format) = match destination {
+            Some(destination) => {
+                let destination_format = creation_rule.and_then(|creation_rule| creation_rule.output_format);
+                let file_format = Self::get_format(Some(&destination), destination_format)?;
+                (Some(destination), file_format)
+            }
+            None => (
+                explicit_file_path.clone(),
+                Self::get_format(explicit_file_path.as_deref(), refresh_args.input_args.format)?,
+            ),
+        };
+
+        let encrypted_rops_file_string = Self::get_input_string(file_path.as_deref(), in_place)?;
 
-        let file_format = Self::get_format(explicit_file_path.as_deref(), refresh_args.input_args.format)?;
-        let encrypted_rops_file_string = Self::get_input_string(explicit_file_path.as_deref(), in_place)?;
-        let encrypted_rops_file_string = refresh_rops_file(file_format, encrypted_rops_file_string, refresh_args)?;
+        let encrypted_rops_file_string = match creation_rule {
+            Some(creation_rule) => refresh_rops_file(file_format, &encrypted_rops_file_string, creation_rule)?.0,
+            None => encrypted_rops_file_string,
+        };
 
-        return Cli::write_or_print(in_place, explicit_file_path.as_deref(), &encrypted_rops_file_string).map_err(Into::into);
+        return Cli::write_or_print(in_place, file_path.as_deref(), &encrypted_rops_file_string).map_err(Into::into);
 
-        fn refresh_rops_file(file_format: Format, encrypted_rops_file_string: String, refresh_args: RefreshArgs) -> anyhow::Result<String> {
+        /// Also returns the metadata discrepancies found across all documents.
+        fn refresh_rops_file(
+            file_format: Format,
+            encrypted_rops_file_str: &str,
+            creation_rule: &CreationRule,
+        ) -> anyhow::Result<(String, Vec<&'static str>)> {
             return match file_format {
-                Format::Yaml => refresh_rops_file_impl::<YamlFileFormat>(encrypted_rops_file_string, refresh_args),
-                Format::Json => refresh_rops_file_impl::<JsonFileFormat>(encrypted_rops_file_string, refresh_args),
-                Format::Toml => refresh_rops_file_impl::<TomlFileFormat>(encrypted_rops_file_string, refresh_args),
+                Format::Yaml => refresh_rops_file_impl::<YamlFileFormat>(encrypted_rops_file_str, creation_rule),
+                Format::Json => refresh_rops_file_impl::<JsonFileFormat>(encrypted_rops_file_str, creation_rule),
+                Format::Toml => refresh_rops_file_impl::<TomlEditFileFormat>(encrypted_rops_file_str, creation_rule),
             };
 
             fn refresh_rops_file_impl<F: FileFormat>(
-                mut encrypted_rops_file_string: String,
-                refresh_args: RefreshArgs,
-            ) -> anyhow::Result<String> {
-                let config = Config::retrieve(refresh_args.config_path())?;
-                let encrypted_rops_file = encrypted_rops_file_string.parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, F>>()?;
-
-                // stdin regarded as empty path
-                let path_to_match = refresh_args.input_args.file.unwrap_or_default();
-                let path_to_match = path_to_match.to_string_lossy();
-
-                for creation_rule in config.creation_rules {
-                    if creation_rule.path_regex.is_match(&path_to_match) {
-                        if !creation_rule.implies_metadata(encrypted_rops_file.metadata()) {
-                            let decrypted_rops_file = encrypted_rops_file.decrypt::<F>()?;
-
-                            // IMPROVEMENT: Dry up code with Cli::encrypt()
-                            let mut rops_file_builder = creation_rule
-                                .integration_keys
-                                .add_to_builder(RopsFileBuilder::<F>::from_map(decrypted_rops_file.into_inner_map()));
-
-                            if let Some(partial_encryption_args) = creation_rule.partial_encryption {
-                                rops_file_builder = rops_file_builder.with_partial_encryption(partial_encryption_args)
-                            }
+                encrypted_rops_file_str: &str,
+                creation_rule: &CreationRule,
+            ) -> anyhow::Result<(String, Vec<&'static str>)> {
+                let mut discrepancies = Vec::new();
+
+                let refreshed_string = Cli::map_documents::<F>(encrypted_rops_file_str, |encrypted_document_str| {
+                    encrypted_document_str.parse::<AnyRopsFile<F>>()?.visit(RefreshVisitor {
+                        encrypted_document_str,
+                        creation_rule,
+                        discrepancies: &mut discrepancies,
+                    })
+                })?;
+
+                return Ok((refreshed_string, discrepancies));
+
+                /// Documents are re-encrypted with their own cipher and hasher.
+                struct RefreshVisitor<'a> {
+                    encrypted_document_str: &'a str,
+                    creation_rule: &'a CreationRule,
+                    discrepancies: &'a mut Vec<&'static str>,
+                }
+
+                impl<F: FileFormat> RopsFileVisitor<F> for RefreshVisitor<'_> {
+                    type Output = anyhow::Result<String>;
+
+                    fn visit<C: Cipher, H: Hasher>(self, encrypted_rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                        let RefreshVisitor {
+                            encrypted_document_str,
+                            creation_rule,
+                            discrepancies,
+                        } = self;
+
+                        let document_discrepancies = creation_rule.metadata_discrepancies(encrypted_rops_file.metadata());
+                        if document_discrepancies.is_empty() {
+                            return Ok(encrypted_document_str.to_string());
+                        }
 
-                            if creation_rule.mac_only_encrypted.unwrap_or_default() {
-                                rops_file_builder = rops_file_builder.mac_only_encrypted()
+                        for discrepancy in document_discrepancies {
+                            if !discrepancies.contains(&discrepancy) {
+                                discrepancies.push(discrepancy);
                             }
+                        }
+
+                        // Kept from the file unless set by the rule.
+                        let encrypt_empty_values = creation_rule
+                            .encrypt_empty_values
+                            .or(encrypted_rops_file.metadata().encrypt_empty_values)
+                            .unwrap_or_default();
+
+                        let decrypted_rops_file = encrypted_rops_file.decrypt::<F>()?;
 
-                            encrypted_rops_file_string = rops_file_builder.encrypt::<DefaultCipher, DefaultHasher>()?.to_string();
+                        // IMPROVEMENT: Dry up code with Cli::encrypt()
+                        let mut rops_file_builder = creation_rule
+                            .integration_keys
+                            .clone()
+                            .add_to_builder(RopsFileBuilder::<F>::from_map(decrypted_rops_file.into_inner_map()));
+
+                        if let Some(partial_encryption_args) = creation_rule.partial_encryption.clone() {
+                            rops_file_builder = rops_file_builder.with_partial_encryption(partial_encryption_args)
                         }
 
-                        break;
+                        if creation_rule.mac_only_encrypted.unwrap_or_default() {
+                            rops_file_builder = rops_file_builder.mac_only_encrypted()
+                        }
+
+                        if encrypt_empty_values {
+                            rops_file_builder = rops_file_builder.encrypt_empty_values()
+                        }
+
+                        Ok(rops_file_builder.encrypt::<C, H>()?.to_string())
                     }
                 }
-
-                Ok(encrypted_rops_file_string)
             }
         }
     }
@@ -1,18 +1,113 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use anyhow::bail;
+use indexmap::IndexMap;
+use rops::file::format::*;
 use serde::Deserialize;
 
+use super::SopsConfig;
 use crate::*;
 
-#[derive(Default, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 #[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
 pub struct Config {
+    /// Paths to configs layered below this one, relative to its directory.
+    #[serde(default)]
+    #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub include: Vec<PathBuf>,
+    /// Stops the search for configs in ancestor directories.
+    #[serde(default)]
+    #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "std::ops::Not::not"))]
+    pub root: bool,
+    #[serde(default)]
     pub creation_rules: Vec<CreationRule>,
+    #[serde(default)]
+    pub encrypt: EncryptConfig,
+    #[serde(default)]
+    pub decrypt: DecryptConfig,
+    #[serde(default)]
+    pub edit: EditConfig,
+    #[serde(default)]
+    pub keys: KeysConfig,
 }
 
 impl Config {
-    pub fn retrieve(optional_config_path: Option<&Path>) -> anyhow::Result<Self> {
-        super::retrieve::retrieve_impl::<Self>(optional_config_path)
+    /// Configs are searched for from the directory of the input path, if any.
+    pub fn retrieve(optional_config_path: Option<&Path>, optional_input_path: Option<&Path>) -> anyhow::Result<Self> {
+        super::retrieve::retrieve_impl::<Self>(optional_config_path, optional_input_path)
+    }
+
+    /// First creation rule with a path regex matching the file path, stdin is regarded as an empty path.
+    pub fn find_creation_rule(&self, file_path: Option<&Path>) -> Option<&CreationRule> {
+        let file_path = file_path.unwrap_or(Path::new(""));
+        self.creation_rules.iter().find(|creation_rule| creation_rule.is_match(file_path))
+    }
+
+    /// Destinations of the files which are encrypted to some other path, along with their source and
+    /// its creation rule. Destinations are often not matched by that rule themselves. Errors if several
+    /// sources share a destination, as they would overwrite each other.
+    pub fn find_destinations<'a>(
+        &'a self,
+        file_paths: impl IntoIterator<Item = &'a PathBuf>,
+    ) -> anyhow::Result<IndexMap<PathBuf, (&'a Path, &'a CreationRule)>> {
+        let mut destinations = IndexMap::<PathBuf, (&Path, &CreationRule)>::new();
+
+        for file_path in file_paths {
+            let Some(creation_rule) = self.find_creation_rule(Some(file_path)) else {
+                continue;
+            };
+
+            let Some(destination) = creation_rule
+                .destination_path(file_path)
+                .filter(|_| creation_rule.has_other_destination(file_path))
+            else {
+                continue;
+            };
+
+            if let Some((other_file_path, _)) = destinations.get(&destination) {
+                bail!(
+                    "{} and {} share the destination {}, consider adding '{{dir}}' to the destination of creation rule '{}'",
+                    other_file_path.display(),
+                    file_path.display(),
+                    destination.display(),
+                    creation_rule.path_regex
+                )
+            }
+
+            destinations.insert(destination, (file_path, creation_rule));
+        }
+
+        Ok(destinations)
+    }
+}
+
+impl LayeredConfig for Config {
+    fn take_includes(&mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.include)
+    }
+
+    fn is_root(&self) -> bool {
+        self.root
+    }
+
+    fn anchor_to(&mut self, config_directory: &Path) {
+        for creation_rule in &mut self.creation_rules {
+            creation_rule.config_directory = Some(config_directory.to_path_buf());
+        }
+    }
+
+    fn from_sops_config_str(sops_config_str: &str) -> anyhow::Result<Self> {
+        YamlFileFormat::deserialize_from_str::<SopsConfig>(sops_config_str)?.try_into()
+    }
+
+    /// Creation rules of `self` are matched first, subcommand settings are overridden one by one.
+    fn layer_over(mut self, base: Self) -> Self {
+        self.creation_rules.extend(base.creation_rules);
+        self.encrypt = self.encrypt.layer_over(base.encrypt);
+        self.decrypt = self.decrypt.layer_over(base.decrypt);
+        self.edit = self.edit.layer_over(base.edit);
+        self.keys = self.keys.layer_over(base.keys);
+        self
     }
 }
 
@@ -26,6 +121,7 @@ mod mock {
         fn mock() -> Self {
             Self {
                 creation_rules: vec![MockTestUtil::mock()],
+                ..Default::default()
             }
         }
     }
@@ -34,6 +130,7 @@ mod mock {
         fn mock_other() -> Self {
             Self {
                 creation_rules: vec![MockOtherTestUtil::mock_other()],
+                ..Default::default()
             }
         }
     }
@@ -1,10 +1,15 @@
+use std::path::{Path, PathBuf};
+
 use regex::Regex;
-use rops::file::metadata::{state::*, *};
+use rops::{
+    cryptography::{cipher::Cipher, hasher::Hasher},
+    file::metadata::{state::*, *},
+};
 use serde::Deserialize;
 
 use crate::*;
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
 pub struct CreationRule {
     #[serde(with = "serde_regex")]
@@ -12,17 +17,94 @@ pub struct CreationRule {
     #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Option::is_none"))]
     pub mac_only_encrypted: Option<bool>,
     #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Option::is_none"))]
+    pub encrypt_empty_values: Option<bool>,
+    #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Option::is_none"))]
     pub partial_encryption: Option<PartialEncryptionConfig>,
     // IMPROVEMENT: add some skip serializing if default for testing
     #[serde(default)]
     pub integration_keys: IntegrationKeys,
+    /// Path template of the encrypted output, `{dir}`, `{file_name}`, `{file_stem}` and `{extension}`
+    /// are replaced by those of the matched file.
+    #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Option::is_none"))]
+    pub destination: Option<String>,
+    #[cfg_attr(feature = "test-utils", serde(skip_serializing_if = "Option::is_none"))]
+    pub output_format: Option<Format>,
+    /// Directory of the declaring config, which the path regex is matched relative to.
+    #[serde(skip)]
+    pub config_directory: Option<PathBuf>,
 }
 
 impl CreationRule {
-    pub fn implies_metadata(&self, metadata: &RopsFileMetadata<EncryptedMetadata<DefaultCipher, DefaultHasher>>) -> bool {
-        self.mac_only_encrypted == metadata.mac_only_encrypted
-            && self.partial_encryption == metadata.partial_encryption
-            && self.integration_keys.implies_integration_metadata(&metadata.intregation)
+    /// Paths within the directory of the declaring config are matched relative to it, others as
+    /// they are given. Stdin is regarded as an empty path.
+    pub fn is_match(&self, file_path: &Path) -> bool {
+        let relative_path = self
+            .config_directory
+            .as_deref()
+            .filter(|_| !file_path.as_os_str().is_empty())
+            .and_then(|config_directory| {
+                let canonical_path = file_path.canonicalize().ok()?;
+                canonical_path.strip_prefix(config_directory).ok().map(Path::to_path_buf)
+            });
+
+        self.path_regex
+            .is_match(&relative_path.as_deref().unwrap_or(file_path).to_string_lossy())
+    }
+
+    /// Destination of a matched file, expanded from the destination template.
+    pub fn destination_path(&self, file_path: &Path) -> Option<PathBuf> {
+        let destination_template = self.destination.as_deref()?;
+        let lossy = |component: Option<&std::ffi::OsStr>| {
+            component
+                .map(|component| component.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let destination_path = destination_template
+            .replace("{dir}", &lossy(file_path.parent().map(Path::as_os_str)))
+            .replace("{file_name}", &lossy(file_path.file_name()))
+            .replace("{file_stem}", &lossy(file_path.file_stem()))
+            .replace("{extension}", &lossy(file_path.extension()));
+
+        // Collected from the components to drop the empty separators of files without a directory.
+        Some(Path::new(&destination_path).components().collect())
+    }
+
+    /// Whether files matched by the rule are encrypted to some other path than their own.
+    pub fn has_other_destination(&self, file_path: &Path) -> bool {
+        self.destination_path(file_path)
+            .is_some_and(|destination_path| destination_path != file_path)
+    }
+
+    pub fn implies_metadata<C: Cipher, H: Hasher>(&self, metadata: &RopsFileMetadata<EncryptedMetadata<C, H>>) -> bool {
+        self.metadata_discrepancies(metadata).is_empty()
+    }
+
+    /// Names of the metadata settings which differ from those of the creation rule.
+    pub fn metadata_discrepancies<C: Cipher, H: Hasher>(&self, metadata: &RopsFileMetadata<EncryptedMetadata<C, H>>) -> Vec<&'static str> {
+        let mut discrepancies = Vec::new();
+
+        if !self.integration_keys.implies_integration_metadata(&metadata.intregation) {
+            discrepancies.push("integration keys");
+        }
+
+        if self.partial_encryption != metadata.partial_encryption {
+            discrepancies.push("partial encryption");
+        }
+
+        if self.mac_only_encrypted != metadata.mac_only_encrypted {
+            discrepancies.push("mac only encrypted");
+        }
+
+        // Left to the file when unset by the rule, as refreshes keep it.
+        if self
+            .encrypt_empty_values
+            .is_some_and(|encrypt_empty_values| encrypt_empty_values != metadata.encrypt_empty_values.unwrap_or_default())
+        {
+            discrepancies.push("encrypt empty values");
+        }
+
+        discrepancies
     }
 }
 
@@ -41,7 +123,11 @@ mod mock {
                 path_regex: file_to_match.to_str().unwrap().parse().unwrap(),
                 integration_keys: MockTestUtil::mock(),
                 mac_only_encrypted: rops_file_metadata.mac_only_encrypted,
+                encrypt_empty_values: rops_file_metadata.encrypt_empty_values,
                 partial_encryption: rops_file_metadata.partial_encryption,
+                destination: None,
+                output_format: None,
+                config_directory: None,
             }
         }
     }
@@ -52,7 +138,11 @@ mod mock {
                 path_regex: ".*".parse().unwrap(),
                 integration_keys: MockOtherTestUtil::mock_other(),
                 mac_only_encrypted: Some(true),
+                encrypt_empty_values: Some(true),
                 partial_encryption: None,
+                destination: None,
+                output_format: None,
+                config_directory: None,
             }
         }
     }
@@ -69,4 +159,61 @@ mod tests {
         assert!(CreationRule::mock().implies_metadata(&MockTestUtil::mock()));
         assert!(!CreationRule::mock_other().implies_metadata(&MockTestUtil::mock()));
     }
+
+    #[test]
+    fn expands_destination_template() {
+        let mut creation_rule = CreationRule::mock();
+        assert!(creation_rule.destination_path(Path::new("secrets/api.yaml")).is_none());
+
+        creation_rule.destination = Some("secrets/enc/{file_stem}.enc.{extension}".to_string());
+        assert_eq!(
+            Some(PathBuf::from("secrets/enc/api.enc.yaml")),
+            creation_rule.destination_path(Path::new("secrets/api.yaml"))
+        );
+
+        creation_rule.destination = Some("enc/{dir}/{file_name}".to_string());
+        assert_eq!(
+            Some(PathBuf::from("enc/secrets/api/api.yaml")),
+            creation_rule.destination_path(Path::new("secrets/api/api.yaml"))
+        );
+        assert_eq!(
+            Some(PathBuf::from("enc/api.yaml")),
+            creation_rule.destination_path(Path::new("api.yaml"))
+        );
+
+        creation_rule.destination = Some("secrets/enc/{file_name}".to_string());
+        assert!(creation_rule.has_other_destination(Path::new("secrets/api.yaml")));
+        assert!(!creation_rule.has_other_destination(Path::new("secrets/enc/api.yaml")));
+    }
+
+    #[test]
+    fn matches_relative_to_config_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("secrets/api.yaml");
+        std::fs::create_dir(temp_dir.path().join("secrets")).unwrap();
+        std::fs::write(&file_path, "").unwrap();
+
+        let mut creation_rule = CreationRule::mock();
+        creation_rule.path_regex = "^secrets/".parse().unwrap();
+        assert!(!creation_rule.is_match(&file_path));
+        assert!(creation_rule.is_match(Path::new("secrets/other.yaml")));
+
+        creation_rule.config_directory = Some(temp_dir.path().canonicalize().unwrap());
+        assert!(creation_rule.is_match(&file_path));
+        assert!(creation_rule.is_match(Path::new("secrets/other.yaml")));
+    }
+
+    #[test]
+    fn lists_metadata_discrepancies() {
+        assert!(CreationRule::mock().metadata_discrepancies(&MockTestUtil::mock()).is_empty());
+        assert_eq!(
+            vec![
+                "integration keys",
+                "partial encryption",
+                "mac only encrypted",
+                "encrypt empty values"
+            ],
+            CreationRule::mock_other().metadata_discrepancies(&MockTestUtil::mock())
+        );
+    }
 }
@@ -4,5 +4,12 @@ pub use core::Config;
 mod creation_rule;
 pub use creation_rule::CreationRule;
 
+mod subcommands;
+pub use subcommands::{DecryptConfig, EditConfig, EncryptConfig, KeysConfig};
+
+mod sops;
+use sops::SopsConfig;
+
 mod retrieve;
 pub use retrieve::DefaulConfigFileFormat;
+pub(crate) use retrieve::LayeredConfig;
@@ -1,42 +1,144 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use anyhow::{bail, Context};
 use rops::file::format::*;
 use serde::de::DeserializeOwned;
 
 pub type DefaulConfigFileFormat = TomlFileFormat;
 const ROPS_CONFIG_ENV_VAR_NAME: &str = "ROPS_CONFIG";
-const ROPS_CONFIG_DEFAULT_FILE_NAME: &str = ".rops.toml";
+/// Looked up in order, only the first found in each directory is used.
+const ROPS_CONFIG_DEFAULT_FILE_NAMES: [&str; 5] = [".rops.toml", ".rops.yaml", ".rops.yml", ".rops.json", SOPS_CONFIG_FILE_NAME];
+const SOPS_CONFIG_FILE_NAME: &str = ".sops.yaml";
+
+/// Configs which may be layered on top of each other, e.g. when nested or included.
+pub(crate) trait LayeredConfig: DeserializeOwned + Default {
+    /// Takes the paths of the configs to include, relative to the including config.
+    fn take_includes(&mut self) -> Vec<PathBuf>;
+
+    /// Whether ancestor directories should be searched for further configs.
+    fn is_root(&self) -> bool;
+
+    /// Anchors the settings of a config read from a file to its directory.
+    fn anchor_to(&mut self, _config_directory: &Path) {}
+
+    /// Settings of `self` take precedence over those of `base`.
+    fn layer_over(self, base: Self) -> Self;
+
+    fn from_sops_config_str(_sops_config_str: &str) -> anyhow::Result<Self> {
+        bail!("SOPS configs are not supported")
+    }
+}
 
 // separated with generic parameter to simplify unit testing of strategy
-pub(super) fn retrieve_impl<T: DeserializeOwned + Default>(optional_config_path: Option<&Path>) -> anyhow::Result<T> {
+pub(super) fn retrieve_impl<T: LayeredConfig>(
+    optional_config_path: Option<&Path>,
+    optional_input_path: Option<&Path>,
+) -> anyhow::Result<T> {
     if let Some(arg_path) = optional_config_path {
-        return read_fs_path_and_deserialize::<T>(arg_path);
+        return read_with_includes::<T>(arg_path, &mut Vec::new());
     }
 
     if let Some(env_path) = std::env::var_os(ROPS_CONFIG_ENV_VAR_NAME) {
-        return read_fs_path_and_deserialize::<T>(env_path);
+        return read_with_includes::<T>(Path::new(&env_path), &mut Vec::new());
     }
 
-    return traverse_fs_or_default::<T>();
+    return traverse_fs_or_default::<T>(optional_input_path);
+
+    /// Configs found in nested directories take precedence over those found in their ancestors.
+    fn traverse_fs_or_default<T: LayeredConfig>(optional_input_path: Option<&Path>) -> anyhow::Result<T> {
+        let mut layered_config = None::<T>;
+        let mut traversal_path = traversal_start(optional_input_path)?;
 
-    fn traverse_fs_or_default<T: DeserializeOwned + Default>() -> anyhow::Result<T> {
-        let mut traversal_path = std::env::current_dir()?;
         loop {
-            traversal_path.push(ROPS_CONFIG_DEFAULT_FILE_NAME);
-            if traversal_path.exists() {
-                return read_fs_path_and_deserialize::<T>(traversal_path);
+            let found_config_path = ROPS_CONFIG_DEFAULT_FILE_NAMES
+                .iter()
+                .map(|file_name| traversal_path.join(file_name))
+                .find(|config_path| config_path.exists());
+
+            if let Some(config_path) = found_config_path {
+                let directory_config = read_with_includes::<T>(&config_path, &mut Vec::new())?;
+                let is_root = directory_config.is_root();
+
+                layered_config = Some(match layered_config {
+                    Some(nested_config) => nested_config.layer_over(directory_config),
+                    None => directory_config,
+                });
+
+                if is_root {
+                    break;
+                }
             }
-            traversal_path.pop();
 
             if !traversal_path.pop() {
-                return Ok(T::default());
+                break;
+            }
+        }
+
+        Ok(layered_config.unwrap_or_default())
+    }
+
+    /// Directories are searched from themselves, files from their parent directory. Globs, and files
+    /// yet to be created, from the closest existing directory.
+    fn traversal_start(optional_input_path: Option<&Path>) -> std::io::Result<PathBuf> {
+        let current_dir = std::env::current_dir()?;
+
+        let Some(input_path) = optional_input_path else {
+            return Ok(current_dir);
+        };
+
+        let mut traversal_start = current_dir.join(input_path);
+        while !traversal_start.is_dir() {
+            if !traversal_start.pop() {
+                return Ok(current_dir);
             }
         }
+
+        Ok(traversal_start)
+    }
+
+    /// Includes are layered below the including config, in the order they are listed.
+    fn read_with_includes<T: LayeredConfig>(config_path: &Path, include_chain: &mut Vec<PathBuf>) -> anyhow::Result<T> {
+        let canonical_path = config_path
+            .canonicalize()
+            .with_context(|| format!("unable to read config {}", config_path.display()))?;
+
+        if include_chain.contains(&canonical_path) {
+            bail!("config {} includes itself", config_path.display())
+        }
+        let mut config = read_fs_path_and_deserialize::<T>(config_path)?;
+        if let Some(canonical_directory) = canonical_path.parent() {
+            config.anchor_to(canonical_directory);
+        }
+
+        include_chain.push(canonical_path);
+        let config_directory = config_path.parent().unwrap_or(Path::new(""));
+
+        for include_path in config.take_includes() {
+            let included_config = read_with_includes::<T>(&config_directory.join(include_path), include_chain)?;
+            config = config.layer_over(included_config);
+        }
+
+        include_chain.pop();
+
+        Ok(config)
     }
 
-    fn read_fs_path_and_deserialize<T: DeserializeOwned>(config_path: impl AsRef<Path>) -> anyhow::Result<T> {
+    /// Format is inferred by file extension, falling back to TOML. SOPS configs are recognized by file name.
+    fn read_fs_path_and_deserialize<T: LayeredConfig>(config_path: &Path) -> anyhow::Result<T> {
         let config_string = std::fs::read_to_string(config_path)?;
-        DefaulConfigFileFormat::deserialize_from_str(&config_string).map_err(Into::into)
+
+        if config_path.file_name().is_some_and(|file_name| file_name == SOPS_CONFIG_FILE_NAME) {
+            return T::from_sops_config_str(&config_string)
+                .with_context(|| format!("unable to read SOPS config {}", config_path.display()));
+        }
+
+        let deserialize_result = match config_path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml" | "yml") => YamlFileFormat::deserialize_from_str(&config_string).map_err(anyhow::Error::from),
+            Some("json") => JsonFileFormat::deserialize_from_str(&config_string).map_err(Into::into),
+            _ => DefaulConfigFileFormat::deserialize_from_str(&config_string).map_err(Into::into),
+        };
+
+        deserialize_result.with_context(|| format!("unable to parse config {}", config_path.display()))
     }
 }
 
@@ -50,6 +152,37 @@ mod tests {
     #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
     struct StubConfig {
         location: Location,
+        #[serde(default)]
+        layered_locations: Vec<Location>,
+        #[serde(default)]
+        include: Vec<PathBuf>,
+        #[serde(default)]
+        root: bool,
+    }
+
+    impl LayeredConfig for StubConfig {
+        fn take_includes(&mut self) -> Vec<PathBuf> {
+            std::mem::take(&mut self.include)
+        }
+
+        fn is_root(&self) -> bool {
+            self.root
+        }
+
+        fn layer_over(mut self, base: Self) -> Self {
+            self.layered_locations.push(base.location);
+            self.layered_locations.extend(base.layered_locations);
+            self
+        }
+    }
+
+    impl From<Location> for StubConfig {
+        fn from(location: Location) -> Self {
+            Self {
+                location,
+                ..Default::default()
+            }
+        }
     }
 
     impl StubConfig {
@@ -64,6 +197,8 @@ mod tests {
         Arg,
         Env,
         Traversal,
+        Nested,
+        Include,
         #[default]
         Fallback,
     }
@@ -71,24 +206,24 @@ mod tests {
     #[test]
     #[serial_test::serial(config_retrieval)]
     fn retrieves_config_by_arg() {
-        let expected_config = StubConfig { location: Location::Arg };
+        let expected_config = StubConfig::from(Location::Arg);
         let temp_file = NamedTempFile::new().unwrap();
         expected_config.serialize(temp_file.path());
 
-        let retrieved_config = retrieve_impl(Some(temp_file.path())).unwrap();
+        let retrieved_config = retrieve_impl(Some(temp_file.path()), None).unwrap();
         assert_eq!(expected_config, retrieved_config);
     }
 
     #[test]
     #[serial_test::serial(config_retrieval)]
     fn retrieves_config_by_env() {
-        let expected_config = StubConfig { location: Location::Env };
+        let expected_config = StubConfig::from(Location::Env);
         let temp_file = NamedTempFile::new().unwrap();
         expected_config.serialize(temp_file.path());
 
         std::env::set_var(ROPS_CONFIG_ENV_VAR_NAME, temp_file.path());
 
-        let retrieved_config = retrieve_impl(None).unwrap();
+        let retrieved_config = retrieve_impl(None, None).unwrap();
         assert_eq!(expected_config, retrieved_config);
 
         std::env::remove_var(ROPS_CONFIG_ENV_VAR_NAME);
@@ -107,13 +242,11 @@ mod tests {
     }
 
     fn test_traversal_impl(directory_path: &Path) {
-        let expected_config = StubConfig {
-            location: Location::Traversal,
-        };
-        let path = directory_path.join(ROPS_CONFIG_DEFAULT_FILE_NAME);
+        let expected_config = StubConfig::from(Location::Traversal);
+        let path = directory_path.join(ROPS_CONFIG_DEFAULT_FILE_NAMES[0]);
         expected_config.serialize(&path);
 
-        let retrieved_config = retrieve_impl(None).unwrap();
+        let retrieved_config = retrieve_impl(None, None).unwrap();
         assert_eq!(expected_config, retrieved_config);
 
         std::fs::remove_file(path).unwrap();
@@ -122,6 +255,82 @@ mod tests {
     #[test]
     #[serial_test::serial(config_retrieval)]
     fn retrieves_config_by_default_fallback() {
-        assert_eq!(StubConfig::default(), retrieve_impl(None).unwrap());
+        assert_eq!(StubConfig::default(), retrieve_impl(None, None).unwrap());
+    }
+
+    #[test]
+    #[serial_test::serial(config_retrieval)]
+    fn layers_nested_configs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested_dir = temp_dir.path().join("nested");
+        std::fs::create_dir(&nested_dir).unwrap();
+
+        let root_config = StubConfig {
+            root: true,
+            ..StubConfig::from(Location::Traversal)
+        };
+        root_config.serialize(&temp_dir.path().join(ROPS_CONFIG_DEFAULT_FILE_NAMES[0]));
+        std::fs::write(nested_dir.join(".rops.yaml"), "location: Nested\n").unwrap();
+
+        let previous_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(&nested_dir).unwrap();
+        let retrieved_config = retrieve_impl::<StubConfig>(None, None);
+        std::env::set_current_dir(previous_dir).unwrap();
+
+        assert_eq!(
+            StubConfig {
+                layered_locations: vec![Location::Traversal],
+                ..StubConfig::from(Location::Nested)
+            },
+            retrieved_config.unwrap()
+        );
+    }
+
+    #[test]
+    #[serial_test::serial(config_retrieval)]
+    fn retrieves_config_by_traversal_from_input_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested_dir = temp_dir.path().join("nested");
+        std::fs::create_dir(&nested_dir).unwrap();
+        StubConfig::from(Location::Traversal).serialize(&temp_dir.path().join(ROPS_CONFIG_DEFAULT_FILE_NAMES[0]));
+
+        for input_path in [nested_dir.join("secrets.yaml"), nested_dir.clone(), nested_dir.join("*.yaml")] {
+            assert_eq!(
+                StubConfig::from(Location::Traversal),
+                retrieve_impl(None, Some(&input_path)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[serial_test::serial(config_retrieval)]
+    fn includes_configs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, "location = \"Arg\"\ninclude = [\"included.json\"]\n").unwrap();
+        std::fs::write(temp_dir.path().join("included.json"), r#"{ "location": "Include" }"#).unwrap();
+
+        assert_eq!(
+            StubConfig {
+                layered_locations: vec![Location::Include],
+                ..StubConfig::from(Location::Arg)
+            },
+            retrieve_impl(Some(&config_path), None).unwrap()
+        );
+    }
+
+    #[test]
+    #[serial_test::serial(config_retrieval)]
+    fn disallows_include_cycles() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, "location = \"Arg\"\ninclude = [\"included.toml\"]\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("included.toml"),
+            "location = \"Include\"\ninclude = [\"config.toml\"]\n",
+        )
+        .unwrap();
+
+        assert!(retrieve_impl::<StubConfig>(Some(&config_path), None).is_err());
     }
 }
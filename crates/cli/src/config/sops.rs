@@ -0,0 +1,325 @@
+use anyhow::{bail, Context};
+use regex::Regex;
+use rops::{
+    file::metadata::PartialEncryptionConfig,
+    integration::{AgeIntegration, AwsKmsIntegration, Integration},
+};
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::*;
+
+/// AWS profile used by SOPS when none is set.
+const SOPS_DEFAULT_AWS_PROFILE: &str = "default";
+
+/// A SOPS `.sops.yaml` config, unknown fields are rejected rather than silently ignored.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct SopsConfig {
+    #[serde(default)]
+    creation_rules: Vec<SopsCreationRule>,
+    destination_rules: Option<IgnoredAny>,
+    // Store formatting options, irrelevant to rops.
+    #[serde(rename = "stores")]
+    _stores: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SopsCreationRule {
+    path_regex: Option<String>,
+    age: Option<SopsKeyList>,
+    kms: Option<SopsKeyList>,
+    aws_profile: Option<String>,
+    key_groups: Option<Vec<SopsKeyGroup>>,
+    shamir_threshold: Option<usize>,
+    pgp: Option<SopsKeyList>,
+    gcp_kms: Option<SopsKeyList>,
+    azure_keyvault: Option<SopsKeyList>,
+    hc_vault_transit_uri: Option<SopsKeyList>,
+    encrypted_suffix: Option<String>,
+    encrypted_regex: Option<String>,
+    unencrypted_suffix: Option<String>,
+    unencrypted_regex: Option<String>,
+    encrypted_comment_regex: Option<String>,
+    unencrypted_comment_regex: Option<String>,
+    mac_only_encrypted: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SopsKeyGroup {
+    #[serde(default)]
+    age: Vec<String>,
+    #[serde(default)]
+    kms: Vec<SopsKmsKey>,
+    pgp: Option<IgnoredAny>,
+    gcp_kms: Option<IgnoredAny>,
+    azure_keyvault: Option<IgnoredAny>,
+    hc_vault: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SopsKmsKey {
+    arn: String,
+    role: Option<String>,
+    context: Option<IgnoredAny>,
+    aws_profile: Option<String>,
+}
+
+/// Comma separated string, or a list of strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SopsKeyList {
+    String(String),
+    List(Vec<String>),
+}
+
+impl SopsKeyList {
+    fn into_keys(self) -> Vec<String> {
+        let keys = match self {
+            SopsKeyList::String(keys_str) => keys_str.split(',').map(ToString::to_string).collect(),
+            SopsKeyList::List(keys) => keys,
+        };
+
+        keys.into_iter()
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect()
+    }
+}
+
+impl TryFrom<SopsConfig> for Config {
+    type Error = anyhow::Error;
+
+    fn try_from(sops_config: SopsConfig) -> Result<Self, Self::Error> {
+        if sops_config.destination_rules.is_some() {
+            bail!("SOPS destination rules are not supported by rops")
+        }
+
+        let creation_rules = sops_config
+            .creation_rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, sops_creation_rule)| {
+                CreationRule::try_from(sops_creation_rule).with_context(|| format!("unable to convert SOPS creation rule {index}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        Ok(Self {
+            // SOPS only ever reads the nearest config.
+            root: true,
+            creation_rules,
+            ..Default::default()
+        })
+    }
+}
+
+impl TryFrom<SopsCreationRule> for CreationRule {
+    type Error = anyhow::Error;
+
+    fn try_from(sops_creation_rule: SopsCreationRule) -> Result<Self, Self::Error> {
+        let unsupported_key_types = [
+            ("pgp", sops_creation_rule.pgp),
+            ("gcp_kms", sops_creation_rule.gcp_kms),
+            ("azure_keyvault", sops_creation_rule.azure_keyvault),
+            ("hc_vault_transit_uri", sops_creation_rule.hc_vault_transit_uri),
+        ];
+
+        for (key_type, keys) in unsupported_key_types {
+            if keys.is_some_and(|keys| !keys.into_keys().is_empty()) {
+                bail!("{key_type} keys are not supported by rops")
+            }
+        }
+
+        if sops_creation_rule.encrypted_comment_regex.is_some() || sops_creation_rule.unencrypted_comment_regex.is_some() {
+            bail!("comment encryption is not supported by rops")
+        }
+
+        let mut integration_keys = IntegrationKeys::default();
+        let aws_profile = sops_creation_rule.aws_profile.as_deref().unwrap_or(SOPS_DEFAULT_AWS_PROFILE);
+
+        for age_key in sops_creation_rule.age.map(SopsKeyList::into_keys).unwrap_or_default() {
+            integration_keys.age.push(parse_age_key(&age_key)?);
+        }
+
+        for kms_key in sops_creation_rule.kms.map(SopsKeyList::into_keys).unwrap_or_default() {
+            if kms_key.contains('+') {
+                bail!("AWS KMS roles are not supported by rops, found in '{kms_key}'")
+            }
+
+            integration_keys.aws_kms.push(parse_aws_kms_key(aws_profile, &kms_key)?);
+        }
+
+        let key_groups = sops_creation_rule.key_groups.unwrap_or_default();
+
+        if key_groups.len() > 1 || sops_creation_rule.shamir_threshold.is_some() {
+            bail!("multiple key groups and shamir thresholds are not supported by rops")
+        }
+
+        for key_group in key_groups {
+            if key_group.pgp.is_some() || key_group.gcp_kms.is_some() || key_group.azure_keyvault.is_some() || key_group.hc_vault.is_some()
+            {
+                bail!("key groups may only contain age and AWS KMS keys in rops")
+            }
+
+            for age_key in key_group.age {
+                integration_keys.age.push(parse_age_key(&age_key)?);
+            }
+
+            for kms_key in key_group.kms {
+                if kms_key.role.is_some() || kms_key.context.is_some() {
+                    bail!(
+                        "AWS KMS roles and encryption contexts are not supported by rops, found for '{}'",
+                        kms_key.arn
+                    )
+                }
+
+                let aws_profile = kms_key.aws_profile.as_deref().unwrap_or(aws_profile);
+                integration_keys.aws_kms.push(parse_aws_kms_key(aws_profile, &kms_key.arn)?);
+            }
+        }
+
+        let partial_encryption_configs = [
+            sops_creation_rule.encrypted_suffix.map(PartialEncryptionConfig::EncryptedSuffix),
+            sops_creation_rule
+                .encrypted_regex
+                .map(|regex_str| Regex::new(&regex_str).map(|regex| PartialEncryptionConfig::EncryptedRegex(regex.into())))
+                .transpose()?,
+            sops_creation_rule
+                .unencrypted_suffix
+                .map(PartialEncryptionConfig::UnencryptedSuffix),
+            sops_creation_rule
+                .unencrypted_regex
+                .map(|regex_str| Regex::new(&regex_str).map(|regex| PartialEncryptionConfig::UnencryptedRegex(regex.into())))
+                .transpose()?,
+        ];
+
+        let mut partial_encryption_configs = partial_encryption_configs.into_iter().flatten();
+        let partial_encryption = partial_encryption_configs.next();
+        if partial_encryption_configs.next().is_some() {
+            bail!("only one of encrypted_suffix, encrypted_regex, unencrypted_suffix and unencrypted_regex may be set")
+        }
+
+        return Ok(Self {
+            // SOPS matches every path if none is set.
+            path_regex: Regex::new(sops_creation_rule.path_regex.as_deref().unwrap_or_default())?,
+            mac_only_encrypted: sops_creation_rule.mac_only_encrypted,
+            encrypt_empty_values: None,
+            partial_encryption,
+            integration_keys,
+            destination: None,
+            output_format: None,
+            config_directory: None,
+        });
+
+        fn parse_age_key(age_key: &str) -> anyhow::Result<<AgeIntegration as Integration>::KeyId> {
+            age_key
+                .parse()
+                .map_err(|error| anyhow::anyhow!("invalid age key '{age_key}': {error}"))
+        }
+
+        fn parse_aws_kms_key(aws_profile: &str, kms_arn: &str) -> anyhow::Result<<AwsKmsIntegration as Integration>::KeyId> {
+            format!("{aws_profile}.{kms_arn}")
+                .parse()
+                .with_context(|| format!("invalid AWS KMS key '{kms_arn}'"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rops::test_utils::*;
+
+    use super::*;
+
+    fn convert(sops_config_str: &str) -> anyhow::Result<Config> {
+        Config::from_sops_config_str(sops_config_str)
+    }
+
+    fn mock_kms_arn() -> String {
+        let aws_key_id = <AwsKmsIntegration as Integration>::KeyId::mock_display();
+        aws_key_id.split_once('.').unwrap().1.to_string()
+    }
+
+    #[test]
+    fn converts_sops_creation_rules() {
+        let sops_config_str = format!(
+            "
+creation_rules:
+  - path_regex: \\.dev\\.yaml$
+    age: '{}, {}'
+    kms: {}
+    aws_profile: staging
+    unencrypted_regex: ^public_
+    mac_only_encrypted: true
+  - key_groups:
+      - age:
+          - {}
+",
+            <AgeIntegration as Integration>::KeyId::mock_display(),
+            <AgeIntegration as Integration>::KeyId::mock_other(),
+            mock_kms_arn(),
+            <AgeIntegration as Integration>::KeyId::mock_display(),
+        );
+
+        let config = convert(&sops_config_str).unwrap();
+        assert!(config.root);
+
+        let [dev_rule, fallback_rule] = config.creation_rules.as_slice() else {
+            panic!("expected two creation rules")
+        };
+
+        assert_eq!(r"\.dev\.yaml$", dev_rule.path_regex.as_str());
+        assert_eq!(2, dev_rule.integration_keys.age.len());
+        assert_eq!(
+            format!("staging.{}", mock_kms_arn()),
+            dev_rule.integration_keys.aws_kms[0].to_string()
+        );
+        assert_eq!(
+            Some(PartialEncryptionConfig::UnencryptedRegex(Regex::new("^public_").unwrap().into())),
+            dev_rule.partial_encryption
+        );
+        assert_eq!(Some(true), dev_rule.mac_only_encrypted);
+
+        assert_eq!("", fallback_rule.path_regex.as_str());
+        assert_eq!(
+            vec![<AgeIntegration as Integration>::KeyId::mock()],
+            fallback_rule.integration_keys.age
+        );
+    }
+
+    #[test]
+    fn defaults_to_default_aws_profile() {
+        let config = convert(&format!("creation_rules:\n  - kms: {}\n", mock_kms_arn())).unwrap();
+        assert_eq!(
+            <AwsKmsIntegration as Integration>::KeyId::mock_display(),
+            config.creation_rules[0].integration_keys.aws_kms[0].to_string()
+        );
+    }
+
+    #[test]
+    fn disallows_unrepresentable_rules() {
+        let assert_error = |sops_config_str: &str, expected_message: &str| {
+            let Err(error) = convert(sops_config_str) else {
+                panic!("expected conversion of '{sops_config_str}' to fail")
+            };
+            assert!(format!("{error:#}").contains(expected_message), "{error:#}")
+        };
+
+        assert_error("creation_rules:\n  - pgp: FBC7B9E2A4F9289AC0C1D4843D16CEE4A27381B4\n", "pgp keys");
+        assert_error(
+            "creation_rules:\n  - encrypted_suffix: _enc\n    unencrypted_suffix: _plain\n",
+            "only one of",
+        );
+        assert_error("creation_rules:\n  - shamir_threshold: 2\n", "shamir");
+        assert_error("creation_rules:\n  - kms: arn:aws:kms:a+arn:aws:iam::role\n", "roles");
+        assert_error("creation_rules:\n  - unknown_setting: true\n", "unknown field");
+        assert_error("destination_rules: []\n", "destination rules");
+    }
+
+    #[test]
+    fn ignores_empty_unsupported_keys() {
+        assert!(convert("creation_rules:\n  - pgp: ''\n").is_ok());
+    }
+}
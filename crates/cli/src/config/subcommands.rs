@@ -0,0 +1,80 @@
+use serde::Deserialize;
+
+use crate::*;
+
+#[derive(Clone, Default, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub struct EncryptConfig {
+    /// Input format used when it can't be inferred from the file extension, e.g. for stdin.
+    pub format: Option<Format>,
+    pub output_format: Option<Format>,
+    pub shared_data_key: Option<bool>,
+    pub cipher: Option<CipherVariant>,
+    pub hasher: Option<HasherVariant>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub struct DecryptConfig {
+    /// Input format used when it can't be inferred from the file extension, e.g. for stdin.
+    pub format: Option<Format>,
+    pub output_format: Option<Format>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub struct EditConfig {
+    /// Input format used when it can't be inferred from the file extension, e.g. for stdin.
+    pub format: Option<Format>,
+    pub output_format: Option<Format>,
+    /// Keep the ciphertexts of unchanged values, enabled by default.
+    pub preserve_nonces: Option<bool>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub struct KeysConfig {
+    /// Keys added by `keys add` if none are provided as arguments.
+    #[serde(default)]
+    pub add: IntegrationKeys,
+}
+
+impl EncryptConfig {
+    pub fn layer_over(self, base: Self) -> Self {
+        Self {
+            format: self.format.or(base.format),
+            output_format: self.output_format.or(base.output_format),
+            shared_data_key: self.shared_data_key.or(base.shared_data_key),
+            cipher: self.cipher.or(base.cipher),
+            hasher: self.hasher.or(base.hasher),
+        }
+    }
+}
+
+impl DecryptConfig {
+    pub fn layer_over(self, base: Self) -> Self {
+        Self {
+            format: self.format.or(base.format),
+            output_format: self.output_format.or(base.output_format),
+        }
+    }
+}
+
+impl EditConfig {
+    pub fn layer_over(self, base: Self) -> Self {
+        Self {
+            format: self.format.or(base.format),
+            output_format: self.output_format.or(base.output_format),
+            preserve_nonces: self.preserve_nonces.or(base.preserve_nonces),
+        }
+    }
+}
+
+impl KeysConfig {
+    pub fn layer_over(self, base: Self) -> Self {
+        match self.add.is_empty() {
+            true => base,
+            false => self,
+        }
+    }
+}
@@ -1,4 +1,38 @@
+use clap::ValueEnum;
 use rops::cryptography::{cipher::AES256GCM, hasher::SHA512};
+use serde::Deserialize;
 
 pub type DefaultCipher = AES256GCM;
 pub type DefaultHasher = SHA512;
+
+/// Cipher used for encryption, files are decrypted with the one named in their MAC.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub enum CipherVariant {
+    #[default]
+    #[value(name = "aes256-gcm")]
+    #[serde(rename = "aes256-gcm")]
+    Aes256Gcm,
+    /// Not supported by SOPS.
+    #[value(name = "xchacha20-poly1305")]
+    #[serde(rename = "xchacha20-poly1305")]
+    XChaCha20Poly1305,
+}
+
+/// MAC hasher used for encryption, files are verified with the one recorded in their metadata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum, Deserialize)]
+#[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
+pub enum HasherVariant {
+    #[default]
+    #[value(name = "sha512")]
+    #[serde(rename = "sha512")]
+    Sha512,
+    /// Not supported by SOPS.
+    #[value(name = "sha256")]
+    #[serde(rename = "sha256")]
+    Sha256,
+    /// Not supported by SOPS.
+    #[value(name = "blake3")]
+    #[serde(rename = "blake3")]
+    Blake3,
+}
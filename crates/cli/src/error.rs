@@ -2,6 +2,8 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::*;
+
 #[derive(Debug, PartialEq, Error)]
 pub enum RopsCliError {
     #[error("multiple inputs; received content from stdin when a file path was provided")]
@@ -10,6 +12,10 @@ pub enum RopsCliError {
     MissingInput,
     #[error("unable to determine input format; {0}")]
     UndeterminedFormat(#[from] UndeterminedFormatError),
+    #[error("{0} documents found; the output format only supports a single document")]
+    MultipleDocuments(usize),
+    #[error("unable to write {1} in place of a {0} file; omit --in-place or --output-format")]
+    InPlaceFormatConversion(Format, Format),
 }
 
 #[derive(Debug, PartialEq, Error)]
@@ -12,7 +12,7 @@ use serde_with::DisplayFromStr;
     - Serde proc macros are for use in config serialization.
 */
 #[serde_with::serde_as]
-#[derive(Default, Args, Deserialize)]
+#[derive(Clone, Default, Args, Deserialize)]
 #[cfg_attr(feature = "test-utils", derive(serde::Serialize))]
 pub struct IntegrationKeys {
     /// Space separated list of public age keys
@@ -35,6 +35,10 @@ impl IntegrationKeys {
         self.aws_kms.append(&mut other.aws_kms);
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.age.is_empty() && self.aws_kms.is_empty()
+    }
+
     pub fn implies_integration_metadata(&self, integration_metadata: &IntegrationMetadata) -> bool {
         return identical_keys::<AgeIntegration>(&self.age, &integration_metadata.age)
             && identical_keys::<AwsKmsIntegration>(&self.aws_kms, &integration_metadata.kms);
@@ -9,14 +9,14 @@ mod args;
 pub(crate) use args::*;
 
 mod cli;
-pub(crate) use cli::Cli;
+pub(crate) use cli::{BatchOutcome, Cli};
 
 mod config;
-pub(crate) use config::CreationRule;
-pub use config::{Config, DefaulConfigFileFormat};
+pub use config::{Config, DecryptConfig, DefaulConfigFileFormat, EditConfig, EncryptConfig, KeysConfig};
+pub(crate) use config::{CreationRule, LayeredConfig};
 
 mod cryptography_stack;
-pub use cryptography_stack::{DefaultCipher, DefaultHasher};
+pub use cryptography_stack::{CipherVariant, DefaultCipher, DefaultHasher, HasherVariant};
 
 mod integration_keys;
 pub(crate) use integration_keys::IntegrationKeys;
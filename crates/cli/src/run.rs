@@ -4,13 +4,18 @@ use crate::*;
 
 pub fn run() -> anyhow::Result<()> {
     let mut args = CliArgs::parse();
-    args.merge_config(Config::retrieve(args.config_path())?);
+    args.merge_config(Config::retrieve(args.config_path(), args.input_path())?);
 
     match args.cmd {
         CliSubcommand::Encrypt(encrypt_args) => Cli::encrypt(encrypt_args),
         CliSubcommand::Decrypt(decrypt_args) => Cli::decrypt(decrypt_args),
-        CliSubcommand::Edit(input_args) => Cli::edit(input_args),
+        CliSubcommand::Edit(edit_args) => Cli::edit(edit_args),
         CliSubcommand::Keys(key_command) => Cli::keys(key_command),
         CliSubcommand::Refresh(refresh_args) => Cli::refresh(refresh_args),
+        CliSubcommand::Check(check_args) => Cli::check(check_args),
+        CliSubcommand::FileStatus(filestatus_args) => Cli::filestatus(filestatus_args),
+        CliSubcommand::Inspect(inspect_args) => Cli::inspect(inspect_args),
+        CliSubcommand::Diff(diff_args) => Cli::diff(diff_args),
+        CliSubcommand::Git(git_command) => Cli::git(git_command),
     }
 }
@@ -0,0 +1,284 @@
+use tempfile::TempDir;
+
+use super::*;
+
+use super::encryption::utils::decrypt_str;
+
+const PLAINTEXT: &str = "hello: world!\n";
+
+#[test]
+fn encrypts_directory() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--in-place", "secrets"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "skipped secrets/encrypted.yaml: already encrypted\nchanged secrets/plaintext.yaml: plaintext matched by creation rule 'secrets/.*'\n1 changed, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+
+    let encrypted_str = std::fs::read_to_string(directory.path().join("secrets/plaintext.yaml")).unwrap();
+    pretty_assertions::assert_eq!(PLAINTEXT, decrypt_str::<AgeIntegration>(&encrypted_str).map().to_string());
+    pretty_assertions::assert_eq!(PLAINTEXT, utils::read(&directory, "secrets/.hidden.yaml"));
+    pretty_assertions::assert_eq!(PLAINTEXT, utils::read(&directory, "unmatched/plaintext.yaml"));
+}
+
+#[test]
+fn skips_unmatched_files() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--in-place", "unmatched"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "skipped unmatched/plaintext.yaml: no matching creation rule\n0 changed, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+}
+
+#[test]
+fn lists_changes_on_dry_run() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--dry-run", "secrets"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "skipped secrets/encrypted.yaml: already encrypted\nwould change secrets/plaintext.yaml: plaintext matched by creation rule 'secrets/.*'\n1 would change, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+    pretty_assertions::assert_eq!(PLAINTEXT, utils::read(&directory, "secrets/plaintext.yaml"));
+}
+
+#[test]
+fn falls_back_to_configured_format() {
+    let directory = utils::init_directory();
+    let config = std::fs::read_to_string(directory.path().join(".rops.toml")).unwrap();
+    std::fs::write(
+        directory.path().join(".rops.toml"),
+        format!("[encrypt]\nformat = \"yaml\"\n\n{config}"),
+    )
+    .unwrap();
+    std::fs::write(directory.path().join("secrets/app.toml"), "hello = \"world!\"\n").unwrap();
+    std::fs::write(directory.path().join("secrets/extensionless"), PLAINTEXT).unwrap();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--dry-run", "secrets/*"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "would change secrets/app.toml: plaintext matched by creation rule 'secrets/.*'\nskipped secrets/encrypted.yaml: already encrypted\nwould change secrets/extensionless: plaintext matched by creation rule 'secrets/.*'\nwould change secrets/plaintext.yaml: plaintext matched by creation rule 'secrets/.*'\n3 would change, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+}
+
+#[test]
+fn decrypts_glob() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["decrypt", "--in-place", "secrets/*.yaml"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "changed secrets/encrypted.yaml: encrypted\nskipped secrets/plaintext.yaml: not encrypted\n1 changed, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+    pretty_assertions::assert_eq!(PLAINTEXT, utils::read(&directory, "secrets/encrypted.yaml"));
+}
+
+#[test]
+fn lists_refresh_reasons_on_dry_run() {
+    let directory = utils::init_directory();
+    let config = format!(
+        "[[creation_rules]]\npath_regex = \"secrets/.*\"\nintegration_keys.age = [\"{}\"]\n",
+        <AgeIntegration as Integration>::KeyId::mock_other()
+    );
+    std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["refresh", "--dry-run", "secrets"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "would change secrets/encrypted.yaml: integration keys differ from creation rule 'secrets/.*'\nskipped secrets/plaintext.yaml: not encrypted\n1 would change, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+}
+
+#[test]
+fn refreshes_destinations_through_sources() {
+    let directory = utils::init_directory();
+    let config = format!(
+        "[[creation_rules]]\npath_regex = \"sources/.*\"\ndestination = \"sources/{{file_stem}}.enc.{{extension}}\"\nintegration_keys.age = [\"{}\", \"{}\"]\n",
+        <AgeIntegration as Integration>::KeyId::mock_display(),
+        <AgeIntegration as Integration>::KeyId::mock_other()
+    );
+    std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+
+    std::fs::create_dir(directory.path().join("sources")).unwrap();
+    std::fs::write(directory.path().join("sources/api.yaml"), PLAINTEXT).unwrap();
+    std::fs::copy(
+        directory.path().join("secrets/encrypted.yaml"),
+        directory.path().join("sources/api.enc.yaml"),
+    )
+    .unwrap();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["refresh", "--in-place", "sources"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "skipped sources/api.enc.yaml: refreshed through sources/api.yaml\nchanged sources/api.yaml -> sources/api.enc.yaml: integration keys differ from creation rule 'sources/.*'\n1 changed, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+    pretty_assertions::assert_eq!(PLAINTEXT, utils::read(&directory, "sources/api.yaml"));
+    assert_eq!(
+        2,
+        decrypt_str::<AgeIntegration>(&utils::read(&directory, "sources/api.enc.yaml"))
+            .metadata()
+            .intregation
+            .age
+            .len()
+    );
+}
+
+#[test]
+fn rejects_shared_destinations() {
+    let directory = utils::init_directory();
+    let config = format!(
+        "[[creation_rules]]\npath_regex = \"sources/.*\"\ndestination = \"encrypted/{{file_name}}\"\nintegration_keys.age = [\"{}\"]\n",
+        <AgeIntegration as Integration>::KeyId::mock_display()
+    );
+    std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+
+    for source_dir in ["sources/api", "sources/web"] {
+        std::fs::create_dir_all(directory.path().join(source_dir)).unwrap();
+        std::fs::write(directory.path().join(source_dir).join("secrets.yaml"), PLAINTEXT).unwrap();
+    }
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--in-place", "sources"]);
+
+    let output = cmd.output().unwrap();
+    output.assert_failure();
+    assert!(output
+        .stderr_str()
+        .contains("sources/api/secrets.yaml and sources/web/secrets.yaml share the destination encrypted/secrets.yaml"));
+    assert!(!directory.path().join("encrypted").exists());
+
+    let config = std::fs::read_to_string(directory.path().join(".rops.toml")).unwrap();
+    std::fs::write(
+        directory.path().join(".rops.toml"),
+        config.replace("{file_name}", "{dir}/{file_name}"),
+    )
+    .unwrap();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["encrypt", "--in-place", "sources"]);
+
+    cmd.output().unwrap().assert_success();
+    assert!(directory.path().join("encrypted/sources/api/secrets.yaml").exists());
+    assert!(directory.path().join("encrypted/sources/web/secrets.yaml").exists());
+}
+
+#[test]
+fn adds_keys_to_directory() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args([
+        "keys",
+        "add",
+        "--age",
+        &<AgeIntegration as Integration>::KeyId::mock_other().to_string(),
+        "secrets",
+    ]);
+
+    let output = cmd.output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "changed secrets/encrypted.yaml: missing 1 key(s)\nskipped secrets/plaintext.yaml: not encrypted\n1 changed, 1 skipped, 0 failed\n",
+        output.stdout_str()
+    );
+    assert_eq!(
+        2,
+        decrypt_str::<AgeIntegration>(&utils::read(&directory, "secrets/encrypted.yaml"))
+            .metadata()
+            .intregation
+            .age
+            .len()
+    );
+}
+
+#[test]
+fn requires_in_place_or_dry_run() {
+    let directory = utils::init_directory();
+
+    let mut cmd = Command::package_command().batch(&directory);
+    cmd.args(["decrypt", "secrets"]);
+
+    cmd.output().unwrap().assert_failure();
+    pretty_assertions::assert_eq!(
+        PLAINTEXT,
+        decrypt_str::<AgeIntegration>(&utils::read(&directory, "secrets/encrypted.yaml"))
+            .map()
+            .to_string()
+    );
+}
+
+use utils::BatchCommand;
+mod utils {
+    use super::*;
+
+    /// Creates 'secrets/{plaintext,encrypted,.hidden}.yaml' and 'unmatched/plaintext.yaml', with
+    /// a config matching files in 'secrets'.
+    pub fn init_directory() -> TempDir {
+        let directory = tempfile::tempdir().unwrap();
+
+        let config = format!(
+            "[[creation_rules]]\npath_regex = \"secrets/.*\"\nintegration_keys.age = [\"{}\"]\n",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        );
+        std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+
+        let encrypted_output = Command::package_command().encrypt().run_piped(PLAINTEXT);
+        encrypted_output.assert_success();
+
+        std::fs::create_dir(directory.path().join("secrets")).unwrap();
+        std::fs::create_dir(directory.path().join("unmatched")).unwrap();
+        std::fs::write(directory.path().join("secrets/plaintext.yaml"), PLAINTEXT).unwrap();
+        std::fs::write(directory.path().join("secrets/encrypted.yaml"), encrypted_output.stdout_str()).unwrap();
+        std::fs::write(directory.path().join("secrets/.hidden.yaml"), PLAINTEXT).unwrap();
+        std::fs::write(directory.path().join("unmatched/plaintext.yaml"), PLAINTEXT).unwrap();
+
+        directory
+    }
+
+    pub fn read(directory: &TempDir, file_path: &str) -> String {
+        std::fs::read_to_string(directory.path().join(file_path)).unwrap()
+    }
+
+    pub trait BatchCommand {
+        fn batch(self, directory: &TempDir) -> Self;
+    }
+
+    impl BatchCommand for Command {
+        fn batch(mut self, directory: &TempDir) -> Self {
+            AgeIntegration::set_mock_private_key_env_var();
+            self.current_dir(directory.path());
+            self
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use tempfile::TempDir;
+
+use super::*;
+
+const PLAINTEXT: &str = "hello: world!\n";
+
+#[test]
+fn passes_configured_files() {
+    let directory = utils::init_directory();
+    utils::write_encrypted(
+        &directory,
+        "secrets/encrypted.yaml",
+        &<AgeIntegration as Integration>::KeyId::mock_display(),
+    );
+    std::fs::write(directory.path().join("unmatched.yaml"), PLAINTEXT).unwrap();
+
+    let output = Command::package_command().check(&directory).output().unwrap();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "{\"path\":\"secrets/encrypted.yaml\",\"status\":\"ok\",\"issues\":[]}\n",
+        output.stdout_str()
+    );
+}
+
+#[test]
+fn reports_drift() {
+    let directory = utils::init_directory();
+
+    let encrypted_str = utils::write_encrypted(
+        &directory,
+        "secrets/leaked.yaml",
+        &<AgeIntegration as Integration>::KeyId::mock_display(),
+    );
+    let leaked_str = encrypted_str
+        .lines()
+        .map(|line| match line.starts_with("hello:") {
+            true => "hello: world!",
+            false => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(directory.path().join("secrets/leaked.yaml"), leaked_str).unwrap();
+
+    utils::write_encrypted(
+        &directory,
+        "secrets/other_key.yaml",
+        &<AgeIntegration as Integration>::KeyId::mock_other().to_string(),
+    );
+    std::fs::write(directory.path().join("secrets/plaintext.yaml"), PLAINTEXT).unwrap();
+
+    let output = Command::package_command().check(&directory).output().unwrap();
+    output.assert_failure();
+    pretty_assertions::assert_eq!(
+        concat!(
+            "{\"path\":\"secrets/leaked.yaml\",\"status\":\"drift\",\"issues\":[\"plaintext value found where encryption is required\"]}\n",
+            "{\"path\":\"secrets/other_key.yaml\",\"status\":\"drift\",\"issues\":[\"integration keys differ from creation rule 'secrets/.*'\"]}\n",
+            "{\"path\":\"secrets/plaintext.yaml\",\"status\":\"drift\",\"issues\":[\"not an encrypted rops file\"]}\n",
+        ),
+        output.stdout_str()
+    );
+}
+
+#[test]
+fn checks_destinations_of_sources() {
+    let directory = utils::init_directory();
+    let config = format!(
+        "[[creation_rules]]\npath_regex = \"secrets/.*\"\ndestination = \"secrets/{{file_stem}}.enc.{{extension}}\"\nintegration_keys.age = [\"{}\"]\n",
+        <AgeIntegration as Integration>::KeyId::mock_display()
+    );
+    std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+
+    std::fs::write(directory.path().join("secrets/api.yaml"), PLAINTEXT).unwrap();
+    utils::write_encrypted(
+        &directory,
+        "secrets/api.enc.yaml",
+        &<AgeIntegration as Integration>::KeyId::mock_display(),
+    );
+    std::fs::write(directory.path().join("secrets/pending.yaml"), PLAINTEXT).unwrap();
+
+    let output = Command::package_command().check(&directory).output().unwrap();
+    output.assert_failure();
+
+    let stdout = output.stdout_str();
+    let mut report_lines = stdout.lines();
+    pretty_assertions::assert_eq!(
+        Some("{\"path\":\"secrets/api.enc.yaml\",\"status\":\"ok\",\"issues\":[]}"),
+        report_lines.next()
+    );
+    assert!(report_lines
+        .next()
+        .unwrap()
+        .starts_with("{\"path\":\"secrets/pending.enc.yaml\",\"status\":\"drift\""));
+    assert_eq!(None, report_lines.next());
+}
+
+use utils::CheckCommand;
+mod utils {
+    use super::*;
+
+    pub fn init_directory() -> TempDir {
+        let directory = tempfile::tempdir().unwrap();
+
+        let config = format!(
+            "[[creation_rules]]\npath_regex = \"secrets/.*\"\nintegration_keys.age = [\"{}\"]\n",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        );
+        std::fs::write(directory.path().join(".rops.toml"), config).unwrap();
+        std::fs::create_dir(directory.path().join("secrets")).unwrap();
+
+        directory
+    }
+
+    pub fn write_encrypted(directory: &TempDir, file_path: &str, age_key_id: &str) -> String {
+        let mut cmd = Command::package_command();
+        cmd.args(["encrypt", "--format", "yaml", "--age", age_key_id]);
+
+        let output = cmd.run_piped(PLAINTEXT);
+        output.assert_success();
+        std::fs::write(directory.path().join(file_path), output.stdout_str()).unwrap();
+
+        output.stdout_str().to_string()
+    }
+
+    pub trait CheckCommand {
+        fn check(self, directory: &TempDir) -> Self;
+    }
+
+    impl CheckCommand for Command {
+        fn check(mut self, directory: &TempDir) -> Self {
+            // Checks must not require access to any private key.
+            self.env_remove(AgeIntegration::private_key_env_var_name());
+            self.current_dir(directory.path()).arg("check");
+            self
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use super::*;
+use tempfile::NamedTempFile;
+
+use super::{encryption::utils::decrypt_str, *};
 
 #[test]
 fn applies_config_creation_rule() {
@@ -25,8 +27,229 @@ fn applies_config_creation_rule() {
     assert_eq!(2, encrypted_rops_file.metadata().intregation.age.len())
 }
 
+#[test]
+fn applies_sops_config() {
+    let config_dir = tempfile::tempdir().unwrap();
+    let config_path = config_dir.path().join(".sops.yaml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "creation_rules:\n  - age: {}\n    unencrypted_suffix: _unencrypted\n",
+            <AgeIntegration as Integration>::KeyId::mock_other()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--config", config_path.to_str().unwrap()]);
+
+    let output = cmd.run_piped("hello: world!");
+    output.assert_success();
+
+    let encrypted_rops_file = output
+        .stdout_str()
+        .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, YamlFileFormat>>()
+        .unwrap();
+
+    assert_eq!(2, encrypted_rops_file.metadata().intregation.age.len());
+    assert_eq!(
+        Some(PartialEncryptionConfig::mock()),
+        encrypted_rops_file.metadata().partial_encryption
+    );
+}
+
+#[test]
+fn applies_creation_rule_destination() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    std::fs::write(temp_dir.path().join("api.yaml"), "hello: world!").unwrap();
+
+    let destination_config = |age_key: String| {
+        utils::write_config(&format!(
+            "[[creation_rules]]\npath_regex = 'api\\.yaml$'\ndestination = 'enc/{{file_stem}}.json'\noutput_format = 'json'\nintegration_keys.age = ['{age_key}']\n",
+        ))
+    };
+
+    let read_destination = || {
+        std::fs::read_to_string(temp_dir.path().join("enc/api.json"))
+            .unwrap()
+            .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, JsonFileFormat>>()
+            .unwrap()
+    };
+
+    let config_file = destination_config(<AgeIntegration as Integration>::KeyId::mock_display());
+    let mut cmd = Command::package_command().encrypt();
+    cmd.current_dir(temp_dir.path());
+    cmd.args(["--config", config_file.path().to_str().unwrap(), "api.yaml"]);
+    cmd.run_tty().assert_success();
+
+    assert_eq!(1, read_destination().metadata().intregation.age.len());
+
+    AgeIntegration::set_mock_private_key_env_var();
+    let config_file = destination_config(<AgeIntegration as Integration>::KeyId::mock_other().to_string());
+    let mut cmd = Command::package_command();
+    cmd.current_dir(temp_dir.path());
+    cmd.args([
+        "refresh",
+        "--in-place",
+        "--config",
+        config_file.path().to_str().unwrap(),
+        "api.yaml",
+    ]);
+    cmd.run_tty().assert_success();
+
+    let refreshed_rops_file = read_destination();
+    assert!(refreshed_rops_file
+        .metadata()
+        .intregation
+        .age
+        .contains_key(&<AgeIntegration as Integration>::KeyId::mock_other()));
+    assert_eq!("hello: world!", std::fs::read_to_string(temp_dir.path().join("api.yaml")).unwrap());
+}
+
+#[test]
+fn applies_nested_config_of_input_path() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let project_dir = temp_dir.path().join("project");
+    std::fs::create_dir_all(project_dir.join("secrets")).unwrap();
+    std::fs::write(project_dir.join("secrets/api.yaml"), "hello: world!").unwrap();
+    std::fs::write(
+        project_dir.join(".rops.toml"),
+        format!(
+            "[[creation_rules]]\npath_regex = '^secrets/'\nintegration_keys.age = ['{}']\n",
+            <AgeIntegration as Integration>::KeyId::mock_other()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = Command::package_command();
+    cmd.current_dir(temp_dir.path());
+    cmd.args(["encrypt", "project/secrets/api.yaml"]);
+
+    let output = cmd.run_tty();
+    output.assert_success();
+
+    let encrypted_rops_file = output
+        .stdout_str()
+        .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, YamlFileFormat>>()
+        .unwrap();
+
+    assert!(encrypted_rops_file
+        .metadata()
+        .intregation
+        .age
+        .contains_key(&<AgeIntegration as Integration>::KeyId::mock_other()));
+}
+
+#[test]
+fn applies_encrypt_config() {
+    let config_file = utils::write_config("[encrypt]\noutput_format = \"json\"\n");
+
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--config", config_file.path().to_str().unwrap()]);
+
+    let output = cmd.run_piped("hello: world!");
+    output.assert_success();
+    output
+        .stdout_str()
+        .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, JsonFileFormat>>()
+        .unwrap();
+}
+
+#[test]
+fn applies_decrypt_config() {
+    let config_file = utils::write_config("[decrypt]\nformat = \"yaml\"\noutput_format = \"json\"\n");
+
+    AgeIntegration::set_mock_private_key_env_var();
+    let mut cmd = Command::package_command();
+    cmd.args(["decrypt", "--config", config_file.path().to_str().unwrap()]);
+
+    let output = cmd.run_piped(sops_yaml_str!("age_example"));
+    output.assert_success();
+    serde_json::from_str::<serde_json::Value>(output.stdout_str()).unwrap();
+}
+
+#[test]
+fn applies_yaml_config() {
+    let config_file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+    std::fs::write(config_file.path(), "decrypt:\n  output_format: json\n").unwrap();
+
+    AgeIntegration::set_mock_private_key_env_var();
+    let mut cmd = Command::package_command();
+    cmd.args(["decrypt", "--config", config_file.path().to_str().unwrap()]);
+
+    let output = cmd.format_args().run_piped(sops_yaml_str!("age_example"));
+    output.assert_success();
+    serde_json::from_str::<serde_json::Value>(output.stdout_str()).unwrap();
+}
+
+#[test]
+fn applies_edit_config() {
+    const PLAINTEXT: &str = "hello: world!\nkept: value\n";
+    const EDIT_CONTENT: &str = "hello: editor\nkept: value\n";
+
+    let encrypted_output = Command::package_command().encrypt().run_piped(PLAINTEXT);
+    encrypted_output.assert_success();
+    let kept_line = |encrypted_str: &str| encrypted_str.lines().find(|line| line.starts_with("kept:")).unwrap().to_string();
+
+    let edit = |config: &str| {
+        let config_file = utils::write_config(config);
+
+        AgeIntegration::set_mock_private_key_env_var();
+        let mut cmd = Command::package_command();
+        cmd.env(
+            "EDITOR",
+            format!("{} '{}'", path_to_mock_editor().to_str().expect("valid unicode"), EDIT_CONTENT),
+        );
+        cmd.args(["edit", "--config", config_file.path().to_str().unwrap()]);
+
+        let output = cmd.format_args().run_piped(encrypted_output.stdout_str());
+        output.assert_success();
+        pretty_assertions::assert_eq!(EDIT_CONTENT, decrypt_str::<AgeIntegration>(output.stdout_str()).map().to_string());
+        kept_line(output.stdout_str())
+    };
+
+    assert_eq!(kept_line(encrypted_output.stdout_str()), edit(""));
+    assert_ne!(kept_line(encrypted_output.stdout_str()), edit("[edit]\npreserve_nonces = false\n"));
+}
+
+#[test]
+fn applies_keys_config() {
+    let config_file = utils::write_config(&format!(
+        "[keys.add]\nage = [\"{}\"]\n",
+        <AgeIntegration as Integration>::KeyId::mock_other()
+    ));
+
+    let encrypted_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(encrypted_file.path(), sops_yaml_str!("age_example")).unwrap();
+
+    AgeIntegration::set_mock_private_key_env_var();
+    let mut cmd = Command::package_command();
+    cmd.args(["keys", "add"]);
+    let mut cmd = cmd.format_args();
+    cmd.arg(encrypted_file.path());
+    cmd.env("ROPS_CONFIG", config_file.path());
+    cmd.run_tty().assert_success();
+
+    let encrypted_rops_file = std::fs::read_to_string(encrypted_file.path())
+        .unwrap()
+        .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, YamlFileFormat>>()
+        .unwrap();
+
+    assert_eq!(2, encrypted_rops_file.metadata().intregation.age.len())
+}
+
 #[test]
 fn deserializes_book_examples() {
     let book_config = include_str!("../../../../book/assets/.rops.toml");
     DefaulConfigFileFormat::deserialize_from_str::<Config>(book_config).unwrap();
 }
+
+mod utils {
+    use super::*;
+
+    pub fn write_config(config_str: &str) -> NamedTempFile {
+        let config_file = NamedTempFile::new().unwrap();
+        std::fs::write(config_file.path(), config_str).unwrap();
+        config_file
+    }
+}
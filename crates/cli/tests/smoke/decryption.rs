@@ -1,5 +1,7 @@
 use super::*;
 
+use super::encryption::utils::MULTI_DOCUMENT_PLAINTEXT;
+
 #[test]
 fn decrypts_from_stdin() {
     let encrypted_str = sops_yaml_str!("age_example");
@@ -31,6 +33,105 @@ fn decrypts_in_place() {
     )
 }
 
+#[test]
+fn decrypts_multiple_documents() {
+    let encrypted_output = Command::package_command().encrypt().run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    encrypted_output.assert_success();
+
+    let decrypted_output = Command::package_command().decrypt_age().run_piped(encrypted_output.stdout_str());
+    decrypted_output.assert_success();
+    pretty_assertions::assert_eq!(format!("{MULTI_DOCUMENT_PLAINTEXT}\n"), decrypted_output.stdout_str())
+}
+
+#[test]
+fn decrypts_with_cipher_named_in_mac() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--cipher", "xchacha20-poly1305"]);
+    let encrypted_output = cmd.run_piped(sops_yaml_str!("age_example_plaintext"));
+    encrypted_output.assert_success();
+    assert!(encrypted_output.stdout_str().contains("ENC[XCHACHA20_POLY1305,"));
+
+    let decrypted_output = Command::package_command().decrypt_age().run_piped(encrypted_output.stdout_str());
+    assert_decrypted_output(decrypted_output);
+}
+
+#[test]
+fn decrypts_with_recorded_mac_hasher() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--hasher", "blake3"]);
+    let encrypted_output = cmd.run_piped(sops_yaml_str!("age_example_plaintext"));
+    encrypted_output.assert_success();
+    assert!(encrypted_output.stdout_str().contains("mac_hasher: BLAKE3"));
+
+    let decrypted_output = Command::package_command().decrypt_age().run_piped(encrypted_output.stdout_str());
+    assert_decrypted_output(decrypted_output);
+}
+
+#[test]
+fn decrypts_to_output_format() {
+    let mut cmd = Command::package_command().decrypt_age();
+    cmd.args(["--output-format", "json"]);
+
+    let decrypted_output = cmd.run_piped(sops_yaml_str!("age_example"));
+    decrypted_output.assert_success();
+
+    pretty_assertions::assert_eq!(
+        "{\n  \"hello\": \"Welcome to SOPS! Edit this file as you please!\",\n  \"example_key\": \"example_value\",\n  \"example_array\": [\n    \"example_value1\",\n    \"example_value2\"\n  ],\n  \"example_number\": 1234.56789,\n  \"example_booleans\": [\n    true,\n    false\n  ]\n}\n",
+        decrypted_output.stdout_str()
+    )
+}
+
+#[test]
+fn reports_integers_out_of_output_format_range() {
+    let mut cmd = Command::package_command();
+    cmd.args([
+        "encrypt",
+        "--format",
+        "json",
+        "--age",
+        &<AgeIntegration as Integration>::KeyId::mock_display(),
+    ]);
+    let encrypted_output = cmd.run_piped(r#"{"big": 123456789012345678901234567890}"#);
+    encrypted_output.assert_success();
+
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let mut cmd = Command::package_command();
+    cmd.args(["decrypt", "--format", "json", "--output-format", "toml"]);
+
+    let decrypted_output = cmd.run_piped(encrypted_output.stdout_str());
+    assert_eq!(Some(1), decrypted_output.status.code());
+    assert!(decrypted_output.stderr_str().contains("integer out of range"));
+}
+
+#[test]
+fn disallows_multiple_documents_in_single_document_output_format() {
+    let encrypted_output = Command::package_command().encrypt().run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    encrypted_output.assert_success();
+
+    let mut cmd = Command::package_command().decrypt_age();
+    cmd.args(["--output-format", "json"]);
+
+    let decrypted_output = cmd.run_piped(encrypted_output.stdout_str());
+    decrypted_output.assert_failure();
+    assert!(decrypted_output.stderr_str().contains("2 documents found"));
+}
+
+#[test]
+fn disallows_in_place_format_conversion() {
+    let mut cmd = Command::package_command().decrypt_age_in_place();
+    cmd.args(["--output-format", "json"]);
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), sops_yaml_str!("age_example")).unwrap();
+    cmd.arg(temp_file.path());
+
+    let output = cmd.run_tty();
+    output.assert_failure();
+    assert!(output.stderr_str().contains("unable to write json in place of a yaml file"));
+    assert_eq!(sops_yaml_str!("age_example"), std::fs::read_to_string(temp_file.path()).unwrap());
+}
+
 use utils::{assert_decrypted_output, DecryptCommand};
 mod utils {
     use super::*;
@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+use super::*;
+
+#[test]
+fn diffs_with_masked_values() {
+    let (_dir, old_path, new_path) = utils::encrypt_files(utils::OLD_PLAINTEXT, utils::NEW_PLAINTEXT, false);
+
+    let output = Command::package_command().diff(&old_path, &new_path).run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "~ hello: *** -> ***\n- nested.removed: ***\n+ nested.added: ***\n",
+        output.stdout_str()
+    )
+}
+
+#[test]
+fn diffs_with_shown_values() {
+    let (_dir, old_path, new_path) = utils::encrypt_files(utils::OLD_PLAINTEXT, utils::NEW_PLAINTEXT, false);
+
+    let mut cmd = Command::package_command().diff(&old_path, &new_path);
+    cmd.arg("--show-values");
+
+    let output = cmd.run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        "~ hello: world -> there\n- nested.removed: a\n+ nested.added: b\n",
+        output.stdout_str()
+    )
+}
+
+#[test]
+fn diffs_metadata() {
+    let (_dir, old_path, new_path) = utils::encrypt_files(utils::OLD_PLAINTEXT, utils::OLD_PLAINTEXT, true);
+
+    let output = Command::package_command().diff(&old_path, &new_path).run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        format!(
+            "~ sops.partial_encryption: none -> unencrypted_suffix={}\n",
+            PartialEncryptionConfig::mock_display()
+        ),
+        output.stdout_str()
+    )
+}
+
+#[test]
+fn diffs_identical_files() {
+    let (_dir, old_path, new_path) = utils::encrypt_files(utils::OLD_PLAINTEXT, utils::OLD_PLAINTEXT, false);
+
+    let output = Command::package_command().diff(&old_path, &new_path).run_tty();
+    output.assert_success();
+    assert!(output.stdout_str().is_empty())
+}
+
+use utils::DiffCommand;
+mod utils {
+    use super::*;
+
+    pub const OLD_PLAINTEXT: &str = "hello: world\nnested:\n  kept: true\n  removed: a\n";
+    pub const NEW_PLAINTEXT: &str = "hello: there\nnested:\n  kept: true\n  added: b\n";
+
+    /// Encrypts both plaintexts with separate data keys, optionally with partial encryption for the new file.
+    pub fn encrypt_files(old_plaintext: &str, new_plaintext: &str, new_partial_encryption: bool) -> (TempDir, PathBuf, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+
+        let old_path = dir.path().join("old.yaml");
+        let old_output = Command::package_command().encrypt().run_piped(old_plaintext);
+        old_output.assert_success();
+        std::fs::write(&old_path, old_output.stdout_str()).unwrap();
+
+        let new_path = dir.path().join("new.yaml");
+        let mut new_cmd = Command::package_command().encrypt();
+        if new_partial_encryption {
+            new_cmd = new_cmd.partial_encryption();
+        }
+        let new_output = new_cmd.run_piped(new_plaintext);
+        new_output.assert_success();
+        std::fs::write(&new_path, new_output.stdout_str()).unwrap();
+
+        (dir, old_path, new_path)
+    }
+
+    pub trait DiffCommand {
+        fn diff(self, old_path: &Path, new_path: &Path) -> Self;
+    }
+
+    impl DiffCommand for Command {
+        fn diff(mut self, old_path: &Path, new_path: &Path) -> Self {
+            AgeIntegration::set_mock_private_key_env_var();
+            self.arg("diff").args([old_path, new_path]);
+            self
+        }
+    }
+}
@@ -22,6 +22,18 @@ fn edits_from_file() {
     pretty_assertions::assert_eq!(EDIT_CONTENT, decrypt_str::<AgeIntegration>(&encrypted_content).map().to_string())
 }
 
+#[test]
+fn edits_keeping_cipher() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--cipher", "xchacha20-poly1305"]);
+    let encrypted_output = cmd.run_piped(sops_yaml_str!("age_example_plaintext"));
+    encrypted_output.assert_success();
+
+    let edited_output = Command::package_command().edit_age().run_piped(encrypted_output.stdout_str());
+    edited_output.assert_success();
+    assert!(edited_output.stdout_str().contains("ENC[XCHACHA20_POLY1305,"));
+}
+
 use utils::{EditCommand, EDIT_CONTENT};
 mod utils {
     use super::*;
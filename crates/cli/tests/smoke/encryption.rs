@@ -54,7 +54,66 @@ fn encrypts_with_mac_encrypted_only() {
     assert_eq!(Some(true), decrypted_file.metadata().mac_only_encrypted)
 }
 
-pub use utils::{assert_encrypted, decrypt_output, decrypt_str, EncryptCommand};
+#[test]
+fn encrypts_multiple_documents() {
+    let output = Command::package_command().encrypt().run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    output.assert_success();
+
+    let encrypted_documents = YamlFileFormat::split_documents(output.stdout_str());
+    assert_eq!(2, encrypted_documents.len());
+
+    for (encrypted_document, plaintext_document) in encrypted_documents
+        .iter()
+        .zip(YamlFileFormat::split_documents(MULTI_DOCUMENT_PLAINTEXT))
+    {
+        pretty_assertions::assert_eq!(
+            plaintext_document,
+            decrypt_str::<AgeIntegration>(encrypted_document).map().to_string()
+        )
+    }
+}
+
+#[test]
+fn encrypts_multiple_documents_with_shared_data_key() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.arg("--shared-data-key");
+
+    let output = cmd.run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    output.assert_success();
+    assert_eq!(2, YamlFileFormat::split_documents(output.stdout_str()).len());
+}
+
+#[test]
+fn encrypts_to_output_format() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--output-format", "json"]);
+
+    let plaintext = sops_yaml_str!("age_example_plaintext");
+    let output = cmd.run_piped(plaintext);
+    output.assert_success();
+
+    AgeIntegration::set_mock_private_key_env_var();
+    let decrypted_file = output
+        .stdout_str()
+        .parse::<RopsFile<EncryptedFile<DefaultCipher, DefaultHasher>, JsonFileFormat>>()
+        .unwrap()
+        .decrypt::<YamlFileFormat>()
+        .unwrap();
+
+    pretty_assertions::assert_eq!(plaintext, decrypted_file.map().to_string())
+}
+
+#[test]
+fn disallows_multiple_documents_in_single_document_output_format() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--output-format", "toml"]);
+
+    let output = cmd.run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    output.assert_failure();
+    assert!(output.stderr_str().contains("2 documents found"));
+}
+
+pub use utils::{assert_encrypted, decrypt_output, decrypt_str, EncryptCommand, MULTI_DOCUMENT_PLAINTEXT};
 pub mod utils {
     use super::*;
 
@@ -77,6 +136,8 @@ pub mod utils {
         pretty_assertions::assert_eq!(expected_plaintext, decrypted_file.map().to_string());
     }
 
+    pub const MULTI_DOCUMENT_PLAINTEXT: &str = "hello: world!\n---\nhello: again!\n";
+
     pub trait EncryptCommand {
         fn encrypt(self) -> Self;
         fn encrypt_in_place(self) -> Self;
@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+
+use super::*;
+
+#[test]
+fn reports_encrypted_files() {
+    let directory = tempfile::tempdir().unwrap();
+    let encrypted_path = utils::write_encrypted(&directory, "encrypted.yaml");
+
+    let output = Command::package_command().filestatus(&[&encrypted_path]).run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(format!("encrypted: {}\n", encrypted_path.display()), output.stdout_str())
+}
+
+#[test]
+fn fails_on_unencrypted_files() {
+    let directory = tempfile::tempdir().unwrap();
+    let encrypted_path = utils::write_encrypted(&directory, "encrypted.yaml");
+    let plaintext_path = directory.path().join("plaintext.yaml");
+    std::fs::write(&plaintext_path, "hello: world!\n").unwrap();
+
+    let output = Command::package_command().filestatus(&[&encrypted_path, &plaintext_path]).run_tty();
+    output.assert_failure();
+    pretty_assertions::assert_eq!(
+        format!("encrypted: {}\nplaintext: {}\n", encrypted_path.display(), plaintext_path.display()),
+        output.stdout_str()
+    )
+}
+
+use utils::FileStatusCommand;
+mod utils {
+    use super::*;
+
+    pub fn write_encrypted(directory: &TempDir, file_name: &str) -> PathBuf {
+        let output = Command::package_command().encrypt().run_piped("hello: world!");
+        output.assert_success();
+
+        let file_path = directory.path().join(file_name);
+        std::fs::write(&file_path, output.stdout_str()).unwrap();
+        file_path
+    }
+
+    pub trait FileStatusCommand {
+        fn filestatus(self, file_paths: &[&Path]) -> Self;
+    }
+
+    impl FileStatusCommand for Command {
+        fn filestatus(mut self, file_paths: &[&Path]) -> Self {
+            // Detection must not require access to any private key.
+            self.env_remove(AgeIntegration::private_key_env_var_name());
+            self.arg("filestatus").args(file_paths);
+            self
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+use super::*;
+
+use super::encryption::utils::decrypt_str;
+
+const PLAINTEXT: &str = "hello: world!\nother: value\n";
+
+#[test]
+fn installs_filter_and_diff_driver() {
+    let repository = utils::init_repository();
+
+    for _ in 0..2 {
+        let mut cmd = Command::package_command();
+        cmd.current_dir(repository.path()).args(["git", "install", "*.enc.yaml"]);
+        cmd.run_tty().assert_success();
+    }
+
+    pretty_assertions::assert_eq!(
+        "*.enc.yaml filter=rops diff=rops merge=rops\n",
+        std::fs::read_to_string(repository.path().join(".gitattributes")).unwrap()
+    );
+    assert_eq!(
+        "rops git filter-clean %f",
+        utils::git(repository.path(), &["config", "filter.rops.clean"]).trim()
+    );
+    assert_eq!(
+        "rops git textconv",
+        utils::git(repository.path(), &["config", "diff.rops.textconv"]).trim()
+    );
+    assert_eq!(
+        "rops git merge-driver --path %P %O %A %B",
+        utils::git(repository.path(), &["config", "merge.rops.driver"]).trim()
+    );
+}
+
+#[test]
+fn cleans_unchanged_plaintext_to_committed_blob() {
+    let repository = utils::init_repository();
+    let committed_str = utils::commit_encrypted(repository.path(), PLAINTEXT);
+
+    let output = Command::package_command().filter_clean(repository.path()).run_piped(PLAINTEXT);
+    output.assert_success();
+    pretty_assertions::assert_eq!(committed_str, output.stdout_str())
+}
+
+#[test]
+fn only_reencrypts_changed_values() {
+    let repository = utils::init_repository();
+    let committed_str = utils::commit_encrypted(repository.path(), PLAINTEXT);
+
+    let output = Command::package_command()
+        .filter_clean(repository.path())
+        .run_piped(&PLAINTEXT.replace("world!", "again!"));
+    output.assert_success();
+
+    let find_line = |encrypted_str: &str, key: &str| encrypted_str.lines().find(|line| line.starts_with(key)).unwrap().to_string();
+    assert_ne!(find_line(&committed_str, "hello:"), find_line(output.stdout_str(), "hello:"));
+    assert_eq!(find_line(&committed_str, "other:"), find_line(output.stdout_str(), "other:"));
+    assert_eq!(
+        "hello: again!\nother: value\n",
+        decrypt_str::<AgeIntegration>(output.stdout_str()).map().to_string()
+    );
+}
+
+#[test]
+fn cleans_uncommitted_file_with_config() {
+    let repository = utils::init_repository();
+    utils::write_config(repository.path());
+
+    let output = Command::package_command().filter_clean(repository.path()).run_piped(PLAINTEXT);
+    output.assert_success();
+    pretty_assertions::assert_eq!(PLAINTEXT, decrypt_str::<AgeIntegration>(output.stdout_str()).map().to_string());
+}
+
+#[test]
+fn encrypts_plaintext_committed_before_installation() {
+    let repository = utils::init_repository();
+    utils::write_config(repository.path());
+    std::fs::write(repository.path().join(utils::FILE_NAME), PLAINTEXT).unwrap();
+    utils::git(repository.path(), &["add", utils::FILE_NAME]);
+    utils::git(repository.path(), &["commit", "--quiet", "--message", "Add plaintext secrets"]);
+
+    let mut cmd = Command::package_command();
+    cmd.current_dir(repository.path()).args(["git", "install", utils::FILE_NAME]);
+    cmd.run_tty().assert_success();
+
+    utils::git(repository.path(), &["add", "--renormalize", utils::FILE_NAME]);
+
+    let staged_str = utils::git(repository.path(), &["show", &format!(":{}", utils::FILE_NAME)]);
+    pretty_assertions::assert_eq!(PLAINTEXT, decrypt_str::<AgeIntegration>(&staged_str).map().to_string());
+}
+
+#[test]
+fn passes_through_encrypted_content() {
+    let repository = utils::init_repository();
+    let encrypted_str = sops_yaml_str!("age_example");
+
+    let output = Command::package_command().filter_clean(repository.path()).run_piped(encrypted_str);
+    output.assert_success();
+    pretty_assertions::assert_eq!(format!("{encrypted_str}\n"), output.stdout_str())
+}
+
+#[test]
+fn smudges_encrypted_content() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let mut cmd = Command::package_command();
+    cmd.args(["git", "filter-smudge", utils::FILE_NAME]);
+
+    let output = cmd.run_piped(sops_yaml_str!("age_example"));
+    output.assert_success();
+    pretty_assertions::assert_eq!(sops_yaml_str!("age_example_plaintext"), output.stdout_str())
+}
+
+#[test]
+fn converts_text_for_diffs() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let mut cmd = Command::package_command();
+    cmd.args(["git", "textconv"]).arg(sops_yaml_path!("age_example"));
+
+    let output = cmd.run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(sops_yaml_str!("age_example_plaintext"), output.stdout_str())
+}
+
+#[test]
+fn merges_changes_of_both_sides() {
+    let versions = utils::write_merge_versions(PLAINTEXT, "hello: there!\nother: value\n", "hello: world!\nother: change\n");
+
+    let output = Command::package_command().merge_driver(&versions).run_tty();
+    output.assert_success();
+
+    let merged_str = std::fs::read_to_string(versions.path().join("ours")).unwrap();
+    pretty_assertions::assert_eq!(
+        "hello: there!\nother: change\n",
+        decrypt_str::<AgeIntegration>(&merged_str).map().to_string()
+    );
+}
+
+#[test]
+fn reports_merge_conflicts() {
+    let versions = utils::write_merge_versions(PLAINTEXT, "hello: there!\nother: change\n", "hello: again!\nother: value\n");
+
+    let output = Command::package_command().merge_driver(&versions).output().unwrap();
+    output.assert_failure();
+
+    let merged_str = std::fs::read_to_string(versions.path().join("ours")).unwrap();
+    pretty_assertions::assert_eq!(
+        "hello: there!\nother: change\n",
+        decrypt_str::<AgeIntegration>(&merged_str).map().to_string()
+    );
+
+    let conflicts_path = versions.path().join(format!(".git/rops/conflicts/{}.conflicts", utils::FILE_NAME));
+    assert!(output.stderr_str().contains(&conflicts_path.display().to_string()));
+    assert!(!versions.path().join(format!("{}.conflicts", utils::FILE_NAME)).exists());
+    pretty_assertions::assert_eq!(
+        "<<<<<<< ours\nhello: there!\n||||||| base\nhello: world!\n=======\nhello: again!\n>>>>>>> theirs\n",
+        std::fs::read_to_string(conflicts_path).unwrap()
+    );
+}
+
+#[test]
+fn merges_without_common_ancestor() {
+    let versions = utils::write_merge_versions(PLAINTEXT, "hello: world!\nours: value\n", "hello: world!\ntheirs: value\n");
+    std::fs::write(versions.path().join("base"), "").unwrap();
+
+    let output = Command::package_command().merge_driver(&versions).run_tty();
+    output.assert_success();
+
+    let merged_str = std::fs::read_to_string(versions.path().join("ours")).unwrap();
+    pretty_assertions::assert_eq!(
+        "hello: world!\nours: value\ntheirs: value\n",
+        decrypt_str::<AgeIntegration>(&merged_str).map().to_string()
+    );
+}
+
+use utils::GitFilterCommand;
+mod utils {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    pub const FILE_NAME: &str = "secrets.yaml";
+
+    pub fn init_repository() -> TempDir {
+        let repository = tempfile::tempdir().unwrap();
+        git(repository.path(), &["init", "--quiet"]);
+        repository
+    }
+
+    /// The package binary is put first in `PATH` for installed filters to find it.
+    pub fn git(repository_path: &Path, args: &[&str]) -> String {
+        let package_dir = PathBuf::from(Command::package_command().get_program())
+            .parent()
+            .unwrap()
+            .to_path_buf();
+        let path =
+            std::env::join_paths(std::iter::once(package_dir).chain(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())))
+                .unwrap();
+
+        let output = Command::new("git")
+            .current_dir(repository_path)
+            .env("PATH", path)
+            .args(["-c", "user.name=rops", "-c", "user.email=rops@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        output.assert_success();
+        output.stdout_str().to_string()
+    }
+
+    pub fn write_config(repository_path: &Path) {
+        let config = format!(
+            "[[creation_rules]]\npath_regex = \".*\"\nintegration_keys.age = [\"{}\"]\n",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        );
+        std::fs::write(repository_path.join(".rops.toml"), config).unwrap();
+    }
+
+    /// Returns the committed blob.
+    pub fn commit_encrypted(repository_path: &Path, plaintext: &str) -> String {
+        write_config(repository_path);
+        let output = Command::package_command().filter_clean(repository_path).run_piped(plaintext);
+        output.assert_success();
+
+        let encrypted_str = output.stdout_str();
+        std::fs::write(repository_path.join(FILE_NAME), encrypted_str).unwrap();
+        git(repository_path, &["add", FILE_NAME]);
+        git(repository_path, &["commit", "--quiet", "--message", "Add secrets"]);
+
+        encrypted_str.to_string()
+    }
+
+    /// Encrypts each version with its own data key into the files 'base', 'ours' and 'theirs' of a
+    /// new repository.
+    pub fn write_merge_versions(base_plaintext: &str, ours_plaintext: &str, theirs_plaintext: &str) -> TempDir {
+        let versions = init_repository();
+
+        for (file_name, plaintext) in [("base", base_plaintext), ("ours", ours_plaintext), ("theirs", theirs_plaintext)] {
+            let output = Command::package_command().encrypt().run_piped(plaintext);
+            output.assert_success();
+            std::fs::write(versions.path().join(file_name), output.stdout_str()).unwrap();
+        }
+
+        versions
+    }
+
+    pub trait GitFilterCommand {
+        fn filter_clean(self, repository_path: &Path) -> Self;
+        fn merge_driver(self, versions: &TempDir) -> Self;
+    }
+
+    impl GitFilterCommand for Command {
+        fn filter_clean(mut self, repository_path: &Path) -> Self {
+            AgeIntegration::set_mock_private_key_env_var();
+            self.current_dir(repository_path).args(["git", "filter-clean", FILE_NAME]);
+            self
+        }
+
+        fn merge_driver(mut self, versions: &TempDir) -> Self {
+            AgeIntegration::set_mock_private_key_env_var();
+            self.current_dir(versions.path())
+                .args(["git", "merge-driver", "--path", FILE_NAME, "base", "ours", "theirs"]);
+            self
+        }
+    }
+}
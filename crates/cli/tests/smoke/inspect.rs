@@ -0,0 +1,68 @@
+use super::{encryption::MULTI_DOCUMENT_PLAINTEXT, *};
+
+#[test]
+fn inspects_metadata() {
+    let output = Command::package_command().inspect(&[]).run_piped(sops_yaml_str!("age_example"));
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        format!(
+            "cipher: AES256_GCM
+hasher: SHA512
+last modified: 2023-12-25T10:31:01Z
+partial encryption: none
+mac only encrypted: false
+encrypt empty values: false
+
+INTEGRATION  KEY ID                                                          CREATED AT
+age          {}  -
+",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        ),
+        output.stdout_str()
+    )
+}
+
+#[test]
+fn inspects_metadata_as_json() {
+    let output = Command::package_command()
+        .inspect(&["--json"])
+        .run_piped(sops_yaml_str!("age_example"));
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        format!(
+            "{{\"integration_keys\":[{{\"integration\":\"age\",\"key_id\":\"{}\",\"created_at\":null}}],\"last_modified\":\"2023-12-25T10:31:01Z\",\"partial_encryption\":null,\"mac_only_encrypted\":false,\"encrypt_empty_values\":false,\"cipher\":\"AES256_GCM\",\"hasher\":\"SHA512\"}}\n",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        ),
+        output.stdout_str()
+    )
+}
+
+#[test]
+fn inspects_multiple_documents() {
+    let encrypted_output = Command::package_command().encrypt().run_piped(MULTI_DOCUMENT_PLAINTEXT);
+    encrypted_output.assert_success();
+
+    let output = Command::package_command()
+        .inspect(&["--json"])
+        .run_piped(encrypted_output.stdout_str());
+    output.assert_success();
+    assert_eq!(2, output.stdout_str().lines().count())
+}
+
+use utils::InspectCommand;
+mod utils {
+    use super::*;
+
+    pub trait InspectCommand {
+        fn inspect(self, args: &[&str]) -> Self;
+    }
+
+    impl InspectCommand for Command {
+        fn inspect(mut self, args: &[&str]) -> Self {
+            // Inspection must not require access to any private key.
+            self.env_remove(AgeIntegration::private_key_env_var_name());
+            self.arg("inspect").args(args);
+            self.format_args()
+        }
+    }
+}
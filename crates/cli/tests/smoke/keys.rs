@@ -23,6 +23,48 @@ fn removes_keys() {
     assert_eq!(1, updated_rops_file(encrypted_temp_file.path()).metadata().intregation.age.len());
 }
 
+#[test]
+fn removes_keys_keeping_cipher() {
+    let mut cmd = Command::package_command().encrypt();
+    cmd.args(["--cipher", "xchacha20-poly1305"]);
+    let encrypted_output = cmd.run_piped(sops_yaml_str!("age_example_plaintext"));
+    encrypted_output.assert_success();
+
+    let encrypted_temp_file = NamedTempFile::new().unwrap();
+    std::fs::write(encrypted_temp_file.path(), encrypted_output.stdout_str()).unwrap();
+
+    add_age_key_command(encrypted_temp_file.path());
+    remove_age_key_command(encrypted_temp_file.path());
+
+    let updated_rops_file = std::fs::read_to_string(encrypted_temp_file.path())
+        .unwrap()
+        .parse::<AnyRopsFile<YamlFileFormat>>()
+        .unwrap();
+
+    assert_eq!("XCHACHA20_POLY1305", updated_rops_file.cipher_name());
+}
+
+#[test]
+fn lists_keys() {
+    let encrypted_temp_file = encrypted_tempfile();
+
+    let mut cmd = Command::package_command();
+    cmd.env_remove(AgeIntegration::private_key_env_var_name());
+    cmd.args(["keys", "list", "--json"]);
+    let mut cmd = cmd.format_args();
+    cmd.arg(encrypted_temp_file.path());
+
+    let output = cmd.run_tty();
+    output.assert_success();
+    pretty_assertions::assert_eq!(
+        format!(
+            "[{{\"integration\":\"age\",\"key_id\":\"{}\",\"created_at\":null}}]\n",
+            <AgeIntegration as Integration>::KeyId::mock_display()
+        ),
+        output.stdout_str()
+    )
+}
+
 fn add_age_key_command(file_path: &Path) {
     let mut cmd = base();
     cmd.arg("add");
@@ -1,5 +1,5 @@
 use rops::{
-    file::{format::*, metadata::*, state::*, RopsFile},
+    file::{format::*, metadata::*, state::*, AnyRopsFile, RopsFile},
     integration::*,
     test_utils::*,
 };
@@ -22,6 +22,18 @@ mod config;
 
 mod refresh;
 
+mod git;
+
+mod diff;
+
+mod batch;
+
+mod check;
+
+mod inspect;
+
+mod filestatus;
+
 test_binary::build_test_binary_once!(mock_editor, "test_bins");
 
 mod command_utils;
@@ -74,7 +74,35 @@ fn skips_refresh_if_possible() {
     assert_eq!(first_rops_file, second_rops_file)
 }
 
-use utils::{initial_rops_file, refreshed_rops_file, store_config_other, store_rops_file, RefreshCommand, TEST_ENCRYPTED_ROPS_FILE};
+#[test]
+fn keeps_encrypt_empty_values_unset_by_rule() {
+    let mut encrypt_cmd = Command::package_command().encrypt();
+    encrypt_cmd.arg("--encrypt-empty-values");
+    let encrypted_output = encrypt_cmd.run_piped("hello: \"\"\n");
+    encrypted_output.assert_success();
+
+    let mut config = Config::mock_other();
+    config.creation_rules[0].encrypt_empty_values = None;
+    let temp_config_file = NamedTempFile::new().unwrap();
+    store_config(temp_config_file.path(), &config);
+
+    let output = Command::package_command()
+        .refresh(temp_config_file.path())
+        .run_piped(encrypted_output.stdout_str());
+
+    let refreshed_rops_file = refreshed_rops_file(&output);
+    assert!(refreshed_rops_file
+        .metadata()
+        .intregation
+        .age
+        .contains_key(&<<AgeIntegration as Integration>::KeyId>::mock_other()));
+    assert_eq!(Some(true), refreshed_rops_file.metadata().encrypt_empty_values);
+}
+
+use super::encryption::EncryptCommand;
+use utils::{
+    initial_rops_file, refreshed_rops_file, store_config, store_config_other, store_rops_file, RefreshCommand, TEST_ENCRYPTED_ROPS_FILE,
+};
 mod utils {
     use std::path::Path;
 
@@ -98,11 +126,11 @@ mod utils {
     }
 
     pub fn store_config_other(config_path: &Path) {
-        std::fs::write(
-            config_path,
-            DefaulConfigFileFormat::serialize_to_string(&Config::mock_other()).unwrap(),
-        )
-        .unwrap()
+        store_config(config_path, &Config::mock_other())
+    }
+
+    pub fn store_config(config_path: &Path, config: &Config) {
+        std::fs::write(config_path, DefaulConfigFileFormat::serialize_to_string(config).unwrap()).unwrap()
     }
 
     pub fn store_rops_file(rops_file_path: &Path) {
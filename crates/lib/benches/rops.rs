@@ -0,0 +1,200 @@
+// Synthetic maps of varying depth, width and value size, encrypted with the mock age key so
+// that no network access is needed. Run with `cargo bench -p rops --features test-utils`, adding
+// `parallel` to compare sequential and parallel map traversal.
+
+use criterion::{criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use rops::{
+    cryptography::{cipher::AES256GCM, hasher::SHA512},
+    file::{builder::RopsFileBuilder, format::*, state::EncryptedFile, RopsFile},
+    integration::{AgeIntegration, Integration, IntegrationTestUtils},
+    test_utils::{BenchMap, MockTestUtil},
+};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+type EncryptedJsonFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, JsonFileFormat>;
+
+struct Shape {
+    name: &'static str,
+    depth: u32,
+    width: usize,
+    value_size: usize,
+}
+
+const SHAPES: &[Shape] = &[
+    Shape {
+        name: "wide",
+        depth: 1,
+        width: 1024,
+        value_size: 32,
+    },
+    Shape {
+        name: "deep",
+        depth: 8,
+        width: 2,
+        value_size: 32,
+    },
+    Shape {
+        name: "large_values",
+        depth: 1,
+        width: 16,
+        value_size: 64 * 1024,
+    },
+];
+
+impl Shape {
+    fn leaf_count(&self) -> u64 {
+        self.width.pow(self.depth) as u64
+    }
+
+    fn json_map(&self) -> JsonMap<String, JsonValue> {
+        return build(self.depth, self.width, self.value_size);
+
+        fn build(depth: u32, width: usize, value_size: usize) -> JsonMap<String, JsonValue> {
+            (0..width)
+                .map(|index| {
+                    let value = match depth {
+                        0 | 1 => leaf(index, value_size),
+                        _ => JsonValue::Object(build(depth - 1, width, value_size)),
+                    };
+                    (format!("key_{index}"), value)
+                })
+                .collect()
+        }
+
+        fn leaf(index: usize, value_size: usize) -> JsonValue {
+            match index % 3 {
+                0 => JsonValue::Bool(true),
+                1 => JsonValue::from(index),
+                _ => JsonValue::String("x".repeat(value_size)),
+            }
+        }
+    }
+
+    fn builder(&self) -> RopsFileBuilder<JsonFileFormat> {
+        RopsFileBuilder::from_map(self.json_map()).add_integration_key::<AgeIntegration>(<AgeIntegration as Integration>::KeyId::mock())
+    }
+
+    fn encrypted_file_str(&self) -> String {
+        self.builder().encrypt::<AES256GCM, SHA512>().unwrap().to_string()
+    }
+}
+
+fn shape_group<'a>(criterion: &'a mut Criterion, name: &str, shape: &Shape) -> BenchmarkGroup<'a, WallTime> {
+    let mut group = criterion.benchmark_group(format!("{name}/{}", shape.name));
+    group.throughput(Throughput::Elements(shape.leaf_count()));
+    group
+}
+
+fn encrypt(criterion: &mut Criterion) {
+    for shape in SHAPES {
+        let mut group = shape_group(criterion, "encrypt", shape);
+        group.bench_function("builder", |bencher| {
+            bencher.iter_batched(
+                || shape.builder(),
+                |builder| builder.encrypt::<AES256GCM, SHA512>().unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn decrypt(criterion: &mut Criterion) {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    for shape in SHAPES {
+        let encrypted_file_str = shape.encrypted_file_str();
+        let parse = || encrypted_file_str.parse::<EncryptedJsonFile>().unwrap();
+
+        let mut group = shape_group(criterion, "decrypt", shape);
+        group.bench_function("decrypt", |bencher| {
+            bencher.iter_batched(parse, |file| file.decrypt::<JsonFileFormat>().unwrap(), BatchSize::LargeInput)
+        });
+        group.bench_function("decrypt_and_save_parameters", |bencher| {
+            bencher.iter_batched(
+                parse,
+                |file| file.decrypt_and_save_parameters::<JsonFileFormat>().unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn mac(criterion: &mut Criterion) {
+    for shape in SHAPES {
+        let map = BenchMap::from_format_map::<JsonFileFormat>(shape.json_map()).unwrap();
+
+        let mut group = shape_group(criterion, "mac", shape);
+        for mac_only_encrypted in [false, true] {
+            group.bench_with_input(
+                BenchmarkId::new("compute", format!("mac_only_encrypted={mac_only_encrypted}")),
+                &mac_only_encrypted,
+                |bencher, mac_only_encrypted| bencher.iter(|| map.compute_mac::<SHA512>(*mac_only_encrypted)),
+            );
+        }
+        group.finish();
+    }
+}
+
+fn format_adapters(criterion: &mut Criterion) {
+    for shape in SHAPES {
+        let mut group = shape_group(criterion, "format", shape);
+        bench_format::<JsonFileFormat>(&mut group, "json", shape);
+        bench_format::<YamlFileFormat>(&mut group, "yaml", shape);
+        bench_format::<TomlFileFormat>(&mut group, "toml", shape);
+        bench_format::<TomlEditFileFormat>(&mut group, "toml_edit", shape);
+        group.finish();
+    }
+
+    fn bench_format<F: FileFormat>(group: &mut BenchmarkGroup<'_, WallTime>, format_name: &str, shape: &Shape)
+    where
+        F::Map: Clone,
+    {
+        let internal_map = || BenchMap::from_format_map::<JsonFileFormat>(shape.json_map()).unwrap();
+        let format_map = internal_map().into_format_map::<F>().unwrap();
+
+        group.bench_function(BenchmarkId::new("to_internal", format_name), |bencher| {
+            bencher.iter_batched(
+                || format_map.clone(),
+                |format_map| BenchMap::from_format_map::<F>(format_map).unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(BenchmarkId::new("from_internal", format_name), |bencher| {
+            bencher.iter_batched(
+                internal_map,
+                |internal_map| internal_map.into_format_map::<F>().unwrap(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn traversal(criterion: &mut Criterion) {
+    for shape in SHAPES {
+        let internal_map = || BenchMap::from_format_map::<JsonFileFormat>(shape.json_map()).unwrap();
+
+        let mut group = shape_group(criterion, "traversal", shape);
+        for (traversal_name, parallel) in [("sequential", false), ("parallel", true)] {
+            group.bench_function(BenchmarkId::new("encrypt", traversal_name), |bencher| {
+                bencher.iter_batched(internal_map, |map| map.encrypt(parallel), BatchSize::LargeInput)
+            });
+            group.bench_function(BenchmarkId::new("decrypt", traversal_name), |bencher| {
+                bencher.iter_batched(
+                    || internal_map().encrypt(parallel),
+                    |encrypted_map| encrypted_map.decrypt(parallel),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn traversal(_criterion: &mut Criterion) {}
+
+criterion_group!(benches, encrypt, decrypt, mac, format_adapters, traversal);
+criterion_main!(benches);
@@ -1,6 +1,5 @@
 use std::str::FromStr;
 
-use aes_gcm::Tag;
 use derive_more::{AsMut, From};
 use generic_array::GenericArray;
 
@@ -11,8 +10,8 @@ use crate::*;
 #[impl_tools::autoimpl(Debug, PartialEq)]
 pub struct AuthorizationTag<C: Cipher>(GenericArray<u8, C::AuthorizationTagSize>);
 
-impl<C: Cipher> AsRef<Tag<C::AuthorizationTagSize>> for AuthorizationTag<C> {
-    fn as_ref(&self) -> &Tag<C::AuthorizationTagSize> {
+impl<C: Cipher> AsRef<GenericArray<u8, C::AuthorizationTagSize>> for AuthorizationTag<C> {
+    fn as_ref(&self) -> &GenericArray<u8, C::AuthorizationTagSize> {
         &self.0
     }
 }
@@ -4,7 +4,7 @@ use generic_array::ArrayLength;
 
 use crate::*;
 
-pub trait Cipher: Sized + private::SealedCipher {
+pub trait Cipher: Sized + Send + Sync + private::SealedCipher {
     const NAME: &'static str;
 
     type NonceSize: ArrayLength<u8> + Debug + PartialEq;
@@ -35,6 +35,9 @@ mod private {
     #[cfg(feature = "aes-gcm")]
     impl SealedCipher for crate::AES256GCM {}
 
+    #[cfg(feature = "chacha20-poly1305")]
+    impl SealedCipher for crate::XCHACHA20POLY1305 {}
+
     #[cfg(feature = "test-utils")]
     impl SealedCipher for crate::StubCipher {}
 }
@@ -1,11 +1,16 @@
 mod core;
-pub(crate) use core::Cipher;
+pub use core::Cipher;
 
 #[cfg(feature = "aes-gcm")]
 mod aes256_gcm;
 #[cfg(feature = "aes-gcm")]
 pub use aes256_gcm::AES256GCM;
 
+#[cfg(feature = "chacha20-poly1305")]
+mod xchacha20_poly1305;
+#[cfg(feature = "chacha20-poly1305")]
+pub use xchacha20_poly1305::XCHACHA20POLY1305;
+
 #[cfg(feature = "test-utils")]
 mod stub_cipher;
 #[cfg(feature = "test-utils")]
@@ -1,22 +1,34 @@
-use derive_more::{AsMut, AsRef};
-use generic_array::{
-    typenum::{Unsigned, U32},
-    GenericArray,
-};
-use zeroize::Zeroize;
+use generic_array::typenum::{Unsigned, U32};
+use rand::RngCore;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::*;
 
 type DataKeySize = U32;
 
-#[derive(Debug, PartialEq, AsRef, AsMut)]
-#[as_ref(forward)]
-#[as_mut(forward)]
-pub struct DataKey(RngKey<DataKeySize>);
+// Boxed so that moving the key around (e.g. within `SavedParameters`) doesn't leave unzeroized
+// copies of it behind on the stack. Memory is locked on creation if the `mlock` feature is enabled.
+#[derive(Debug, PartialEq)]
+pub struct DataKey(Box<RngKey<DataKeySize>>);
 
 impl Drop for DataKey {
     fn drop(&mut self) {
-        self.0 .0.zeroize()
+        self.0 .0.zeroize();
+        unlock_memory(self.as_ref());
+    }
+}
+
+impl ZeroizeOnDrop for DataKey {}
+
+impl AsRef<[u8]> for DataKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0 .0.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for DataKey {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.0 .0.as_mut_slice()
     }
 }
 
@@ -27,11 +39,16 @@ impl DataKey {
 
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        DataKey(RngKey::new())
+        // Filled in place rather than through `RngKey::new()` to avoid intermediate stack copies.
+        let mut data_key = Self::empty();
+        rand::rng().fill_bytes(data_key.as_mut());
+        data_key
     }
 
     pub fn empty() -> Self {
-        Self(RngKey::empty())
+        let data_key = Self(Box::new(RngKey::empty()));
+        lock_memory(data_key.as_ref());
+        data_key
     }
 }
 
@@ -42,29 +59,53 @@ pub struct DataKeySizeError(usize, usize);
 impl TryFrom<Vec<u8>> for DataKey {
     type Error = DataKeySizeError;
 
-    fn try_from(vec: Vec<u8>) -> Result<Self, Self::Error> {
+    fn try_from(mut vec: Vec<u8>) -> Result<Self, Self::Error> {
         if vec.len() != DataKey::byte_size() {
             return Err(DataKeySizeError(DataKey::byte_size(), vec.len()));
         }
 
-        GenericArray::from_exact_iter(vec)
-            .map(|array| Ok(Self(RngKey(array))))
-            .expect("invalid data key size assertion")
+        let mut data_key = Self::empty();
+        data_key.as_mut().copy_from_slice(&vec);
+        vec.zeroize();
+
+        Ok(data_key)
     }
 }
 
 #[cfg(feature = "test-utils")]
 mod mock {
-    use generic_array::GenericArray;
-
     use crate::*;
 
     impl MockTestUtil for DataKey {
         fn mock() -> Self {
-            Self(RngKey(GenericArray::from([
+            let mut data_key = Self::empty();
+            data_key.as_mut().copy_from_slice(&[
                 254, 79, 93, 103, 195, 165, 169, 238, 35, 187, 236, 95, 222, 243, 40, 26, 130, 128, 59, 176, 15, 195, 55, 93, 129, 212, 57,
                 80, 15, 181, 72, 114,
-            ])))
+            ]);
+            data_key
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_data_key_not_zeroed() {
+        assert_ne!(&[0; DataKeySize::USIZE], DataKey::new().as_ref())
+    }
+
+    #[test]
+    fn zeroizes_on_drop() {
+        let data_key = DataKey::new();
+        let ptr = data_key.as_ref().as_ptr();
+        assert!(zeroed_on_drop(data_key, ptr, DataKey::byte_size()))
+    }
+
+    #[test]
+    fn converts_from_vec() {
+        assert_eq!(DataKey::mock(), DataKey::try_from(DataKey::mock().as_ref().to_vec()).unwrap())
+    }
+}
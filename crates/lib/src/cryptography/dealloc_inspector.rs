@@ -0,0 +1,68 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+// Test-only global allocator for inspecting whether secrets are zeroized before their memory is
+// handed back to the system allocator.
+#[global_allocator]
+static ALLOCATOR: InspectingAllocator = InspectingAllocator;
+
+static WATCHED_PTR: AtomicUsize = AtomicUsize::new(0);
+static WATCHED_LEN: AtomicUsize = AtomicUsize::new(0);
+static OUTCOME: AtomicU8 = AtomicU8::new(UNOBSERVED);
+static INSPECTION_LOCK: Mutex<()> = Mutex::new(());
+
+const UNOBSERVED: u8 = 0;
+const ZEROED: u8 = 1;
+const NOT_ZEROED: u8 = 2;
+
+struct InspectingAllocator;
+
+unsafe impl GlobalAlloc for InspectingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if WATCHED_PTR
+            .compare_exchange(ptr as usize, 0, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+        {
+            // SAFETY: the watched range lies within the allocation that is yet to be deallocated.
+            let bytes = unsafe { std::slice::from_raw_parts(ptr, WATCHED_LEN.load(Ordering::Acquire)) };
+            let outcome = match bytes.iter().all(|byte| *byte == 0) {
+                true => ZEROED,
+                false => NOT_ZEROED,
+            };
+            OUTCOME.store(outcome, Ordering::Release);
+        }
+
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+/// Drops `value` and returns whether the `len` bytes at the start of the heap allocation at `ptr`
+/// were all zero by the time that allocation was deallocated.
+///
+/// Panics if the allocation isn't deallocated when `value` is dropped.
+pub fn zeroed_on_drop<T>(value: T, ptr: *const u8, len: usize) -> bool {
+    let _guard = INSPECTION_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    OUTCOME.store(UNOBSERVED, Ordering::Release);
+    WATCHED_LEN.store(len, Ordering::Release);
+    WATCHED_PTR.store(ptr as usize, Ordering::Release);
+
+    drop(value);
+
+    WATCHED_PTR.store(0, Ordering::Release);
+
+    match OUTCOME.load(Ordering::Acquire) {
+        ZEROED => true,
+        NOT_ZEROED => false,
+        _ => panic!("watched allocation was not deallocated when dropping value"),
+    }
+}
@@ -0,0 +1,40 @@
+use generic_array::{typenum::U32, GenericArray};
+
+use crate::*;
+
+/// Not supported by SOPS.
+#[derive(Debug)]
+pub struct BLAKE3(::blake3::Hasher);
+
+impl Hasher for BLAKE3 {
+    const NAME: &'static str = "BLAKE3";
+
+    type OutputSize = U32;
+
+    fn new() -> Self {
+        Self(::blake3::Hasher::new())
+    }
+
+    fn update(&mut self, input: impl AsRef<[u8]>) {
+        self.0.update(input.as_ref());
+    }
+
+    fn finalize(self) -> GenericArray<u8, Self::OutputSize> {
+        GenericArray::from(*self.0.finalize().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_digest() {
+        let mut hasher = BLAKE3::new();
+        hasher.update("abc");
+        assert_eq!(
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+            hex::encode(hasher.finalize())
+        )
+    }
+}
@@ -1,6 +1,8 @@
 use generic_array::{ArrayLength, GenericArray};
 
 pub trait Hasher: private::SealedHasher {
+    const NAME: &'static str;
+
     type OutputSize: ArrayLength<u8>;
 
     fn new() -> Self;
@@ -15,4 +17,8 @@ mod private {
 
     #[cfg(feature = "sha2")]
     impl SealedHasher for crate::SHA512 {}
+    #[cfg(feature = "sha2")]
+    impl SealedHasher for crate::SHA256 {}
+    #[cfg(feature = "blake3")]
+    impl SealedHasher for crate::BLAKE3 {}
 }
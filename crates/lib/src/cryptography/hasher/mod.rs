@@ -1,7 +1,17 @@
 mod core;
-pub(crate) use core::Hasher;
+pub use core::Hasher;
 
 #[cfg(feature = "sha2")]
 mod sha512;
 #[cfg(feature = "sha2")]
 pub use sha512::SHA512;
+
+#[cfg(feature = "sha2")]
+mod sha256;
+#[cfg(feature = "sha2")]
+pub use sha256::SHA256;
+
+#[cfg(feature = "blake3")]
+mod blake3;
+#[cfg(feature = "blake3")]
+pub use blake3::BLAKE3;
@@ -0,0 +1,40 @@
+use sha2::{digest::OutputSizeUser, Digest, Sha256};
+
+use crate::*;
+
+/// Not supported by SOPS.
+#[derive(Debug)]
+pub struct SHA256(Sha256);
+
+impl Hasher for SHA256 {
+    const NAME: &'static str = "SHA256";
+
+    type OutputSize = <Sha256 as OutputSizeUser>::OutputSize;
+
+    fn new() -> Self {
+        Self(Sha256::new())
+    }
+
+    fn update(&mut self, input: impl AsRef<[u8]>) {
+        self.0.update(input)
+    }
+
+    fn finalize(self) -> generic_array::GenericArray<u8, Self::OutputSize> {
+        self.0.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_digest() {
+        let mut hasher = SHA256::new();
+        hasher.update("abc");
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            hex::encode(hasher.finalize())
+        )
+    }
+}
@@ -6,6 +6,8 @@ use crate::*;
 pub struct SHA512(Sha512);
 
 impl Hasher for SHA512 {
+    const NAME: &'static str = "SHA512";
+
     type OutputSize = <Sha512 as OutputSizeUser>::OutputSize;
 
     fn new() -> Self {
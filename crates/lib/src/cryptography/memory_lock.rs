@@ -0,0 +1,29 @@
+// Best-effort page locking of secret buffers, keeping them from being swapped out to disk. Only
+// performed on unix targets with the `mlock` feature enabled, a no-op otherwise. Failures (e.g.
+// from exceeding RLIMIT_MEMLOCK) are ignored since locking is a hardening measure rather than a
+// precondition for encryption or decryption.
+//
+// NOTE: `munlock` works on whole pages, so unlocking one buffer also unlocks any other locked
+// buffer sharing its pages.
+
+pub(crate) fn lock_memory(bytes: &[u8]) {
+    #[cfg(all(unix, feature = "mlock"))]
+    if !bytes.is_empty() {
+        // SAFETY: pointer and length are taken from a live slice.
+        unsafe { libc::mlock(bytes.as_ptr().cast(), bytes.len()) };
+    }
+
+    #[cfg(not(all(unix, feature = "mlock")))]
+    let _ = bytes;
+}
+
+pub(crate) fn unlock_memory(bytes: &[u8]) {
+    #[cfg(all(unix, feature = "mlock"))]
+    if !bytes.is_empty() {
+        // SAFETY: pointer and length are taken from a live slice.
+        unsafe { libc::munlock(bytes.as_ptr().cast(), bytes.len()) };
+    }
+
+    #[cfg(not(all(unix, feature = "mlock")))]
+    let _ = bytes;
+}
@@ -18,3 +18,14 @@ pub(crate) use cipher::*;
 
 pub mod hasher;
 pub(crate) use hasher::*;
+
+mod memory_lock;
+pub(crate) use memory_lock::{lock_memory, unlock_memory};
+
+mod secret_buffer;
+pub(crate) use secret_buffer::SecretBuffer;
+
+#[cfg(test)]
+mod dealloc_inspector;
+#[cfg(test)]
+pub(crate) use dealloc_inspector::zeroed_on_drop;
@@ -0,0 +1,59 @@
+use std::ops::{Deref, DerefMut};
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::*;
+
+/// Fixed length byte buffer for plaintext secrets, such as the in-place buffers used when
+/// encrypting and decrypting values. Memory is locked on creation if the `mlock` feature is
+/// enabled, and always zeroized before being deallocated.
+pub(crate) struct SecretBuffer(Vec<u8>);
+
+impl SecretBuffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        lock_memory(&bytes);
+        Self(bytes)
+    }
+}
+
+impl Deref for SecretBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SecretBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Drop for SecretBuffer {
+    fn drop(&mut self) {
+        self.0.as_mut_slice().zeroize();
+        unlock_memory(&self.0);
+    }
+}
+
+impl ZeroizeOnDrop for SecretBuffer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeroizes_on_drop() {
+        let secret_buffer = SecretBuffer::new(b"secret".to_vec());
+        let (ptr, len) = (secret_buffer.as_ptr(), secret_buffer.len());
+        assert!(zeroed_on_drop(secret_buffer, ptr, len))
+    }
+
+    #[test]
+    fn inspects_unzeroized_drop() {
+        let vec = b"secret".to_vec();
+        let (ptr, len) = (vec.as_ptr(), vec.len());
+        assert!(!zeroed_on_drop(vec, ptr, len))
+    }
+}
@@ -0,0 +1,457 @@
+use std::{fmt::Display, str::FromStr};
+
+use indexmap::IndexSet;
+use serde::{
+    de::{self, DeserializeSeed, EnumAccess, IgnoredAny, MapAccess, SeqAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::*;
+
+/// Cipher names of the enabled cipher features, in the order they are tried.
+pub const SUPPORTED_CIPHER_NAMES: &[&str] = &[
+    #[cfg(feature = "aes-gcm")]
+    AES256GCM::NAME,
+    #[cfg(feature = "chacha20-poly1305")]
+    XCHACHA20POLY1305::NAME,
+];
+
+/// Hasher names of the enabled hasher features, in the order they are tried.
+pub const SUPPORTED_HASHER_NAMES: &[&str] = &[
+    #[cfg(feature = "sha2")]
+    SHA512::NAME,
+    #[cfg(feature = "sha2")]
+    SHA256::NAME,
+    #[cfg(feature = "blake3")]
+    BLAKE3::NAME,
+];
+
+/// An encrypted rops file of which the cipher and hasher are determined at runtime. The cipher by
+/// the name found in the `ENC[...]` string of its MAC, and the hasher by the `mac_hasher` metadata
+/// entry, SHA512 if absent. Encrypted values naming another cipher are rejected up front.
+#[derive(Debug, PartialEq)]
+pub enum AnyRopsFile<F: FileFormat> {
+    #[cfg(feature = "aes-gcm")]
+    Aes256Gcm(AnyHasherRopsFile<AES256GCM, F>),
+    #[cfg(feature = "chacha20-poly1305")]
+    XChaCha20Poly1305(AnyHasherRopsFile<XCHACHA20POLY1305, F>),
+}
+
+/// The hasher dispatch of an [`AnyRopsFile`] once its cipher is known.
+#[derive(Debug, PartialEq)]
+pub enum AnyHasherRopsFile<C: Cipher, F: FileFormat> {
+    #[cfg(feature = "sha2")]
+    Sha512(RopsFile<EncryptedFile<C, SHA512>, F>),
+    #[cfg(feature = "sha2")]
+    Sha256(RopsFile<EncryptedFile<C, SHA256>, F>),
+    #[cfg(feature = "blake3")]
+    Blake3(RopsFile<EncryptedFile<C, BLAKE3>, F>),
+}
+
+/// An operation generic over the cipher and hasher of an encrypted rops file, applied through
+/// [`AnyRopsFile::visit`].
+pub trait RopsFileVisitor<F: FileFormat> {
+    type Output;
+
+    fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnyRopsFileFromStrError {
+    #[error("unable to find the cipher name of an encrypted MAC")]
+    MissingCipherName,
+    #[error("encrypted values name the cipher(s) {values}, whereas the MAC names {mac}", values = values.join(", "))]
+    MismatchedCipher { mac: String, values: Vec<String> },
+    #[error("unsupported cipher: {0}, supported: {supported}", supported = SUPPORTED_CIPHER_NAMES.join(", "))]
+    UnsupportedCipher(String),
+    #[error("unsupported MAC hasher: {0}, supported: {supported}", supported = SUPPORTED_HASHER_NAMES.join(", "))]
+    UnsupportedHasher(String),
+    #[error(transparent)]
+    FromStr(#[from] RopsFileFromStrError),
+}
+
+impl<F: FileFormat> AnyRopsFile<F> {
+    pub fn visit<V: RopsFileVisitor<F>>(self, visitor: V) -> V::Output {
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            AnyRopsFile::Aes256Gcm(rops_file) => rops_file.visit(visitor),
+            #[cfg(feature = "chacha20-poly1305")]
+            AnyRopsFile::XChaCha20Poly1305(rops_file) => rops_file.visit(visitor),
+        }
+    }
+
+    pub fn cipher_name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            AnyRopsFile::Aes256Gcm(_) => AES256GCM::NAME,
+            #[cfg(feature = "chacha20-poly1305")]
+            AnyRopsFile::XChaCha20Poly1305(_) => XCHACHA20POLY1305::NAME,
+        }
+    }
+
+    pub fn hasher_name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            AnyRopsFile::Aes256Gcm(rops_file) => rops_file.hasher_name(),
+            #[cfg(feature = "chacha20-poly1305")]
+            AnyRopsFile::XChaCha20Poly1305(rops_file) => rops_file.hasher_name(),
+        }
+    }
+
+    /// Reads the cipher name from the encrypted MAC, and checks that the encrypted values name the
+    /// same. Neither the map nor the metadata are otherwise parsed.
+    pub fn detect_cipher_name(rops_file_str: &str) -> Result<String, AnyRopsFileFromStrError> {
+        let metadata_names = MetadataNames::read::<F>(rops_file_str)?;
+
+        let mac_cipher_name = encrypted_cipher_name(&metadata_names.sops.mac)
+            .map(ToString::to_string)
+            .ok_or(AnyRopsFileFromStrError::MissingCipherName)?;
+
+        let EncryptedCipherNames(cipher_names) =
+            F::deserialize_from_str(rops_file_str).map_err(|_| AnyRopsFileFromStrError::MissingCipherName)?;
+
+        let mismatched_cipher_names = cipher_names
+            .into_iter()
+            .filter(|cipher_name| *cipher_name != mac_cipher_name)
+            .collect::<Vec<_>>();
+
+        match mismatched_cipher_names.is_empty() {
+            true => Ok(mac_cipher_name),
+            false => Err(AnyRopsFileFromStrError::MismatchedCipher {
+                mac: mac_cipher_name,
+                values: mismatched_cipher_names,
+            }),
+        }
+    }
+
+    /// Reads the MAC hasher name without parsing the rest of the file.
+    pub fn detect_hasher_name(rops_file_str: &str) -> Result<String, AnyRopsFileFromStrError> {
+        let metadata_names = MetadataNames::read::<F>(rops_file_str)?;
+
+        Ok(metadata_names.sops.mac_hasher.unwrap_or_else(|| SOPS_MAC_HASHER_NAME.to_string()))
+    }
+}
+
+impl<C: Cipher, F: FileFormat> AnyHasherRopsFile<C, F> {
+    pub fn visit<V: RopsFileVisitor<F>>(self, visitor: V) -> V::Output {
+        match self {
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha512(rops_file) => visitor.visit(rops_file),
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha256(rops_file) => visitor.visit(rops_file),
+            #[cfg(feature = "blake3")]
+            AnyHasherRopsFile::Blake3(rops_file) => visitor.visit(rops_file),
+        }
+    }
+
+    pub fn hasher_name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha512(_) => SHA512::NAME,
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha256(_) => SHA256::NAME,
+            #[cfg(feature = "blake3")]
+            AnyHasherRopsFile::Blake3(_) => BLAKE3::NAME,
+        }
+    }
+
+    fn from_str_with_hasher_name(rops_file_str: &str, hasher_name: String) -> Result<Self, AnyRopsFileFromStrError> {
+        match hasher_name.as_str() {
+            #[cfg(feature = "sha2")]
+            name if name == SHA512::NAME => Ok(Self::Sha512(RopsFile::from_str(rops_file_str)?)),
+            #[cfg(feature = "sha2")]
+            name if name == SHA256::NAME => Ok(Self::Sha256(RopsFile::from_str(rops_file_str)?)),
+            #[cfg(feature = "blake3")]
+            name if name == BLAKE3::NAME => Ok(Self::Blake3(RopsFile::from_str(rops_file_str)?)),
+            _ => Err(AnyRopsFileFromStrError::UnsupportedHasher(hasher_name)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataNames {
+    sops: MetadataNamesInner,
+}
+
+#[derive(Deserialize)]
+struct MetadataNamesInner {
+    mac: String,
+    mac_hasher: Option<String>,
+}
+
+impl MetadataNames {
+    fn read<F: FileFormat>(rops_file_str: &str) -> Result<Self, AnyRopsFileFromStrError> {
+        F::deserialize_from_str::<Self>(rops_file_str).map_err(|_| AnyRopsFileFromStrError::MissingCipherName)
+    }
+}
+
+/// Cipher names of all `ENC[...]` strings in a file, that of the MAC included.
+struct EncryptedCipherNames(IndexSet<String>);
+
+impl<'de> Deserialize<'de> for EncryptedCipherNames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut cipher_names = IndexSet::new();
+        CipherNameCollector(&mut cipher_names).deserialize(deserializer)?;
+        Ok(Self(cipher_names))
+    }
+}
+
+struct CipherNameCollector<'a>(&'a mut IndexSet<String>);
+
+impl<'de> DeserializeSeed<'de> for CipherNameCollector<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for CipherNameCollector<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_str<E: de::Error>(self, str: &str) -> Result<Self::Value, E> {
+        if let Some(cipher_name) = encrypted_cipher_name(str) {
+            self.0.insert(cipher_name.to_string());
+        }
+        Ok(())
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i128<E: de::Error>(self, _: i128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u128<E: de::Error>(self, _: u128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E: de::Error>(self, _: &[u8]) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element_seed(CipherNameCollector(self.0))?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while map.next_key::<IgnoredAny>()?.is_some() {
+            map.next_value_seed(CipherNameCollector(self.0))?;
+        }
+        Ok(())
+    }
+
+    // YAML tagged values.
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (IgnoredAny, variant) = data.variant::<IgnoredAny>()?;
+        variant.newtype_variant_seed(self)
+    }
+}
+
+impl<F: FileFormat> FromStr for AnyRopsFile<F> {
+    type Err = AnyRopsFileFromStrError;
+
+    fn from_str(rops_file_str: &str) -> Result<Self, Self::Err> {
+        let cipher_name = Self::detect_cipher_name(rops_file_str)?;
+        let hasher_name = Self::detect_hasher_name(rops_file_str)?;
+
+        match cipher_name.as_str() {
+            #[cfg(feature = "aes-gcm")]
+            name if name == AES256GCM::NAME => Ok(Self::Aes256Gcm(AnyHasherRopsFile::from_str_with_hasher_name(
+                rops_file_str,
+                hasher_name,
+            )?)),
+            #[cfg(feature = "chacha20-poly1305")]
+            name if name == XCHACHA20POLY1305::NAME => Ok(Self::XChaCha20Poly1305(AnyHasherRopsFile::from_str_with_hasher_name(
+                rops_file_str,
+                hasher_name,
+            )?)),
+            _ => Err(AnyRopsFileFromStrError::UnsupportedCipher(cipher_name)),
+        }
+    }
+}
+
+impl<F: FileFormat> Display for AnyRopsFile<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "aes-gcm")]
+            AnyRopsFile::Aes256Gcm(rops_file) => rops_file.fmt(f),
+            #[cfg(feature = "chacha20-poly1305")]
+            AnyRopsFile::XChaCha20Poly1305(rops_file) => rops_file.fmt(f),
+        }
+    }
+}
+
+impl<C: Cipher, F: FileFormat> Display for AnyHasherRopsFile<C, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha512(rops_file) => rops_file.fmt(f),
+            #[cfg(feature = "sha2")]
+            AnyHasherRopsFile::Sha256(rops_file) => rops_file.fmt(f),
+            #[cfg(feature = "blake3")]
+            AnyHasherRopsFile::Blake3(rops_file) => rops_file.fmt(f),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml", feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod tests {
+    use super::*;
+
+    struct DecryptVisitor;
+
+    impl RopsFileVisitor<YamlFileFormat> for DecryptVisitor {
+        type Output = <YamlFileFormat as FileFormat>::Map;
+
+        fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, YamlFileFormat>) -> Self::Output {
+            rops_file.decrypt::<YamlFileFormat>().unwrap().into_inner_map()
+        }
+    }
+
+    fn encrypted_string() -> String {
+        RopsFile::<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>::mock_format_display()
+    }
+
+    #[test]
+    fn parses_by_cipher_name_of_mac() {
+        let any_rops_file = encrypted_string().parse::<AnyRopsFile<YamlFileFormat>>().unwrap();
+        assert_eq!(AES256GCM::NAME, any_rops_file.cipher_name());
+        assert_eq!(SHA512::NAME, any_rops_file.hasher_name());
+        assert_eq!(encrypted_string(), any_rops_file.to_string());
+    }
+
+    #[test]
+    fn visits_rops_file() {
+        AgeIntegration::set_mock_private_key_env_var();
+        assert_eq!(
+            RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock().into_inner_map(),
+            encrypted_string()
+                .parse::<AnyRopsFile<YamlFileFormat>>()
+                .unwrap()
+                .visit(DecryptVisitor)
+        );
+    }
+
+    #[cfg(feature = "chacha20-poly1305")]
+    #[test]
+    fn parses_xchacha20_poly1305_file() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let encrypted_string =
+            RopsFileBuilder::<YamlFileFormat>::new(&RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display())
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+                .encrypt::<XCHACHA20POLY1305, SHA512>()
+                .unwrap()
+                .to_string();
+
+        let any_rops_file = encrypted_string.parse::<AnyRopsFile<YamlFileFormat>>().unwrap();
+        assert_eq!(XCHACHA20POLY1305::NAME, any_rops_file.cipher_name());
+        assert_eq!(
+            RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock().into_inner_map(),
+            any_rops_file.visit(DecryptVisitor)
+        );
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn parses_by_mac_hasher() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let encrypted_string =
+            RopsFileBuilder::<YamlFileFormat>::new(&RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display())
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+                .encrypt::<AES256GCM, BLAKE3>()
+                .unwrap()
+                .to_string();
+
+        let any_rops_file = encrypted_string.parse::<AnyRopsFile<YamlFileFormat>>().unwrap();
+        assert_eq!(BLAKE3::NAME, any_rops_file.hasher_name());
+        assert_eq!(
+            RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock().into_inner_map(),
+            any_rops_file.visit(DecryptVisitor)
+        );
+    }
+
+    #[test]
+    fn names_unsupported_hasher() {
+        let error = format!("{}  mac_hasher: GIBBERISH\n", encrypted_string())
+            .parse::<AnyRopsFile<YamlFileFormat>>()
+            .unwrap_err();
+
+        assert!(matches!(&error, AnyRopsFileFromStrError::UnsupportedHasher(hasher_name) if hasher_name == "GIBBERISH"));
+        assert!(error.to_string().contains("GIBBERISH"));
+    }
+
+    #[test]
+    fn names_unsupported_cipher() {
+        let error = encrypted_string()
+            .replace(AES256GCM::NAME, "GIBBERISH")
+            .parse::<AnyRopsFile<YamlFileFormat>>()
+            .unwrap_err();
+
+        assert!(matches!(&error, AnyRopsFileFromStrError::UnsupportedCipher(cipher_name) if cipher_name == "GIBBERISH"));
+        assert!(error.to_string().contains("GIBBERISH"));
+    }
+
+    #[test]
+    fn names_mismatched_value_cipher() {
+        let error = encrypted_string()
+            .replacen(AES256GCM::NAME, "GIBBERISH", 1)
+            .parse::<AnyRopsFile<YamlFileFormat>>()
+            .unwrap_err();
+
+        assert!(matches!(
+            &error,
+            AnyRopsFileFromStrError::MismatchedCipher { mac, values } if mac == AES256GCM::NAME && values == &["GIBBERISH"]
+        ));
+        assert!(error.to_string().contains("GIBBERISH"));
+    }
+
+    #[test]
+    fn disallows_missing_mac() {
+        assert!(matches!(
+            RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display()
+                .parse::<AnyRopsFile<YamlFileFormat>>()
+                .unwrap_err(),
+            AnyRopsFileFromStrError::MissingCipherName
+        ));
+    }
+}
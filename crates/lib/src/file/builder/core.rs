@@ -4,6 +4,7 @@ pub struct RopsFileBuilder<F: FileFormat> {
     format_map: F::Map,
     partial_encryption: Option<PartialEncryptionConfig>,
     mac_only_encrypted: Option<bool>,
+    encrypt_empty_values: Option<bool>,
     pub(crate) integration_metadata_builder: IntegrationMetadataBuilder,
 }
 
@@ -17,7 +18,7 @@ pub enum RopsFileBuilderError {
 
 impl<F: FileFormat> RopsFileBuilder<F> {
     pub fn new(plaintext_map: &str) -> Result<Self, F::DeserializeError> {
-        F::deserialize_from_str(plaintext_map).map(Self::from_map)
+        F::deserialize_format_map::<DecryptedMap>(plaintext_map).map(|format_map| Self::from_map(format_map.into_inner_map()))
     }
 
     pub fn from_map(format_map: F::Map) -> Self {
@@ -25,6 +26,7 @@ impl<F: FileFormat> RopsFileBuilder<F> {
             format_map,
             partial_encryption: None,
             mac_only_encrypted: None,
+            encrypt_empty_values: None,
             integration_metadata_builder: Default::default(),
         }
     }
@@ -39,6 +41,14 @@ impl<F: FileFormat> RopsFileBuilder<F> {
         self
     }
 
+    /// Encrypts nulls, empty strings and empty collections, and includes them in the MAC.
+    /// Files with encrypted empty strings can still be decrypted by SOPS, but not those with
+    /// encrypted nulls or empty collections.
+    pub fn encrypt_empty_values(mut self) -> Self {
+        self.encrypt_empty_values = Some(true);
+        self
+    }
+
     pub fn add_integration_key<I: Integration>(mut self, key_id: I::KeyId) -> Self {
         key_id.append_to_metadata_builder(&mut self.integration_metadata_builder);
         self
@@ -52,32 +62,53 @@ impl<F: FileFormat> RopsFileBuilder<F> {
     }
 
     pub fn encrypt<C: Cipher, H: Hasher>(self) -> Result<RopsFile<EncryptedFile<C, H>, F>, RopsFileBuilderError> {
-        #[rustfmt::skip]
-        let Self { format_map: plaintext_map, partial_encryption, mac_only_encrypted, .. } = self;
+        self.encrypt_with_data_key(&DataKey::new())
+    }
 
+    /// Encrypts the maps of several builders using one data key, each still receiving their own
+    /// metadata. Mirrors how SOPS handles multi-document files.
+    #[allow(clippy::type_complexity)]
+    pub fn encrypt_with_shared_data_key<C: Cipher, H: Hasher>(
+        builders: impl IntoIterator<Item = Self>,
+    ) -> Result<Vec<RopsFile<EncryptedFile<C, H>, F>>, RopsFileBuilderError> {
         let data_key = DataKey::new();
+        builders
+            .into_iter()
+            .map(|builder| builder.encrypt_with_data_key(&data_key))
+            .collect()
+    }
+
+    fn encrypt_with_data_key<C: Cipher, H: Hasher>(
+        self,
+        data_key: &DataKey,
+    ) -> Result<RopsFile<EncryptedFile<C, H>, F>, RopsFileBuilderError> {
+        #[rustfmt::skip]
+        let Self { format_map: plaintext_map, partial_encryption, mac_only_encrypted, encrypt_empty_values, .. } = self;
 
+        let layout = plaintext_map.layout();
         let decrypted_map = plaintext_map
             .decrypted_to_internal()
             .map_err(RopsFileEncryptError::FormatToIntenrnalMap)?;
 
         let mac = Mac::<H>::compute(
-            MacOnlyEncryptedConfig::new(mac_only_encrypted, partial_encryption.as_ref()),
+            MacOnlyEncryptedConfig::new(mac_only_encrypted, partial_encryption.as_ref(), encrypt_empty_values),
             &decrypted_map,
         );
 
-        let encrypted_map_result = decrypted_map.encrypt(&data_key, partial_encryption.as_ref());
+        let encrypted_map_result = decrypted_map.encrypt(data_key, partial_encryption.as_ref(), encrypt_empty_values.unwrap_or_default());
 
         let encrypted_metadata_result = RopsFileMetadata {
-            intregation: self.integration_metadata_builder.into_integration_metadata(&data_key)?,
+            intregation: self.integration_metadata_builder.into_integration_metadata(data_key)?,
             last_modified: LastModifiedDateTime::now(),
             mac,
             partial_encryption,
             mac_only_encrypted,
+            encrypt_empty_values,
+            mac_hasher: MacHasher::default(),
         }
-        .encrypt(&data_key);
+        .encrypt(data_key);
 
-        RopsFile::from_parts_results(encrypted_map_result, encrypted_metadata_result).map_err(Into::into)
+        RopsFile::from_parts_results(encrypted_map_result, encrypted_metadata_result, layout).map_err(Into::into)
     }
 }
 
@@ -105,4 +136,97 @@ mod tests {
         assert_eq!(&RopsFileFormatMap::mock(), builder_rops_file.map());
         assert_ne!(&RopsFileMetadata::mock(), builder_rops_file.metadata());
     }
+
+    #[cfg(feature = "chacha20-poly1305")]
+    #[test]
+    fn encrypts_with_xchacha20_poly1305() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let encrypted_rops_file_string =
+            RopsFileBuilder::<YamlFileFormat>::new(&RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display())
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+                .encrypt::<XCHACHA20POLY1305, SHA512>()
+                .unwrap()
+                .to_string();
+
+        assert!(encrypted_rops_file_string.contains("ENC[XCHACHA20_POLY1305,"));
+
+        let decrypted_rops_file = encrypted_rops_file_string
+            .parse::<RopsFile<EncryptedFile<XCHACHA20POLY1305, SHA512>, YamlFileFormat>>()
+            .unwrap()
+            .decrypt::<YamlFileFormat>()
+            .unwrap();
+
+        assert_eq!(&RopsFileFormatMap::mock(), decrypted_rops_file.map());
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn encrypts_with_blake3() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let encrypted_rops_file_string =
+            RopsFileBuilder::<YamlFileFormat>::new(&RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display())
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+                .encrypt::<AES256GCM, BLAKE3>()
+                .unwrap()
+                .to_string();
+
+        assert!(encrypted_rops_file_string.contains("mac_hasher: BLAKE3"));
+
+        let decrypted_rops_file = encrypted_rops_file_string
+            .parse::<RopsFile<EncryptedFile<AES256GCM, BLAKE3>, YamlFileFormat>>()
+            .unwrap()
+            .decrypt::<YamlFileFormat>()
+            .unwrap();
+
+        assert_eq!(&RopsFileFormatMap::mock(), decrypted_rops_file.map());
+
+        assert!(encrypted_rops_file_string
+            .parse::<RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>>()
+            .is_err());
+    }
+
+    #[test]
+    fn omits_sops_mac_hasher() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let encrypted_rops_file_string =
+            RopsFileBuilder::<YamlFileFormat>::new(&RopsFileFormatMap::<DecryptedMap, YamlFileFormat>::mock_format_display())
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+                .encrypt::<AES256GCM, SHA512>()
+                .unwrap()
+                .to_string();
+
+        assert!(!encrypted_rops_file_string.contains("mac_hasher"));
+    }
+
+    #[test]
+    fn encrypts_with_shared_data_key() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        type DecryptedFormatMap = RopsFileFormatMap<DecryptedMap, YamlFileFormat>;
+
+        let builders = [
+            DecryptedFormatMap::mock_format_display(),
+            DecryptedFormatMap::mock_other().to_string(),
+        ]
+        .map(|plaintext_map| {
+            RopsFileBuilder::<YamlFileFormat>::new(&plaintext_map)
+                .unwrap()
+                .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+        });
+
+        let data_keys = RopsFileBuilder::encrypt_with_shared_data_key::<AES256GCM, SHA512>(builders)
+            .unwrap()
+            .into_iter()
+            .map(|rops_file| rops_file.metadata().retrieve_data_key().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(2, data_keys.len());
+        assert_eq!(data_keys[0], data_keys[1]);
+    }
 }
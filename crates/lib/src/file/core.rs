@@ -1,6 +1,7 @@
 use std::{fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use thiserror::Error;
 
 use crate::*;
@@ -22,17 +23,29 @@ impl<S: RopsFileState, F: FileFormat> RopsFile<S, F>
 where
     <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
 {
-    pub fn new(map: impl ToExternalMap<S::MapState>, metadata: RopsFileMetadata<S::MetadataState>) -> Self {
-        Self {
-            map: map.to_external::<F>(),
+    pub fn new(
+        map: impl ToExternalMap<S::MapState>,
+        metadata: RopsFileMetadata<S::MetadataState>,
+    ) -> Result<Self, FormatFromInternalMapError> {
+        Ok(Self {
+            map: map.to_external::<F>()?,
             metadata,
-        }
+        })
+    }
+
+    pub fn from_parts(map: RopsFileFormatMap<S::MapState, F>, metadata: RopsFileMetadata<S::MetadataState>) -> Self {
+        Self { map, metadata }
     }
 
     pub fn map(&self) -> &RopsFileFormatMap<S::MapState, F> {
         &self.map
     }
 
+    fn with_layout(mut self, layout: Option<FormatMapLayout>) -> Self {
+        self.map = self.map.with_layout(layout);
+        self
+    }
+
     pub fn into_inner_map(self) -> F::Map {
         self.map.into_inner_map()
     }
@@ -56,13 +69,13 @@ where
     <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", F::serialize_to_string(self).expect("failed to serialize rops map"))
+        write!(f, "{}", F::serialize_rops_file(self).expect("failed to serialize rops map"))
     }
 }
 
 #[derive(Debug, Error)]
 pub enum RopsFileFromStrError {
-    #[error("deserialize error")]
+    #[error("deserialize error: {0}")]
     Deserialize(anyhow::Error),
 }
 
@@ -73,28 +86,79 @@ where
     type Err = RopsFileFromStrError;
 
     fn from_str(str: &str) -> Result<Self, Self::Err> {
-        F::deserialize_from_str(str).map_err(|error| RopsFileFromStrError::Deserialize(error.into()))
+        F::deserialize_rops_file(str).map_err(|error| RopsFileFromStrError::Deserialize(error.into()))
     }
 }
 
 impl<H: Hasher, F: FileFormat> RopsFile<DecryptedFile<H>, F> {
-    pub fn set_map(mut self, other_map: RopsFileFormatMap<DecryptedMap, F>) -> Result<Self, FormatToInternalMapError> {
+    pub fn set_map(mut self, other_map: RopsFileFormatMap<DecryptedMap, F>) -> Result<Self, RopsFileSetMapError> {
         if self.map != other_map {
             self.metadata.last_modified = LastModifiedDateTime::now();
         }
 
+        let layout = other_map.layout();
         let internal_other_map = other_map.to_internal()?;
 
         self.metadata.mac = Mac::<H>::compute(
-            MacOnlyEncryptedConfig::new(self.metadata.mac_only_encrypted, self.metadata.partial_encryption.as_ref()),
+            MacOnlyEncryptedConfig::new(
+                self.metadata.mac_only_encrypted,
+                self.metadata.partial_encryption.as_ref(),
+                self.metadata.encrypt_empty_values,
+            ),
             &internal_other_map,
         );
 
-        self.map = internal_other_map.to_external();
+        self.map = internal_other_map.to_external()?.with_layout(layout);
 
         Ok(self)
     }
 
+    /// Three-way merge of `self` and `theirs`, with `base` being their common ancestor. See [`RopsMap::merge`].
+    ///
+    /// The merged file keeps the data key and settings of `self`, to which the integration keys
+    /// of `theirs` are added. Conflicting key paths keep the value of `self`.
+    pub fn merge(self, base: Self, theirs: Self) -> Result<(Self, Vec<KeyPathConflict>), RopsFileMergeError> {
+        let Self { map, mut metadata } = self;
+
+        let layout = map.layout();
+        let (merged_map, conflicts) = map.to_internal()?.merge(&base.map.to_internal()?, theirs.map.to_internal()?);
+
+        metadata.last_modified = LastModifiedDateTime::now();
+        metadata.mac = Mac::<H>::compute(
+            MacOnlyEncryptedConfig::new(
+                metadata.mac_only_encrypted,
+                metadata.partial_encryption.as_ref(),
+                metadata.encrypt_empty_values,
+            ),
+            &merged_map,
+        );
+
+        #[cfg_attr(not(any(feature = "age", feature = "aws-kms")), allow(unused_mut))]
+        let mut merged_file = RopsFile::new(merged_map, metadata)?.with_layout(layout);
+
+        #[cfg(feature = "age")]
+        merged_file.add_keys::<AgeIntegration>(missing_key_ids(
+            &merged_file.metadata.intregation.age,
+            &theirs.metadata.intregation.age,
+        ))?;
+
+        #[cfg(feature = "aws-kms")]
+        merged_file.add_keys::<AwsKmsIntegration>(missing_key_ids(
+            &merged_file.metadata.intregation.kms,
+            &theirs.metadata.intregation.kms,
+        ))?;
+
+        return Ok((merged_file, conflicts));
+
+        #[cfg(any(feature = "age", feature = "aws-kms"))]
+        fn missing_key_ids<I: Integration>(units: &IntegrationMetadataUnits<I>, other_units: &IntegrationMetadataUnits<I>) -> Vec<I::KeyId>
+        where
+            I::KeyId: Clone,
+        {
+            other_units.keys().filter(|key_id| !units.contains_key(*key_id)).cloned().collect()
+        }
+    }
+
     /// Returns the removed integration medata unit, if any.
     pub fn remove_integration_key<I: Integration>(&mut self, key_id: &I::KeyId) -> IntegrationResult<Option<IntegrationMetadataUnit<I>>> {
         self.metadata.remove_integration_key(key_id)
@@ -102,12 +166,14 @@ impl<H: Hasher, F: FileFormat> RopsFile<DecryptedFile<H>, F> {
 
     pub fn encrypt<C: Cipher, Fo: FileFormat>(self) -> Result<RopsFile<EncryptedFile<C, H>, Fo>, RopsFileEncryptError> {
         let data_key = self.metadata.retrieve_data_key()?;
-        let encrypted_map = self
-            .map
-            .to_internal()?
-            .encrypt::<C>(&data_key, self.metadata.partial_encryption.as_ref());
+        let layout = self.map.layout();
+        let encrypted_map = self.map.to_internal()?.encrypt::<C>(
+            &data_key,
+            self.metadata.partial_encryption.as_ref(),
+            self.metadata.encrypt_empty_values.unwrap_or_default(),
+        );
         let encrypted_metadata = self.metadata.encrypt::<C>(&data_key);
-        RopsFile::from_parts_results(encrypted_map, encrypted_metadata)
+        RopsFile::from_parts_results(encrypted_map, encrypted_metadata, layout)
     }
 
     pub fn encrypt_with_saved_parameters<C: Cipher, Fo: FileFormat>(
@@ -117,25 +183,35 @@ impl<H: Hasher, F: FileFormat> RopsFile<DecryptedFile<H>, F> {
         #[rustfmt::skip]
         let SavedParameters { data_key, saved_map_nonces, saved_mac_nonce } = saved_parameters;
 
-        let encrypted_map =
-            self.map
-                .to_internal()?
-                .encrypt_with_saved_nonces(&data_key, self.metadata.partial_encryption.as_ref(), &saved_map_nonces);
+        let layout = self.map.layout();
+
+        let encrypted_map = self.map.to_internal()?.encrypt_with_saved_nonces(
+            &data_key,
+            self.metadata.partial_encryption.as_ref(),
+            self.metadata.encrypt_empty_values.unwrap_or_default(),
+            &saved_map_nonces,
+        );
 
         let encrypted_metadata = self.metadata.encrypt_with_saved_mac_nonce::<C>(&data_key, saved_mac_nonce);
-        RopsFile::from_parts_results(encrypted_map, encrypted_metadata)
+        RopsFile::from_parts_results(encrypted_map, encrypted_metadata, layout)
     }
 }
 
 impl<C: Cipher, F: FileFormat, H: Hasher> RopsFile<EncryptedFile<C, H>, F> {
     pub fn decrypt<Fo: FileFormat>(self) -> Result<RopsFile<DecryptedFile<H>, Fo>, RopsFileDecryptError> {
+        let layout = self.map.layout();
+        let (decrypted_map, decrypted_metadata) = self.decrypt_internal()?;
+        Ok(RopsFile::new(decrypted_map, decrypted_metadata)?.with_layout(layout))
+    }
+
+    pub(crate) fn decrypt_internal(self) -> Result<(RopsMap<DecryptedMap>, RopsFileMetadata<DecryptedMetadata<H>>), RopsFileDecryptError> {
         let (decrypted_metadata, data_key) = self.metadata.decrypt()?;
         let decrypted_map = self
             .map
             .to_internal(decrypted_metadata.partial_encryption.as_ref())?
             .decrypt(&data_key)?;
         Self::validate_mac(&decrypted_map, &decrypted_metadata)?;
-        Ok(RopsFile::new(decrypted_map, decrypted_metadata))
+        Ok((decrypted_map, decrypted_metadata))
     }
 
     #[allow(clippy::type_complexity)]
@@ -143,6 +219,7 @@ impl<C: Cipher, F: FileFormat, H: Hasher> RopsFile<EncryptedFile<C, H>, F> {
         self,
     ) -> Result<(RopsFile<DecryptedFile<H>, Fo>, SavedParameters<C, H>), RopsFileDecryptError> {
         let (decrypted_metadata, data_key, saved_mac_nonce) = self.metadata.decrypt_and_save_mac_nonce()?;
+        let layout = self.map.layout();
         let (decrypted_map, saved_map_nonces) = self
             .map
             .to_internal(decrypted_metadata.partial_encryption.as_ref())?
@@ -151,7 +228,7 @@ impl<C: Cipher, F: FileFormat, H: Hasher> RopsFile<EncryptedFile<C, H>, F> {
         Self::validate_mac(&decrypted_map, &decrypted_metadata)?;
 
         Ok((
-            RopsFile::new(decrypted_map, decrypted_metadata),
+            RopsFile::new(decrypted_map, decrypted_metadata)?.with_layout(layout),
             SavedParameters {
                 data_key,
                 saved_map_nonces,
@@ -160,6 +237,13 @@ impl<C: Cipher, F: FileFormat, H: Hasher> RopsFile<EncryptedFile<C, H>, F> {
         ))
     }
 
+    /// Converts the file to another format without decrypting it, ciphertexts and MAC are left untouched.
+    pub fn convert<Fo: FileFormat>(self) -> Result<RopsFile<EncryptedFile<C, H>, Fo>, RopsFileConvertError> {
+        let layout = self.map.layout();
+        let encrypted_map = self.map.to_internal(self.metadata.partial_encryption.as_ref())?;
+        Ok(RopsFile::new(encrypted_map, self.metadata)?.with_layout(layout))
+    }
+
     fn validate_mac(
         decrypted_map: &RopsMap<DecryptedMap>,
         decrypted_metadata: &RopsFileMetadata<DecryptedMetadata<H>>,
@@ -168,24 +252,26 @@ impl<C: Cipher, F: FileFormat, H: Hasher> RopsFile<EncryptedFile<C, H>, F> {
             MacOnlyEncryptedConfig::new(
                 decrypted_metadata.mac_only_encrypted,
                 decrypted_metadata.partial_encryption.as_ref(),
+                decrypted_metadata.encrypt_empty_values,
             ),
             decrypted_map,
         );
         let stored_mac = &decrypted_metadata.mac;
 
-        match &computed_mac != stored_mac {
-            true => Err(RopsFileDecryptError::MacMismatch(computed_mac.to_string(), stored_mac.to_string())),
-            false => Ok(()),
+        match bool::from(computed_mac.ct_eq(stored_mac)) {
+            true => Ok(()),
+            false => Err(RopsFileDecryptError::MacMismatch(computed_mac.to_string(), stored_mac.to_string())),
         }
     }
 
     pub(crate) fn from_parts_results(
         encrypted_map_result: Result<RopsMap<EncryptedMap<C>>, C::Error>,
         encrypted_metadata_result: Result<RopsFileMetadata<EncryptedMetadata<C, H>>, C::Error>,
+        layout: Option<FormatMapLayout>,
     ) -> Result<Self, RopsFileEncryptError> {
         let encrypted_map = encrypted_map_result.map_err(|error| RopsFileEncryptError::MetadataEncryption(error.into()))?;
         let encrypted_metadata = encrypted_metadata_result.map_err(|error| RopsFileEncryptError::MetadataEncryption(error.into()))?;
-        Ok(RopsFile::new(encrypted_map, encrypted_metadata))
+        Ok(RopsFile::new(encrypted_map, encrypted_metadata)?.with_layout(layout))
     }
 }
 
@@ -260,6 +346,19 @@ mod tests {
         )
     }
 
+    #[cfg(feature = "json")]
+    #[test]
+    fn converts_encrypted_rops_file() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let converted_rops_file = EncryptedRopsFile::mock().convert::<JsonFileFormat>().unwrap();
+        pretty_assertions::assert_eq!(
+            RopsFile::<EncryptedFile<AES256GCM, SHA512>, JsonFileFormat>::mock(),
+            converted_rops_file
+        );
+        pretty_assertions::assert_eq!(DecryptedRopsFile::mock(), converted_rops_file.decrypt().unwrap())
+    }
+
     #[test]
     fn decryption_disallows_mac_mismatch() {
         AgeIntegration::set_mock_private_key_env_var();
@@ -288,6 +387,27 @@ mod tests {
         )
     }
 
+    #[test]
+    fn merges_rops_file() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let ours = DecryptedRopsFile::mock().set_map(RopsFileFormatMap::mock_other()).unwrap();
+        let mut theirs = DecryptedRopsFile::mock();
+        theirs
+            .add_keys::<AgeIntegration>([<AgeIntegration as Integration>::KeyId::mock_other()])
+            .unwrap();
+
+        let (merged_file, conflicts) = ours.merge(DecryptedRopsFile::mock(), theirs).unwrap();
+
+        assert!(conflicts.is_empty());
+        assert_eq!(RopsFileFormatMap::mock_other(), merged_file.map);
+        assert_eq!(2, merged_file.metadata.intregation.age.len());
+        assert_eq!(
+            Mac::<SHA512>::compute(MacOnlyEncryptedConfig::mock(), &RopsMap::mock_other()),
+            merged_file.metadata.mac
+        )
+    }
+
     #[test]
     fn skips_updating_unmodified_map() {
         let rops_file = RopsFile::<DecryptedFile<SHA512>, YamlFileFormat>::mock()
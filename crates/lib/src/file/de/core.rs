@@ -0,0 +1,49 @@
+use std::{io::Read, path::Path};
+
+use serde::de::DeserializeOwned;
+
+use crate::*;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RopsDeserializeError {
+    #[error("unable to read rops file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unable to parse rops file: {0}")]
+    FromStr(#[from] AnyRopsFileFromStrError),
+    #[error("unable to decrypt rops file: {0}")]
+    Decrypt(#[from] RopsFileDecryptError),
+    #[error("unable to deserialize decrypted map: {0}")]
+    Deserialize(#[from] RopsMapDeserializeError),
+}
+
+/// Decrypts an encrypted rops file string, verifies its MAC and deserializes the decrypted map into `T`.
+/// The cipher is the one named by the file, see [`AnyRopsFile`].
+///
+/// Decrypted values are zeroized once visited by the deserializer of `T`.
+pub fn from_str<F: FileFormat, T: DeserializeOwned>(str: &str) -> Result<T, RopsDeserializeError> {
+    let decrypted_map = str.parse::<AnyRopsFile<F>>()?.visit(DecryptMapVisitor)?;
+
+    return T::deserialize(decrypted_map).map_err(Into::into);
+
+    struct DecryptMapVisitor;
+
+    impl<F: FileFormat> RopsFileVisitor<F> for DecryptMapVisitor {
+        type Output = Result<RopsMap<DecryptedMap>, RopsFileDecryptError>;
+
+        fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+            rops_file.decrypt_internal().map(|(decrypted_map, _)| decrypted_map)
+        }
+    }
+}
+
+/// See [`from_str`].
+pub fn from_reader<F: FileFormat, T: DeserializeOwned>(mut reader: impl Read) -> Result<T, RopsDeserializeError> {
+    let mut encrypted_string = String::new();
+    reader.read_to_string(&mut encrypted_string)?;
+    from_str::<F, T>(&encrypted_string)
+}
+
+/// See [`from_str`].
+pub fn from_path<F: FileFormat, T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, RopsDeserializeError> {
+    from_str::<F, T>(&std::fs::read_to_string(path)?)
+}
@@ -0,0 +1,191 @@
+use std::fmt::Display;
+
+use serde::{
+    de::{DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+
+use crate::*;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("{0}")]
+pub struct RopsMapDeserializeError(String);
+
+impl serde::de::Error for RopsMapDeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl<'de> Deserializer<'de> for RopsMap<DecryptedMap> {
+    type Error = RopsMapDeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(RopsMapAccess::new(self))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+// Visited with the smallest fitting primitive, with those too large to fit any of them visited as their literal.
+fn visit_integer<'de, V: Visitor<'de>>(integer: &RopsInteger, visitor: V) -> Result<V::Value, RopsMapDeserializeError> {
+    if let Ok(integer) = i64::try_from(integer) {
+        return visitor.visit_i64(integer);
+    }
+    if let Ok(integer) = u64::try_from(integer) {
+        return visitor.visit_u64(integer);
+    }
+    if let Ok(integer) = i128::try_from(integer) {
+        return visitor.visit_i128(integer);
+    }
+    if let Ok(integer) = u128::try_from(integer) {
+        return visitor.visit_u128(integer);
+    }
+    visitor.visit_str(&integer.canonical())
+}
+
+impl<'de> Deserializer<'de> for RopsTree<DecryptedMap> {
+    type Error = RopsMapDeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            RopsTree::Sequence(sequence) => visitor.visit_seq(RopsSequenceAccess(sequence.into_iter())),
+            RopsTree::Map(map) => map.deserialize_any(visitor),
+            RopsTree::Null => visitor.visit_unit(),
+            // Visited by reference, the decrypted value is zeroized when dropped afterwards.
+            RopsTree::Leaf(value) => match &value {
+                RopsValue::String(string) => visitor.visit_str(string),
+                RopsValue::Boolean(boolean) => visitor.visit_bool(*boolean),
+                RopsValue::Integer(integer) => visit_integer(integer, visitor),
+                RopsValue::Float(float) => visitor.visit_f64(float.into()),
+            },
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            RopsTree::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variants are expected as strings, and others as maps with the variant name as their only key.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            RopsTree::Leaf(RopsValue::String(ref variant)) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            RopsTree::Map(map) => {
+                let mut entries = map.0.into_iter();
+                match (entries.next(), entries.next()) {
+                    (Some((variant, value)), None) => visitor.visit_enum(RopsEnumAccess { variant, value }),
+                    _ => Err(serde::de::Error::custom("expected a map with a single key for enum variant")),
+                }
+            }
+            _ => Err(serde::de::Error::custom("expected a string or a map for enum variant")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct RopsMapAccess {
+    entries: indexmap::map::IntoIter<String, RopsTree<DecryptedMap>>,
+    next_value: Option<RopsTree<DecryptedMap>>,
+}
+
+impl RopsMapAccess {
+    fn new(map: RopsMap<DecryptedMap>) -> Self {
+        Self {
+            entries: map.0.into_iter(),
+            next_value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for RopsMapAccess {
+    type Error = RopsMapDeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.next_value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let value = self.next_value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct RopsSequenceAccess(std::vec::IntoIter<RopsTree<DecryptedMap>>);
+
+impl<'de> SeqAccess<'de> for RopsSequenceAccess {
+    type Error = RopsMapDeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next().map(|tree| seed.deserialize(tree)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct RopsEnumAccess {
+    variant: String,
+    value: RopsTree<DecryptedMap>,
+}
+
+impl<'de> EnumAccess<'de> for RopsEnumAccess {
+    type Error = RopsMapDeserializeError;
+    type Variant = RopsTree<DecryptedMap>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, self.value))
+    }
+}
+
+impl<'de> VariantAccess<'de> for RopsTree<DecryptedMap> {
+    type Error = RopsMapDeserializeError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        serde::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+}
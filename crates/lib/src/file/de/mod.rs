@@ -0,0 +1,10 @@
+mod deserializer;
+pub use deserializer::RopsMapDeserializeError;
+
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+mod core;
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub use core::{from_path, from_reader, from_str, RopsDeserializeError};
+
+#[cfg(all(test, feature = "yaml", feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod tests;
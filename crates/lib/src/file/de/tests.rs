@@ -0,0 +1,126 @@
+use serde::Deserialize;
+
+use crate::*;
+
+type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct MockConfig {
+    hello: String,
+    nested_map: NestedMap,
+    booleans: Vec<bool>,
+    escape_unencrypted: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct NestedMap {
+    null_key: Option<String>,
+    array: (String, ArrayMap, ArrayFloat),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct ArrayMap {
+    nested_map_in_array: IntegerMap,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct IntegerMap {
+    integer: u16,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct ArrayFloat {
+    float: f64,
+}
+
+impl MockTestUtil for MockConfig {
+    fn mock() -> Self {
+        Self {
+            hello: "world!".to_string(),
+            nested_map: NestedMap {
+                null_key: None,
+                array: (
+                    "string".to_string(),
+                    ArrayMap {
+                        nested_map_in_array: IntegerMap { integer: 1234 },
+                    },
+                    ArrayFloat { float: 1234.56789 },
+                ),
+            },
+            booleans: vec![true, false],
+            escape_unencrypted: "plaintext".to_string(),
+        }
+    }
+}
+
+#[test]
+fn deserializes_from_str() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    pretty_assertions::assert_eq!(
+        MockConfig::mock(),
+        crate::from_str::<YamlFileFormat, MockConfig>(&EncryptedRopsFile::mock().to_string()).unwrap()
+    )
+}
+
+#[test]
+fn deserializes_from_reader() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let encrypted_string = EncryptedRopsFile::mock().to_string();
+    pretty_assertions::assert_eq!(
+        MockConfig::mock(),
+        crate::from_reader::<YamlFileFormat, MockConfig>(encrypted_string.as_bytes()).unwrap()
+    )
+}
+
+#[test]
+fn deserializes_from_path() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let temp_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(temp_file.path(), EncryptedRopsFile::mock().to_string()).unwrap();
+    pretty_assertions::assert_eq!(
+        MockConfig::mock(),
+        crate::from_path::<YamlFileFormat, MockConfig>(temp_file.path()).unwrap()
+    )
+}
+
+#[test]
+fn disallows_mac_mismatch() {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let encrypted_file = RopsFile::<_, YamlFileFormat>::from_parts(RopsFileFormatMap::mock_other(), RopsFileMetadata::mock());
+
+    assert!(matches!(
+        crate::from_str::<YamlFileFormat, serde::de::IgnoredAny>(&EncryptedRopsFile::to_string(&encrypted_file)).unwrap_err(),
+        RopsDeserializeError::Decrypt(RopsFileDecryptError::MacMismatch(_, _))
+    ))
+}
+
+#[test]
+fn deserializes_enums() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Mode {
+        Unit,
+        Newtype(i64),
+        Struct { value: bool },
+    }
+
+    let deserialize = |tree: RopsTree<DecryptedMap>| Mode::deserialize(tree).unwrap();
+    let single_entry_map =
+        |key: &str, value: RopsTree<DecryptedMap>| RopsTree::Map(indexmap::indexmap! { key.to_string() => value }.into());
+
+    assert_eq!(Mode::Unit, deserialize(RopsTree::Leaf(RopsValue::String("Unit".to_string()))));
+    assert_eq!(
+        Mode::Newtype(1),
+        deserialize(single_entry_map("Newtype", RopsTree::Leaf(RopsValue::Integer(1.into()))))
+    );
+    assert_eq!(
+        Mode::Struct { value: true },
+        deserialize(single_entry_map(
+            "Struct",
+            single_entry_map("value", RopsTree::Leaf(RopsValue::Boolean(true)))
+        ))
+    );
+}
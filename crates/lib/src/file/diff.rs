@@ -0,0 +1,30 @@
+use crate::*;
+
+#[derive(Debug, PartialEq)]
+pub struct RopsFileDiff {
+    pub map: Vec<KeyPathDiff>,
+    pub metadata: Vec<MetadataDiff>,
+}
+
+impl RopsFileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty() && self.metadata.is_empty()
+    }
+}
+
+impl<H: Hasher, F: FileFormat> RopsFile<DecryptedFile<H>, F> {
+    /// Changes going from `self` to `other`, which may be stored in another format.
+    pub fn diff<Ho: Hasher, Fo: FileFormat>(
+        self,
+        other: RopsFile<DecryptedFile<Ho>, Fo>,
+    ) -> Result<RopsFileDiff, FormatToInternalMapError> {
+        let metadata_diff = self.metadata().diff(other.metadata());
+        let old_map = RopsFileFormatMap::<DecryptedMap, F>::from_inner_map(self.into_inner_map()).to_internal()?;
+        let new_map = RopsFileFormatMap::<DecryptedMap, Fo>::from_inner_map(other.into_inner_map()).to_internal()?;
+
+        Ok(RopsFileDiff {
+            map: old_map.diff(&new_map),
+            metadata: metadata_diff,
+        })
+    }
+}
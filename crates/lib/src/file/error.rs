@@ -6,6 +6,8 @@ use crate::*;
 pub enum RopsFileEncryptError {
     #[error("invalid decrypted map format: {0}")]
     FormatToIntenrnalMap(#[from] FormatToInternalMapError),
+    #[error("unable to write encrypted map: {0}")]
+    FormatFromInternalMap(#[from] FormatFromInternalMapError),
     #[error("unable to retrieve data key: {0}")]
     DataKeyRetrieval(#[from] RopsFileMetadataDataKeyRetrievalError),
     #[error("unable to encrypt map: {0}")]
@@ -20,6 +22,8 @@ pub enum RopsFileEncryptError {
 pub enum RopsFileDecryptError {
     #[error("invalid encrypted map format; {0}")]
     FormatToIntenrnalMap(#[from] FormatToInternalMapError),
+    #[error("unable to write decrypted map: {0}")]
+    FormatFromInternalMap(#[from] FormatFromInternalMapError),
     #[error("unable to decrypt map value: {0}")]
     DecryptValue(#[from] DecryptRopsValueError),
     #[error("unable to decrypt file metadata")]
@@ -35,3 +39,29 @@ pub enum RopsFileAddKeyError {
     #[error("encountered error when attempting to add keys")]
     AddKey(#[from] IntegrationError),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum RopsFileMergeError {
+    #[error("invalid decrypted map format: {0}")]
+    FormatToIntenrnalMap(#[from] FormatToInternalMapError),
+    #[error("unable to write merged map: {0}")]
+    FormatFromInternalMap(#[from] FormatFromInternalMapError),
+    #[error("unable to add integration keys of the other file: {0}")]
+    AddKey(#[from] RopsFileAddKeyError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RopsFileSetMapError {
+    #[error("invalid decrypted map format: {0}")]
+    FormatToIntenrnalMap(#[from] FormatToInternalMapError),
+    #[error("unable to write decrypted map: {0}")]
+    FormatFromInternalMap(#[from] FormatFromInternalMapError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RopsFileConvertError {
+    #[error("invalid encrypted map format: {0}")]
+    FormatToIntenrnalMap(#[from] FormatToInternalMapError),
+    #[error("unable to write converted map: {0}")]
+    FormatFromInternalMap(#[from] FormatFromInternalMapError),
+}
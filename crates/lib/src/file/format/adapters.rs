@@ -22,17 +22,17 @@ impl FileFormatKeyAdapter for String {
     }
 }
 
-pub trait FileFormatValueAdapter {
+pub trait FileFormatValueAdapter: Sized {
     fn decrypted_to_internal(self) -> Result<RopsTree<DecryptedMap>, FormatToInternalMapError>;
 
-    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Self;
+    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Result<Self, FormatFromInternalMapError>;
 
     fn encrypted_to_internal<C: Cipher>(
         self,
         resolved_partial_encryption: ResolvedPartialEncryption,
     ) -> Result<RopsTree<EncryptedMap<C>>, FormatToInternalMapError>;
 
-    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Self;
+    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError>;
 }
 
 pub trait FileFormatMapAdapter: Sized + Serialize + DeserializeOwned + PartialEq + Debug
@@ -46,6 +46,15 @@ where
 
     fn insert(&mut self, key: Self::Key, value: Self::Value);
 
+    /// Only implemented by formats with details not carried by the internal map, such as
+    /// comments or YAML tags.
+    fn layout(&self) -> Option<FormatMapLayout> {
+        None
+    }
+
+    /// Applies the layout of a previous revision of the map, keeping the current values.
+    fn restore_layout(&mut self, _layout: FormatMapLayout) {}
+
     fn decrypted_to_internal(self) -> Result<RopsMap<DecryptedMap>, FormatToInternalMapError> {
         let mut tree_map = IndexMap::default();
 
@@ -57,14 +66,14 @@ where
         Ok(tree_map.into())
     }
 
-    fn decrypted_from_internal(rops_map: RopsMap<DecryptedMap>) -> Self {
+    fn decrypted_from_internal(rops_map: RopsMap<DecryptedMap>) -> Result<Self, FormatFromInternalMapError> {
         let mut format_map = Self::with_capacity(rops_map.len());
 
         for (key, value) in rops_map.0 {
-            format_map.insert(Self::Key::from_internal(key), Self::Value::decrypted_from_internal(value));
+            format_map.insert(Self::Key::from_internal(key), Self::Value::decrypted_from_internal(value)?);
         }
 
-        format_map
+        Ok(format_map)
     }
 
     fn encrypted_to_internal<F, C: Cipher>(
@@ -91,13 +100,13 @@ where
         Ok(tree_map.into())
     }
 
-    fn encrypted_from_internal<C: Cipher>(internal_map: RopsMap<EncryptedMap<C>>) -> Self {
+    fn encrypted_from_internal<C: Cipher>(internal_map: RopsMap<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError> {
         let mut format_map = Self::with_capacity(internal_map.len());
 
         for (key, tree) in internal_map.0 {
-            format_map.insert(Self::Key::from_internal(key), Self::Value::encrypted_from_internal(tree));
+            format_map.insert(Self::Key::from_internal(key), Self::Value::encrypted_from_internal(tree)?);
         }
 
-        format_map
+        Ok(format_map)
     }
 }
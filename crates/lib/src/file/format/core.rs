@@ -1,3 +1,5 @@
+use std::{fmt::Display, str::FromStr};
+
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::*;
@@ -11,4 +13,45 @@ pub trait FileFormat: Sized {
     fn serialize_to_string<T: Serialize>(t: &T) -> Result<String, Self::SerializeError>;
 
     fn deserialize_from_str<T: DeserializeOwned>(str: &str) -> Result<T, Self::DeserializeError>;
+
+    /// Whether [`FileFormat::join_documents`] is able to join several documents into one string
+    /// which splits back into them.
+    const MULTIPLE_DOCUMENTS: bool = false;
+
+    /// Splits a string containing several documents, e.g. `---` separated YAML documents, so
+    /// that each may be handled as a separate rops file. Formats without multi-document
+    /// support return the string as is.
+    fn split_documents(str: &str) -> Vec<String> {
+        vec![str.to_string()]
+    }
+
+    /// Inverse of [`FileFormat::split_documents`]. Formats without multi-document support
+    /// concatenate the documents, which is only valid for a single one.
+    fn join_documents(documents: Vec<String>) -> String {
+        documents.concat()
+    }
+
+    /// Formats backed by a layout preserving document model override the `*_rops_file` and
+    /// `*_format_map` methods, comments and whitespace can't otherwise be passed through serde.
+    fn serialize_rops_file<S: RopsFileState>(rops_file: &RopsFile<S, Self>) -> Result<String, Self::SerializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        Self::serialize_to_string(rops_file)
+    }
+
+    fn deserialize_rops_file<S: RopsFileState>(str: &str) -> Result<RopsFile<S, Self>, Self::DeserializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        Self::deserialize_from_str(str)
+    }
+
+    fn serialize_format_map<S: RopsMapState>(format_map: &RopsFileFormatMap<S, Self>) -> Result<String, Self::SerializeError> {
+        Self::serialize_to_string(format_map)
+    }
+
+    fn deserialize_format_map<S: RopsMapState>(str: &str) -> Result<RopsFileFormatMap<S, Self>, Self::DeserializeError> {
+        Self::deserialize_from_str(str)
+    }
 }
@@ -4,6 +4,12 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+    str::FromStr,
+};
+
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{Map as JsonMap, Value as JsonValue};
 
@@ -25,6 +31,41 @@ impl FileFormat for JsonFileFormat {
     fn deserialize_from_str<T: DeserializeOwned>(str: &str) -> Result<T, Self::DeserializeError> {
         serde_json::from_str(str)
     }
+
+    // Maps are parsed by hand to keep the literals of their numbers, see `literals::NumberLiteralReader`.
+    fn deserialize_rops_file<S: RopsFileState>(str: &str) -> Result<RopsFile<S, Self>, Self::DeserializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        let mut map = literals::deserialize_map(str)?;
+        let metadata = map
+            .shift_remove(METADATA_KEY)
+            .ok_or_else(|| <serde_json::Error as serde::de::Error>::missing_field(METADATA_KEY))?;
+
+        Ok(RopsFile::from_parts(
+            RopsFileFormatMap::from_inner_map(map),
+            serde_json::from_value(metadata)?,
+        ))
+    }
+
+    fn deserialize_format_map<S: RopsMapState>(str: &str) -> Result<RopsFileFormatMap<S, Self>, Self::DeserializeError> {
+        literals::deserialize_map(str).map(RopsFileFormatMap::from_inner_map)
+    }
+}
+
+const METADATA_KEY: &str = "sops";
+
+impl StreamFileFormat for JsonFileFormat {
+    fn transcode_stream(reader: impl Read, writer: impl Write, transcoder: impl StreamTranscoder) -> Result<(), Self::SerializeError> {
+        let reader = literals::NumberLiteralReader::new(reader);
+        let source_literals = reader.source_literals();
+
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        transcoder.transcode(&mut deserializer, &mut serde_json::Serializer::pretty(writer), &|literal| {
+            source_literals.source_literal(literal)
+        })?;
+        deserializer.end()
+    }
 }
 
 impl FileFormatMapAdapter for JsonMap<String, JsonValue> {
@@ -46,7 +87,7 @@ impl FileFormatValueAdapter for JsonValue {
             JsonValue::Object(map) => RopsTree::Map(JsonMap::decrypted_to_internal(map)?),
             JsonValue::Bool(boolean) => RopsTree::Leaf(RopsValue::Boolean(boolean)),
             JsonValue::String(string) => RopsTree::Leaf(RopsValue::String(string)),
-            JsonValue::Number(number) => RopsTree::Leaf(helpers::to_internal_number(number)?),
+            JsonValue::Number(number) => RopsTree::Leaf(literals::to_internal_number(number.as_str())),
             JsonValue::Array(sequence) => RopsTree::Sequence(
                 sequence
                     .into_iter()
@@ -57,13 +98,18 @@ impl FileFormatValueAdapter for JsonValue {
         })
     }
 
-    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Self {
-        match rops_tree {
-            RopsTree::Sequence(sequence) => JsonValue::Array(sequence.into_iter().map(Self::decrypted_from_internal).collect()),
-            RopsTree::Map(map) => JsonValue::Object(JsonMap::decrypted_from_internal(map)),
+    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match rops_tree {
+            RopsTree::Sequence(sequence) => JsonValue::Array(
+                sequence
+                    .into_iter()
+                    .map(Self::decrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => JsonValue::Object(JsonMap::decrypted_from_internal(map)?),
             RopsTree::Null => JsonValue::Null,
-            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value),
-        }
+            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value)?,
+        })
     }
 
     fn encrypted_to_internal<C: Cipher>(
@@ -88,48 +134,238 @@ impl FileFormatValueAdapter for JsonValue {
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(bool.to_string())),
             },
             JsonValue::Number(number) => match resolved_partial_encryption.escape_encryption() {
-                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(helpers::to_internal_number(number)?)),
+                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(literals::to_internal_number(number.as_str()))),
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(number.to_string())),
             },
         })
     }
 
-    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Self {
-        match internal_tree {
-            RopsTree::Sequence(sequence) => JsonValue::Array(sequence.into_iter().map(Self::encrypted_from_internal).collect()),
-            RopsTree::Map(map) => JsonValue::Object(JsonMap::encrypted_from_internal(map)),
+    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match internal_tree {
+            RopsTree::Sequence(sequence) => JsonValue::Array(
+                sequence
+                    .into_iter()
+                    .map(Self::encrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => JsonValue::Object(JsonMap::encrypted_from_internal(map)?),
             RopsTree::Null => JsonValue::Null,
             RopsTree::Leaf(maybe_encrypted_value) => match maybe_encrypted_value {
                 RopsMapEncryptedLeaf::Encrypted(encrypted_value) => JsonValue::String(encrypted_value.to_string()),
-                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value),
+                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value)?,
             },
-        }
+        })
     }
 }
 
 mod helpers {
     use super::*;
 
-    pub fn to_internal_number(number: serde_json::Number) -> Result<RopsValue, FormatToInternalMapError> {
-        Ok(match number.is_f64() {
-            true => RopsValue::Float(number.as_f64().expect("number not a f64").into()),
-            false => RopsValue::Integer(
-                number
-                    .as_i64()
-                    .ok_or_else(|| FormatToInternalMapError::IntegerOutOfRange(number.as_u64().expect("number not an u64")))?,
+    pub fn from_internal_value(mut value: RopsValue) -> Result<JsonValue, FormatFromInternalMapError> {
+        Ok(match &mut value {
+            RopsValue::String(string) => JsonValue::String(std::mem::take(string)),
+            RopsValue::Boolean(bool) => JsonValue::Bool(*bool),
+            // Literals of other formats are written by value when they aren't valid JSON numbers, e.g. `0x1F` or `.inf`.
+            RopsValue::Integer(integer) => JsonValue::Number(
+                literals::number(integer.as_str())
+                    .unwrap_or_else(|| literals::number(&integer.canonical()).expect("canonical integer not a valid JSON number")),
             ),
+            RopsValue::Float(rops_float) => JsonValue::Number(match literals::number(rops_float.as_str()) {
+                Some(number) => number,
+                None => {
+                    serde_json::Number::from_f64(f64::from(&*rops_float)).ok_or_else(|| FormatFromInternalMapError::NonFiniteFloat {
+                        format: "JSON",
+                        literal: rops_float.as_str().to_string(),
+                    })?
+                }
+            }),
         })
     }
+}
+
+pub(crate) mod literals {
+    use std::{cell::RefCell, io, rc::Rc};
+
+    use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+
+    use super::*;
+
+    // Key of the single entry maps which `serde_json` hands out numbers as when its `arbitrary_precision`
+    // feature is enabled, the value being the number literal.
+    pub const NUMBER_KEY: &str = "$serde_json::private::Number";
+
+    pub fn to_internal_number(literal: &str) -> RopsValue {
+        match literal.contains(['.', 'e', 'E']) {
+            true => RopsValue::Float(literal.parse().expect("number not a valid float literal")),
+            false => RopsValue::Integer(literal.parse().expect("number not a valid integer literal")),
+        }
+    }
+
+    // `Number::from_string_unchecked` is hidden from the docs, but is the only way to hold on to a
+    // literal as written, those parsed by `serde_json` itself have their exponents normalized.
+    pub fn number(literal: &str) -> Option<serde_json::Number> {
+        literal
+            .parse::<serde_json::Number>()
+            .ok()
+            .map(|_| serde_json::Number::from_string_unchecked(literal.to_string()))
+    }
+
+    /// Parses a map whilst keeping the literals of its numbers.
+    pub fn deserialize_map(str: &str) -> Result<JsonMap<String, JsonValue>, serde_json::Error> {
+        let reader = NumberLiteralReader::new(str.as_bytes());
+        let source_literals = reader.source_literals();
+
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let value = deserializer.deserialize_map(ValueVisitor(&source_literals))?;
+        deserializer.end()?;
 
-    pub fn from_internal_value(value: RopsValue) -> JsonValue {
         match value {
-            RopsValue::String(string) => JsonValue::String(string),
-            RopsValue::Boolean(bool) => JsonValue::Bool(bool),
-            RopsValue::Integer(integer) => JsonValue::Number(integer.into()),
-            // IMPROVEMENT: return Result instead
-            RopsValue::Float(rops_float) => JsonValue::Number(
-                serde_json::Number::from_f64(rops_float.into()).expect("invalid float, Infinite or NaN values are not valid JSON"),
-            ),
+            JsonValue::Object(map) => Ok(map),
+            _ => Err(de::Error::invalid_type(de::Unexpected::Other("number"), &"a map")),
+        }
+    }
+
+    /// Keeps track of the number last read by `serde_json`. Numbers are only handed out once the
+    /// byte following them has been read, which in turn marks the end of their literal.
+    pub struct NumberLiteralReader<R> {
+        reader: R,
+        current_literal: String,
+        source_literals: SourceLiterals,
+    }
+
+    impl<R: Read> NumberLiteralReader<R> {
+        pub fn new(reader: R) -> Self {
+            Self {
+                reader,
+                current_literal: String::new(),
+                source_literals: SourceLiterals(Rc::default()),
+            }
+        }
+
+        pub fn source_literals(&self) -> SourceLiterals {
+            self.source_literals.clone()
+        }
+    }
+
+    impl<R: Read> Read for NumberLiteralReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = self.reader.read(buf)?;
+
+            for byte in &buf[..read] {
+                match byte {
+                    b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => self.current_literal.push(char::from(*byte)),
+                    _ if !self.current_literal.is_empty() => {
+                        *self.source_literals.0.borrow_mut() = std::mem::take(&mut self.current_literal);
+                    }
+                    _ => (),
+                }
+            }
+
+            Ok(read)
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct SourceLiterals(Rc<RefCell<String>>);
+
+    impl SourceLiterals {
+        /// Maps a literal handed out by `serde_json` back to how it was written, e.g. `1e+10` to `1E10`.
+        /// Literals which don't match the last one read are returned as is.
+        pub fn source_literal(&self, literal: String) -> String {
+            let last_literal = self.0.borrow();
+            match normalize(&last_literal) == literal {
+                true => last_literal.clone(),
+                false => literal,
+            }
+        }
+
+        // Integers within 64 bits are handed out by value, which only loses the sign of `-0`.
+        fn integer(&self, integer: i128) -> JsonValue {
+            let last_literal = self.0.borrow();
+            match last_literal.parse::<i128>() == Ok(integer) {
+                true => JsonValue::Number(serde_json::Number::from_string_unchecked(last_literal.clone())),
+                false => JsonValue::Number(serde_json::Number::from_string_unchecked(integer.to_string())),
+            }
+        }
+    }
+
+    /// Mirrors `serde_json`, which lowercases exponent markers and adds a `+` to unsigned exponents.
+    fn normalize(literal: &str) -> String {
+        match literal.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => match exponent.starts_with(['+', '-']) {
+                true => format!("{mantissa}e{exponent}"),
+                false => format!("{mantissa}e+{exponent}"),
+            },
+            None => literal.to_string(),
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct ValueVisitor<'a>(&'a SourceLiterals);
+
+    impl<'de> DeserializeSeed<'de> for ValueVisitor<'_> {
+        type Value = JsonValue;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for ValueVisitor<'_> {
+        type Value = JsonValue;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("any JSON value")
+        }
+
+        fn visit_bool<E: de::Error>(self, bool: bool) -> Result<Self::Value, E> {
+            Ok(JsonValue::Bool(bool))
+        }
+
+        fn visit_i64<E: de::Error>(self, integer: i64) -> Result<Self::Value, E> {
+            Ok(self.0.integer(i128::from(integer)))
+        }
+
+        fn visit_u64<E: de::Error>(self, integer: u64) -> Result<Self::Value, E> {
+            Ok(self.0.integer(i128::from(integer)))
+        }
+
+        fn visit_str<E: de::Error>(self, str: &str) -> Result<Self::Value, E> {
+            Ok(JsonValue::String(str.to_string()))
+        }
+
+        fn visit_string<E: de::Error>(self, string: String) -> Result<Self::Value, E> {
+            Ok(JsonValue::String(string))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(JsonValue::Null)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq_access: A) -> Result<Self::Value, A::Error> {
+            let mut sequence = Vec::new();
+            while let Some(value) = seq_access.next_element_seed(self)? {
+                sequence.push(value);
+            }
+            Ok(JsonValue::Array(sequence))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map_access: A) -> Result<Self::Value, A::Error> {
+            let mut next_key = map_access.next_key::<String>()?;
+
+            if next_key.as_deref() == Some(NUMBER_KEY) {
+                let literal = self.0.source_literal(map_access.next_value()?);
+                return number(&literal)
+                    .map(JsonValue::Number)
+                    .ok_or_else(|| de::Error::custom("invalid number literal"));
+            }
+
+            let mut map = JsonMap::new();
+            while let Some(key) = next_key {
+                map.insert(key, map_access.next_value_seed(self)?);
+                next_key = map_access.next_key()?;
+            }
+            Ok(JsonValue::Object(map))
         }
     }
 }
@@ -9,3 +9,94 @@ impl FileFormatTestSuiteUtils for JsonFileFormat {
 }
 
 generate_file_format_test_suite!(JsonFileFormat);
+
+#[cfg(all(feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod numbers {
+    use crate::*;
+
+    type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, JsonFileFormat>;
+
+    const PLAINTEXT: &str = indoc::indoc! {r#"
+        {
+          "big": 123456789012345678901234567890,
+          "max": 18446744073709551615,
+          "float": 1.0,
+          "exponent": 1e+10,
+          "unsigned_exponent": 1e10,
+          "uppercase_exponent": 1E10,
+          "precise": 0.10000000000000000001,
+          "sequence": [
+            -1.5E-3,
+            2
+          ]
+        }"#};
+
+    #[test]
+    fn retains_number_literals() {
+        let decrypted_file = encrypt(PLAINTEXT).decrypt::<JsonFileFormat>().unwrap();
+        pretty_assertions::assert_eq!(PLAINTEXT, decrypted_file.map().to_string())
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn retains_integers_out_of_u64_range_in_yaml() {
+        let decrypted_file = encrypt(r#"{"big": 123456789012345678901234567890}"#)
+            .decrypt::<YamlFileFormat>()
+            .unwrap();
+        pretty_assertions::assert_eq!("big: 123456789012345678901234567890\n", decrypted_file.map().to_string())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn disallows_integers_out_of_toml_range() {
+        assert_integer_out_of_range::<TomlFileFormat>("TOML", "18446744073709551615")
+    }
+
+    #[cfg(feature = "toml-edit")]
+    #[test]
+    fn disallows_integers_out_of_toml_edit_range() {
+        assert_integer_out_of_range::<TomlEditFileFormat>("TOML", "18446744073709551615")
+    }
+
+    #[cfg(feature = "toml-edit")]
+    #[test]
+    fn disallows_unencrypted_integers_out_of_range() {
+        let encrypted_file = RopsFileBuilder::<JsonFileFormat>::new(r#"{"big_unencrypted": 123456789012345678901234567890}"#)
+            .unwrap()
+            .with_partial_encryption(PartialEncryptionConfig::UnencryptedSuffix("_unencrypted".to_string()))
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt::<AES256GCM, SHA512>()
+            .unwrap();
+
+        assert!(matches!(
+            encrypted_file.convert::<TomlEditFileFormat>().unwrap_err(),
+            RopsFileConvertError::FormatFromInternalMap(FormatFromInternalMapError::IntegerOutOfRange { .. })
+        ))
+    }
+
+    fn assert_integer_out_of_range<Fo: FileFormat>(expected_format: &str, expected_literal: &str) {
+        match encrypt(&format!(r#"{{"integer": {expected_literal}}}"#))
+            .decrypt::<Fo>()
+            .unwrap_err()
+        {
+            RopsFileDecryptError::FormatFromInternalMap(FormatFromInternalMapError::IntegerOutOfRange { format, literal }) => {
+                assert_eq!(expected_format, format);
+                assert_eq!(expected_literal, literal);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    fn encrypt(plaintext: &str) -> EncryptedRopsFile {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        RopsFileBuilder::<JsonFileFormat>::new(plaintext)
+            .unwrap()
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt::<AES256GCM, SHA512>()
+            .unwrap()
+            .to_string()
+            .parse()
+            .unwrap()
+    }
+}
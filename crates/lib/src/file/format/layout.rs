@@ -0,0 +1,17 @@
+use std::any::Any;
+
+/// Type erased formatting details, such as comments, whitespace or tags, of a file format map.
+///
+/// Retrieved before a format map is converted to its internal representation so that they
+/// can be restored once converted back. Formats that don't match are silently ignored.
+pub struct FormatMapLayout(Box<dyn Any>);
+
+impl FormatMapLayout {
+    pub fn new<T: Any>(layout: T) -> Self {
+        Self(Box::new(layout))
+    }
+
+    pub fn downcast<T: Any>(self) -> Option<T> {
+        self.0.downcast().ok().map(|layout| *layout)
+    }
+}
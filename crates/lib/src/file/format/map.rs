@@ -20,15 +20,25 @@ pub struct RopsFileFormatMap<S: RopsMapState, F: FileFormat> {
 pub enum FormatToInternalMapError {
     #[error("only string keys are supported, found: {0}")]
     NonStringKey(String),
-    #[error("integer out of range, allowed values must fit inside an i64, found: {0}")]
-    IntegerOutOfRange(u64),
     #[error("unable to parse encrypted value components: {0}")]
     EncryptedRopsValue(#[from] EncryptedRopsValueFromStrError),
     #[error("encountered plaintext value when it should have been encrypted")]
     PlaintextWhenEncrypted(String),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum FormatFromInternalMapError {
+    #[error("integer out of range for {format}, found: {literal}")]
+    IntegerOutOfRange { format: &'static str, literal: String },
+    #[error("float not representable in {format}, found: {literal}")]
+    NonFiniteFloat { format: &'static str, literal: String },
+}
+
 impl<S: RopsMapState, F: FileFormat> RopsFileFormatMap<S, F> {
+    pub fn inner_map(&self) -> &F::Map {
+        &self.inner
+    }
+
     pub fn into_inner_map(self) -> F::Map {
         self.inner
     }
@@ -39,6 +49,21 @@ impl<S: RopsMapState, F: FileFormat> RopsFileFormatMap<S, F> {
             state_marker: PhantomData,
         }
     }
+
+    pub fn empty() -> Self {
+        Self::from_inner_map(F::Map::with_capacity(0))
+    }
+
+    pub(crate) fn layout(&self) -> Option<FormatMapLayout> {
+        self.inner.layout()
+    }
+
+    pub(crate) fn with_layout(mut self, layout: Option<FormatMapLayout>) -> Self {
+        if let Some(layout) = layout {
+            self.inner.restore_layout(layout);
+        }
+        self
+    }
 }
 
 impl<C: Cipher, F: FileFormat> RopsFileFormatMap<EncryptedMap<C>, F> {
@@ -61,7 +86,7 @@ impl<F: FileFormat> RopsFileFormatMap<DecryptedMap, F> {
 
 impl<S: RopsMapState, F: FileFormat> Display for RopsFileFormatMap<S, F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", F::serialize_to_string(self).expect("file format map not serializable"))
+        write!(f, "{}", F::serialize_format_map(self).expect("file format map not serializable"))
     }
 }
 
@@ -69,7 +94,7 @@ impl<S: RopsMapState, F: FileFormat> FromStr for RopsFileFormatMap<S, F> {
     type Err = F::DeserializeError;
 
     fn from_str(str: &str) -> Result<Self, Self::Err> {
-        F::deserialize_from_str(str)
+        F::deserialize_format_map(str)
     }
 }
 
@@ -92,7 +117,7 @@ mod mock {
         RopsMap<S>: ToExternalMap<S>,
     {
         fn mock_other() -> Self {
-            RopsMap::mock_other().to_external()
+            RopsMap::mock_other().to_external().expect("mock map not representable in format")
         }
     }
 }
@@ -9,6 +9,8 @@ pub use yaml::YamlFileFormat;
 #[cfg(feature = "json")]
 mod json;
 #[cfg(feature = "json")]
+pub(crate) use json::literals as json_literals;
+#[cfg(feature = "json")]
 pub use json::JsonFileFormat;
 
 #[cfg(feature = "toml")]
@@ -16,12 +18,19 @@ mod toml;
 #[cfg(feature = "toml")]
 pub use toml::TomlFileFormat;
 
+#[cfg(feature = "toml-edit")]
+mod toml_edit;
+#[cfg(feature = "toml-edit")]
+pub use toml_edit::{TomlEditDeserializeError, TomlEditFileFormat, TomlEditMap};
+
 mod adapters;
 pub(crate) use adapters::{FileFormatKeyAdapter, FileFormatMapAdapter, FileFormatValueAdapter};
 
+mod layout;
+pub(crate) use layout::FormatMapLayout;
+
 mod map;
-pub(crate) use map::FormatToInternalMapError;
-pub use map::RopsFileFormatMap;
+pub use map::{FormatFromInternalMapError, FormatToInternalMapError, RopsFileFormatMap};
 
 #[cfg(feature = "test-utils")]
 mod test_utils;
@@ -45,7 +45,7 @@ macro_rules! generate_file_format_test_suite {
                     fn adapts_from_internal() {
                         pretty_assertions::assert_eq!(
                             RopsFileFormatMap::<EncryptedMap<AES256GCM>, $file_format>::mock(),
-                            RopsMap::mock().to_external()
+                            RopsMap::mock().to_external().unwrap()
                         )
                     }
                 }
@@ -85,20 +85,9 @@ macro_rules! generate_file_format_test_suite {
                 fn adapts_from_internal() {
                     pretty_assertions::assert_eq!(
                         RopsFileFormatMap::<DecryptedMap, $file_format>::mock(),
-                        RopsMap::mock().to_external()
+                        RopsMap::mock().to_external().unwrap()
                     )
                 }
-
-                #[test]
-                #[should_panic]
-                fn disallows_out_of_range_integers() {
-                    assert!(!matches!(
-                        $file_format::key_value_map::<DecryptedMap>("invalid_integer", u64::MAX)
-                            .to_internal()
-                            .unwrap_err(),
-                        FormatToInternalMapError::IntegerOutOfRange(_)
-                    ))
-                }
             }
         }
 
@@ -53,7 +53,7 @@ impl FileFormatValueAdapter for TomlValue {
             // TEMP:
             TomlValue::String(string) if &string == "null" => RopsTree::Null,
             TomlValue::String(string) => RopsTree::Leaf(RopsValue::String(string)),
-            TomlValue::Integer(integer) => RopsTree::Leaf(RopsValue::Integer(integer)),
+            TomlValue::Integer(integer) => RopsTree::Leaf(RopsValue::Integer(integer.into())),
             TomlValue::Float(float) => RopsTree::Leaf(RopsValue::Float(float.into())),
             TomlValue::Array(sequence) => RopsTree::Sequence(
                 sequence
@@ -65,14 +65,19 @@ impl FileFormatValueAdapter for TomlValue {
         })
     }
 
-    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Self {
-        match rops_tree {
-            RopsTree::Sequence(sequence) => TomlValue::Array(sequence.into_iter().map(Self::decrypted_from_internal).collect()),
-            RopsTree::Map(map) => TomlValue::Table(TomlMap::decrypted_from_internal(map)),
+    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match rops_tree {
+            RopsTree::Sequence(sequence) => TomlValue::Array(
+                sequence
+                    .into_iter()
+                    .map(Self::decrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => TomlValue::Table(TomlMap::decrypted_from_internal(map)?),
             // TEMP:
             RopsTree::Null => TomlValue::String("null".to_string()),
-            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value),
-        }
+            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value)?,
+        })
     }
 
     fn encrypted_to_internal<C: Cipher>(
@@ -98,7 +103,7 @@ impl FileFormatValueAdapter for TomlValue {
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(bool.to_string())),
             },
             TomlValue::Integer(integer) => match resolved_partial_encryption.escape_encryption() {
-                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::Integer(integer))),
+                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::Integer(integer.into()))),
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(integer.to_string())),
             },
             TomlValue::Float(float) => match resolved_partial_encryption.escape_encryption() {
@@ -112,29 +117,39 @@ impl FileFormatValueAdapter for TomlValue {
         })
     }
 
-    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Self {
-        match internal_tree {
-            RopsTree::Sequence(sequence) => TomlValue::Array(sequence.into_iter().map(Self::encrypted_from_internal).collect()),
-            RopsTree::Map(map) => TomlValue::Table(TomlMap::encrypted_from_internal(map)),
+    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match internal_tree {
+            RopsTree::Sequence(sequence) => TomlValue::Array(
+                sequence
+                    .into_iter()
+                    .map(Self::encrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => TomlValue::Table(TomlMap::encrypted_from_internal(map)?),
             // TEMP:
             RopsTree::Null => TomlValue::String("null".to_string()),
             RopsTree::Leaf(maybe_encrypted_value) => match maybe_encrypted_value {
                 RopsMapEncryptedLeaf::Encrypted(encrypted_value) => TomlValue::String(encrypted_value.to_string()),
-                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value),
+                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value)?,
             },
-        }
+        })
     }
 }
 
 mod helpers {
     use super::*;
 
-    pub fn from_internal_value(value: RopsValue) -> TomlValue {
-        match value {
-            RopsValue::String(string) => TomlValue::String(string),
-            RopsValue::Boolean(bool) => TomlValue::Boolean(bool),
-            RopsValue::Integer(integer) => TomlValue::Integer(integer),
-            RopsValue::Float(rops_float) => TomlValue::Float(rops_float.into()),
-        }
+    pub fn from_internal_value(mut value: RopsValue) -> Result<TomlValue, FormatFromInternalMapError> {
+        Ok(match &mut value {
+            RopsValue::String(string) => TomlValue::String(std::mem::take(string)),
+            RopsValue::Boolean(bool) => TomlValue::Boolean(*bool),
+            RopsValue::Integer(integer) => {
+                TomlValue::Integer(i64::try_from(&*integer).map_err(|_| FormatFromInternalMapError::IntegerOutOfRange {
+                    format: "TOML",
+                    literal: integer.as_str().to_string(),
+                })?)
+            }
+            RopsValue::Float(rops_float) => TomlValue::Float(f64::from(&*rops_float)),
+        })
     }
 }
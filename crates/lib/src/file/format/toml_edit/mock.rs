@@ -0,0 +1,41 @@
+// Serde output is identical to that of `TomlFileFormat`, only the layout preserving
+// `*_rops_file` and `*_format_map` methods differ.
+
+mod rops_file {
+    use std::{fmt::Display, str::FromStr};
+
+    use crate::*;
+
+    impl<S: RopsFileState> MockFileFormatUtil<TomlEditFileFormat> for RopsFile<S, TomlEditFileFormat>
+    where
+        RopsFile<S, TomlFileFormat>: MockFileFormatUtil<TomlFileFormat>,
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        fn mock_format_display() -> String {
+            RopsFile::<S, TomlFileFormat>::mock_format_display()
+        }
+    }
+}
+
+mod map {
+    use crate::*;
+
+    impl<S: RopsMapState> MockFileFormatUtil<TomlEditFileFormat> for RopsFileFormatMap<S, TomlEditFileFormat>
+    where
+        RopsFileFormatMap<S, TomlFileFormat>: MockFileFormatUtil<TomlFileFormat>,
+    {
+        fn mock_format_display() -> String {
+            RopsFileFormatMap::<S, TomlFileFormat>::mock_format_display()
+        }
+    }
+}
+
+mod metadata {
+    use crate::*;
+
+    impl<T: MockFileFormatUtil<TomlFileFormat>> MockFileFormatUtil<TomlEditFileFormat> for T {
+        fn mock_format_display() -> String {
+            <T as MockFileFormatUtil<TomlFileFormat>>::mock_format_display()
+        }
+    }
+}
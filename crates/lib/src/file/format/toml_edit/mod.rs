@@ -0,0 +1,481 @@
+#[cfg(feature = "test-utils")]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use toml_edit::{DocumentMut, Formatted, Item, Repr, Table, Value};
+
+use crate::*;
+
+/// Layout preserving alternative to [`TomlFileFormat`].
+///
+/// Comments, whitespace, key ordering, and table styles are retained when going from an
+/// encrypted file to a decrypted one and back. Values are otherwise handled like in
+/// [`TomlFileFormat`], including its unresolved questions.
+#[derive(Debug, PartialEq)]
+pub struct TomlEditFileFormat;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TomlEditDeserializeError {
+    #[error("unable to parse TOML document: {0}")]
+    Document(#[from] toml_edit::TomlError),
+    #[error(transparent)]
+    Serde(#[from] toml::de::Error),
+}
+
+impl FileFormat for TomlEditFileFormat {
+    type Map = TomlEditMap;
+
+    type SerializeError = toml::ser::Error;
+    type DeserializeError = TomlEditDeserializeError;
+
+    fn serialize_to_string<T: Serialize>(t: &T) -> Result<String, Self::SerializeError> {
+        toml::to_string_pretty(t)
+    }
+
+    fn deserialize_from_str<T: DeserializeOwned>(str: &str) -> Result<T, Self::DeserializeError> {
+        toml::from_str(str).map_err(Into::into)
+    }
+
+    fn serialize_rops_file<S: RopsFileState>(rops_file: &RopsFile<S, Self>) -> Result<String, Self::SerializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        let metadata_document = toml::to_string_pretty(&MetadataTable {
+            sops: rops_file.metadata(),
+        })?
+        .parse::<DocumentMut>()
+        .expect("serialized metadata should be a valid TOML document");
+
+        let mut document = rops_file.map().inner_map().0.clone();
+        helpers::append_tables(&mut document, metadata_document.into_table());
+
+        Ok(document.to_string())
+    }
+
+    fn deserialize_rops_file<S: RopsFileState>(str: &str) -> Result<RopsFile<S, Self>, Self::DeserializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        let MetadataTable { sops: metadata } = toml::from_str(str)?;
+
+        let mut document = str.parse::<DocumentMut>()?;
+        document.remove(METADATA_KEY);
+
+        Ok(RopsFile::from_parts(
+            RopsFileFormatMap::from_inner_map(TomlEditMap(document)),
+            metadata,
+        ))
+    }
+
+    fn serialize_format_map<S: RopsMapState>(format_map: &RopsFileFormatMap<S, Self>) -> Result<String, Self::SerializeError> {
+        Ok(format_map.inner_map().0.to_string())
+    }
+
+    fn deserialize_format_map<S: RopsMapState>(str: &str) -> Result<RopsFileFormatMap<S, Self>, Self::DeserializeError> {
+        str.parse()
+            .map(|document| RopsFileFormatMap::from_inner_map(TomlEditMap(document)))
+            .map_err(Into::into)
+    }
+}
+
+const METADATA_KEY: &str = "sops";
+
+#[derive(Serialize, Deserialize)]
+struct MetadataTable<M> {
+    sops: M,
+}
+
+/// TOML document retaining its original formatting.
+#[derive(Debug, Clone, Default)]
+pub struct TomlEditMap(DocumentMut);
+
+impl TomlEditMap {
+    fn from_table(table: Table) -> Self {
+        Self(table.into())
+    }
+
+    fn to_toml_table(&self) -> Result<toml::Table, toml::de::Error> {
+        self.0.to_string().parse()
+    }
+}
+
+impl PartialEq for TomlEditMap {
+    fn eq(&self, other: &Self) -> bool {
+        // Formatting is deliberately disregarded, unless either document can't be converted.
+        match (self.to_toml_table(), other.to_toml_table()) {
+            (Ok(table), Ok(other_table)) => table == other_table,
+            _ => self.0.to_string() == other.0.to_string(),
+        }
+    }
+}
+
+impl Serialize for TomlEditMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_toml_table().map_err(serde::ser::Error::custom)?.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TomlEditMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let toml_table = toml::Table::deserialize(deserializer)?;
+        toml::to_string(&toml_table)
+            .map_err(D::Error::custom)?
+            .parse()
+            .map(Self)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl IntoIterator for TomlEditMap {
+    type Item = (String, Item);
+    type IntoIter = <Table as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_table().into_iter()
+    }
+}
+
+impl FileFormatMapAdapter for TomlEditMap {
+    type Key = String;
+    type Value = Item;
+
+    fn with_capacity(_capacity: usize) -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, key: Self::Key, value: Self::Value) {
+        self.0.insert(&key, value);
+    }
+
+    fn layout(&self) -> Option<FormatMapLayout> {
+        Some(FormatMapLayout::new(self.0.clone()))
+    }
+
+    fn restore_layout(&mut self, layout: FormatMapLayout) {
+        if let Some(mut layout_document) = layout.downcast::<DocumentMut>() {
+            let values = std::mem::take(&mut self.0).into_table();
+            layout::patch_table(layout_document.as_table_mut(), values);
+            self.0 = layout_document;
+        }
+    }
+}
+
+impl FileFormatValueAdapter for Item {
+    fn decrypted_to_internal(self) -> Result<RopsTree<DecryptedMap>, FormatToInternalMapError> {
+        Ok(match self {
+            Item::None => RopsTree::Null,
+            Item::Table(table) => RopsTree::Map(TomlEditMap::from_table(table).decrypted_to_internal()?),
+            Item::ArrayOfTables(tables) => RopsTree::Sequence(
+                tables
+                    .into_iter()
+                    .map(|table| Item::Table(table).decrypted_to_internal())
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Item::Value(value) => match value {
+                Value::InlineTable(inline_table) => Item::Table(inline_table.into_table()).decrypted_to_internal()?,
+                Value::Array(array) => RopsTree::Sequence(
+                    array
+                        .into_iter()
+                        .map(|value| Item::Value(value).decrypted_to_internal())
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                Value::Boolean(boolean) => RopsTree::Leaf(RopsValue::Boolean(boolean.into_value())),
+                // TEMP:
+                Value::String(string) if string.value() == "null" => RopsTree::Null,
+                Value::String(string) => RopsTree::Leaf(RopsValue::String(string.into_value())),
+                Value::Integer(integer) => RopsTree::Leaf(RopsValue::Integer(helpers::to_internal_integer(integer))),
+                Value::Float(float) => RopsTree::Leaf(RopsValue::Float(helpers::to_internal_float(float))),
+                Value::Datetime(datetime) => RopsTree::Leaf(RopsValue::String(datetime.into_value().to_string())),
+            },
+        })
+    }
+
+    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match rops_tree {
+            RopsTree::Sequence(sequence) => helpers::array(
+                sequence
+                    .into_iter()
+                    .map(Self::decrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => Item::Table(TomlEditMap::decrypted_from_internal(map)?.0.into_table()),
+            // TEMP:
+            RopsTree::Null => toml_edit::value("null"),
+            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value)?,
+        })
+    }
+
+    fn encrypted_to_internal<C: Cipher>(
+        self,
+        resolved_partial_encryption: ResolvedPartialEncryption,
+    ) -> Result<RopsTree<EncryptedMap<C>>, FormatToInternalMapError> {
+        Ok(match self {
+            Item::None => RopsTree::Null,
+            Item::Table(table) => RopsTree::Map(
+                TomlEditMap::from_table(table).encrypted_to_internal(resolved_partial_encryption, Self::encrypted_to_internal)?,
+            ),
+            Item::ArrayOfTables(tables) => RopsTree::Sequence(
+                tables
+                    .into_iter()
+                    .map(|table| Item::Table(table).encrypted_to_internal(resolved_partial_encryption))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Item::Value(value) => match value {
+                Value::InlineTable(inline_table) => {
+                    Item::Table(inline_table.into_table()).encrypted_to_internal(resolved_partial_encryption)?
+                }
+                Value::Array(array) => RopsTree::Sequence(
+                    array
+                        .into_iter()
+                        .map(|value| Item::Value(value).encrypted_to_internal(resolved_partial_encryption))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+                // TEMP:
+                Value::String(string) if string.value() == "null" => RopsTree::Null,
+                Value::String(string) => {
+                    let string = string.into_value();
+                    match resolved_partial_encryption.escape_encryption() || string.is_empty() {
+                        true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::String(string))),
+                        false => RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(string.parse()?)),
+                    }
+                }
+                Value::Boolean(bool) => match resolved_partial_encryption.escape_encryption() {
+                    true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::Boolean(bool.into_value()))),
+                    false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(bool.to_string())),
+                },
+                Value::Integer(integer) => match resolved_partial_encryption.escape_encryption() {
+                    true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::Integer(helpers::to_internal_integer(
+                        integer,
+                    )))),
+                    false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(integer.to_string())),
+                },
+                Value::Float(float) => match resolved_partial_encryption.escape_encryption() {
+                    true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::Float(helpers::to_internal_float(float)))),
+                    false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(float.to_string())),
+                },
+                Value::Datetime(datetime) => match resolved_partial_encryption.escape_encryption() {
+                    true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(RopsValue::String(datetime.into_value().to_string()))),
+                    false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(datetime.to_string())),
+                },
+            },
+        })
+    }
+
+    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match internal_tree {
+            RopsTree::Sequence(sequence) => helpers::array(
+                sequence
+                    .into_iter()
+                    .map(Self::encrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => Item::Table(TomlEditMap::encrypted_from_internal(map)?.0.into_table()),
+            // TEMP:
+            RopsTree::Null => toml_edit::value("null"),
+            RopsTree::Leaf(maybe_encrypted_value) => match maybe_encrypted_value {
+                RopsMapEncryptedLeaf::Encrypted(encrypted_value) => toml_edit::value(encrypted_value.to_string()),
+                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value)?,
+            },
+        })
+    }
+}
+
+mod helpers {
+    use toml_edit::Array;
+
+    use super::*;
+
+    pub fn from_internal_value(mut value: RopsValue) -> Result<Item, FormatFromInternalMapError> {
+        Ok(match &mut value {
+            RopsValue::String(string) => toml_edit::value(std::mem::take(string)),
+            RopsValue::Boolean(bool) => toml_edit::value(*bool),
+            // Literals of other formats are written by value when TOML has no notation for them, e.g. `-0x1F`.
+            RopsValue::Integer(integer) => match integer.as_str().parse::<Value>() {
+                Ok(mut integer @ Value::Integer(_)) => {
+                    integer.decor_mut().clear();
+                    Item::Value(integer)
+                }
+                _ => toml_edit::value(i64::try_from(&*integer).map_err(|_| FormatFromInternalMapError::IntegerOutOfRange {
+                    format: "TOML",
+                    literal: integer.as_str().to_string(),
+                })?),
+            },
+            RopsValue::Float(rops_float) => match rops_float.as_str().parse::<Value>() {
+                Ok(mut float @ Value::Float(_)) => {
+                    float.decor_mut().clear();
+                    Item::Value(float)
+                }
+                _ => toml_edit::value(f64::from(&*rops_float)),
+            },
+        })
+    }
+
+    pub fn to_internal_integer(integer: Formatted<i64>) -> RopsInteger {
+        literal(integer.as_repr())
+            .and_then(|literal| literal.parse().ok())
+            .unwrap_or_else(|| integer.into_value().into())
+    }
+
+    pub fn to_internal_float(float: Formatted<f64>) -> RopsFloat {
+        literal(float.as_repr())
+            .and_then(|literal| literal.parse().ok())
+            .unwrap_or_else(|| float.into_value().into())
+    }
+
+    /// Source text of parsed values, e.g. `0o17` or `1_000.0`.
+    fn literal(repr: Option<&Repr>) -> Option<&str> {
+        repr.and_then(|repr| repr.as_raw().as_str())
+    }
+
+    /// Tables within arrays are always written inline.
+    pub fn array(items: Vec<Item>) -> Item {
+        let array = items
+            .into_iter()
+            .map(|item| match item {
+                Item::Table(table) => Value::InlineTable(table.into_inline_table()),
+                Item::ArrayOfTables(tables) => Value::Array(tables.into_array()),
+                Item::Value(value) => value,
+                Item::None => unreachable!("internal map to never produce empty items"),
+            })
+            .collect::<Array>();
+
+        toml_edit::value(array)
+    }
+
+    /// Positions the tables after the existing ones so that they're rendered last.
+    pub fn append_tables(document: &mut DocumentMut, tables: Table) {
+        let mut next_position = max_position(document.as_table()).map(|position| position + 1).unwrap_or_default();
+
+        for (key, mut item) in tables {
+            if let Item::Table(table) = &mut item {
+                table.decor_mut().clear();
+                reposition(table, &mut next_position);
+            }
+            document.insert(&key, item);
+        }
+
+        fn max_position(table: &Table) -> Option<isize> {
+            table
+                .iter()
+                .map(|(_, item)| match item {
+                    Item::Table(table) => max_position(table),
+                    Item::ArrayOfTables(tables) => tables.iter().filter_map(max_position).max(),
+                    _ => None,
+                })
+                .fold(table.position(), Ord::max)
+        }
+
+        fn reposition(table: &mut Table, next_position: &mut isize) {
+            table.set_position(Some(*next_position));
+            *next_position += 1;
+
+            for (_, item) in table.iter_mut() {
+                match item {
+                    Item::Table(table) => reposition(table, next_position),
+                    Item::ArrayOfTables(tables) => tables.iter_mut().for_each(|table| reposition(table, next_position)),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Merges freshly converted values into a previous revision of the document. Entries are
+/// matched by key or sequence index; unchanged values keep their original representation,
+/// changed values keep the surrounding decor.
+mod layout {
+    use toml_edit::{Array, InlineTable};
+
+    use super::*;
+
+    pub fn patch_table(layout: &mut Table, values: Table) {
+        layout.retain(|key, _| values.contains_key(key));
+
+        for (key, item) in values {
+            match layout.get_mut(&key) {
+                Some(layout_item) => patch_item(layout_item, item),
+                None => {
+                    layout.insert(&key, item);
+                }
+            }
+        }
+    }
+
+    fn patch_item(layout: &mut Item, item: Item) {
+        match (layout, item) {
+            (Item::Table(layout_table), Item::Table(table)) => patch_table(layout_table, table),
+            (Item::ArrayOfTables(layout_tables), Item::Value(Value::Array(array)))
+                if layout_tables.len() == array.len() && array.iter().all(Value::is_inline_table) =>
+            {
+                for (layout_table, value) in layout_tables.iter_mut().zip(array) {
+                    if let Value::InlineTable(inline_table) = value {
+                        patch_table(layout_table, inline_table.into_table())
+                    }
+                }
+            }
+            (Item::Value(layout_value @ Value::InlineTable(_)), Item::Table(table)) => {
+                patch_value(layout_value, Value::InlineTable(table.into_inline_table()))
+            }
+            (Item::Value(layout_value), Item::Value(value)) => patch_value(layout_value, value),
+            (layout, item) => *layout = item,
+        }
+    }
+
+    fn patch_value(layout: &mut Value, value: Value) {
+        match (layout, value) {
+            (Value::Array(layout_array), Value::Array(array)) => patch_array(layout_array, array),
+            (Value::InlineTable(layout_table), Value::InlineTable(inline_table)) => patch_inline_table(layout_table, inline_table),
+            (layout, value) if is_unchanged(layout, &value) => (),
+            (layout, mut value) => {
+                *value.decor_mut() = layout.decor().clone();
+                *layout = value;
+            }
+        }
+    }
+
+    fn patch_array(layout: &mut Array, array: Array) {
+        while layout.len() > array.len() {
+            layout.remove(layout.len() - 1);
+        }
+
+        for (index, value) in array.into_iter().enumerate() {
+            match layout.get_mut(index) {
+                Some(layout_value) => patch_value(layout_value, value),
+                None => layout.push(value),
+            }
+        }
+    }
+
+    fn patch_inline_table(layout: &mut InlineTable, inline_table: InlineTable) {
+        layout.retain(|key, _| inline_table.contains_key(key));
+
+        for (key, value) in inline_table {
+            match layout.get_mut(&key) {
+                Some(layout_value) => patch_value(layout_value, value),
+                None => {
+                    layout.insert(key, value);
+                }
+            }
+        }
+    }
+
+    fn is_unchanged(layout: &Value, value: &Value) -> bool {
+        match (layout, value) {
+            (Value::String(layout), Value::String(value)) => layout.value() == value.value(),
+            (Value::Integer(layout), Value::Integer(value)) => layout.value() == value.value(),
+            (Value::Float(layout), Value::Float(value)) => layout.value().to_bits() == value.value().to_bits(),
+            (Value::Boolean(layout), Value::Boolean(value)) => layout.value() == value.value(),
+            (Value::Datetime(layout), Value::Datetime(value)) => layout.value() == value.value(),
+            // Datetimes are converted to strings internally.
+            (Value::Datetime(layout), Value::String(value)) => &layout.value().to_string() == value.value(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt::Display;
+
+use crate::*;
+
+impl FileFormatTestSuiteUtils for TomlEditFileFormat {
+    fn simple_map(key: impl Display, value: impl Display) -> String {
+        format!("{key} = {value}")
+    }
+}
+
+generate_file_format_test_suite!(TomlEditFileFormat);
+
+#[cfg(all(feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod layout {
+    use crate::*;
+
+    type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, TomlEditFileFormat>;
+
+    const PLAINTEXT: &str = indoc::indoc! {"
+        # Leading comment
+        hello = \"world!\" # Trailing comment
+
+        [nested_map]
+        integers = [ 1, 2 ]
+        inline = { key = \"value\" }
+
+        [[array_of_tables]]
+        float = 1.5
+    "};
+
+    fn encrypted_file() -> EncryptedRopsFile {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        RopsFileBuilder::<TomlEditFileFormat>::new(PLAINTEXT)
+            .unwrap()
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt()
+            .unwrap()
+    }
+
+    #[test]
+    fn retains_layout_on_encryption() {
+        let encrypted_file_string = encrypted_file().to_string();
+
+        assert!(encrypted_file_string.starts_with("# Leading comment\nhello = \"ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains(",type:str]\" # Trailing comment\n\n[nested_map]\nintegers = [ \"ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\n\n[[array_of_tables]]\nfloat = \"ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\n\n[sops]\n"));
+    }
+
+    #[test]
+    fn retains_layout_on_decryption() {
+        let encrypted_file_string = encrypted_file().to_string();
+        let decrypted_file = encrypted_file_string
+            .parse::<EncryptedRopsFile>()
+            .unwrap()
+            .decrypt::<TomlEditFileFormat>()
+            .unwrap();
+
+        pretty_assertions::assert_eq!(PLAINTEXT, decrypted_file.map().to_string())
+    }
+
+    #[test]
+    fn only_reencrypts_changed_values() {
+        let encrypted_file_string = encrypted_file().to_string();
+        let (decrypted_file, saved_parameters) = encrypted_file_string
+            .parse::<EncryptedRopsFile>()
+            .unwrap()
+            .decrypt_and_save_parameters::<TomlEditFileFormat>()
+            .unwrap();
+
+        let edited_map = decrypted_file
+            .map()
+            .to_string()
+            .replace("float = 1.5", "float = 2.5 # Edited")
+            .parse()
+            .unwrap();
+
+        let reencrypted_file_string = decrypted_file
+            .set_map(edited_map)
+            .unwrap()
+            .encrypt_with_saved_parameters::<AES256GCM, TomlEditFileFormat>(saved_parameters)
+            .unwrap()
+            .to_string();
+
+        let changed_map_lines = encrypted_file_string
+            .lines()
+            .zip(reencrypted_file_string.lines())
+            .take_while(|(previous, _)| *previous != "[sops]")
+            .filter(|(previous, current)| previous != current)
+            .map(|(_, current)| current.split_once(" = ").unwrap().0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["float"], changed_map_lines);
+        assert!(reencrypted_file_string.contains(",type:float]\" # Edited\n"));
+    }
+}
+
+#[cfg(all(feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod numbers {
+    use crate::*;
+
+    type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, TomlEditFileFormat>;
+
+    const PLAINTEXT: &str = indoc::indoc! {"
+        min = -9223372036854775808
+        positive = +1
+        separated = 1_000
+        hexadecimal = 0xdead_beef
+        octal = 0o17
+        binary = 0b101
+        float = 1.0
+        trailing_zero = 1.50
+        exponent = 1e10
+        uppercase_exponent = 1E+10
+        separated_float = 1_000.5
+        precise = 0.10000000000000000001
+        infinity = inf
+    "};
+
+    #[test]
+    fn retains_number_literals() {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let decrypted_file = RopsFileBuilder::<TomlEditFileFormat>::new(PLAINTEXT)
+            .unwrap()
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt::<AES256GCM, SHA512>()
+            .unwrap()
+            .to_string()
+            .parse::<EncryptedRopsFile>()
+            .unwrap()
+            .decrypt::<TomlEditFileFormat>()
+            .unwrap();
+
+        pretty_assertions::assert_eq!(PLAINTEXT, decrypted_file.map().to_string())
+    }
+}
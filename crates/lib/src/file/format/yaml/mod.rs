@@ -4,6 +4,8 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+use std::{fmt::Display, str::FromStr};
+
 use serde::{de::DeserializeOwned, Serialize};
 use serde_yaml::{Mapping as YamlMap, Value as YamlValue};
 
@@ -18,15 +20,93 @@ impl FileFormat for YamlFileFormat {
     type SerializeError = serde_yaml::Error;
     type DeserializeError = serde_yaml::Error;
 
+    const MULTIPLE_DOCUMENTS: bool = true;
+
     fn serialize_to_string<T: Serialize>(t: &T) -> Result<String, Self::SerializeError> {
         serde_yaml::to_string(t)
     }
 
     fn deserialize_from_str<T: DeserializeOwned>(str: &str) -> Result<T, Self::DeserializeError> {
-        serde_yaml::from_str(str)
+        serde_yaml::from_str(str).map_err(helpers::explain_integer_range)
+    }
+
+    fn serialize_rops_file<S: RopsFileState>(rops_file: &RopsFile<S, Self>) -> Result<String, Self::SerializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        let mut map = rops_file.map().inner_map().clone();
+        map.insert(
+            YamlValue::String(METADATA_KEY.to_string()),
+            serde_yaml::to_value(rops_file.metadata())?,
+        );
+        literals::serialize_map(map)
+    }
+
+    // Flattened maps are buffered by serde in a way which can't represent tagged values.
+    fn deserialize_rops_file<S: RopsFileState>(str: &str) -> Result<RopsFile<S, Self>, Self::DeserializeError>
+    where
+        <<S::MetadataState as RopsMetadataState>::Mac as FromStr>::Err: Display,
+    {
+        let mut map = literals::deserialize_map(str)?;
+        let metadata = map
+            .shift_remove(METADATA_KEY)
+            .ok_or_else(|| <serde_yaml::Error as serde::de::Error>::missing_field(METADATA_KEY))?;
+
+        Ok(RopsFile::from_parts(
+            RopsFileFormatMap::from_inner_map(map),
+            serde_yaml::from_value(metadata)?,
+        ))
+    }
+
+    fn serialize_format_map<S: RopsMapState>(format_map: &RopsFileFormatMap<S, Self>) -> Result<String, Self::SerializeError> {
+        literals::serialize_map(format_map.inner_map().clone())
+    }
+
+    fn deserialize_format_map<S: RopsMapState>(str: &str) -> Result<RopsFileFormatMap<S, Self>, Self::DeserializeError> {
+        literals::deserialize_map(str).map(RopsFileFormatMap::from_inner_map)
+    }
+
+    fn split_documents(str: &str) -> Vec<String> {
+        let mut documents = vec![String::new()];
+
+        for line in str.split_inclusive('\n') {
+            let line_content = line.trim_end_matches(['\n', '\r']);
+
+            if let Some(remainder) = helpers::strip_marker(line_content, DOCUMENT_START_MARKER) {
+                // Content such as `--- !tag` or `--- {}` may follow the marker.
+                documents.push(match remainder.is_empty() {
+                    true => String::new(),
+                    false => format!("{}\n", remainder),
+                });
+            } else if helpers::strip_marker(line_content, DOCUMENT_END_MARKER).is_none() {
+                documents.last_mut().expect("documents initialized as non-empty").push_str(line);
+            }
+        }
+
+        let documents = helpers::attach_contentless_chunks(documents);
+
+        match documents.len() > 1 {
+            true => documents,
+            false => vec![str.to_string()],
+        }
+    }
+
+    fn join_documents(mut documents: Vec<String>) -> String {
+        if documents.len() > 1 {
+            documents
+                .iter_mut()
+                .filter(|document| !document.ends_with('\n'))
+                .for_each(|document| document.push('\n'));
+        }
+
+        documents.join(&format!("{}\n", DOCUMENT_START_MARKER))
     }
 }
 
+const METADATA_KEY: &str = "sops";
+const DOCUMENT_START_MARKER: &str = "---";
+const DOCUMENT_END_MARKER: &str = "...";
+
 impl FileFormatMapAdapter for YamlMap {
     type Key = YamlValue;
     type Value = YamlValue;
@@ -38,6 +118,17 @@ impl FileFormatMapAdapter for YamlMap {
     fn insert(&mut self, key: Self::Key, value: Self::Value) {
         self.insert(key, value);
     }
+
+    fn layout(&self) -> Option<FormatMapLayout> {
+        let tags = tags::YamlTags::collect(self);
+        (!tags.is_empty()).then(|| FormatMapLayout::new(tags))
+    }
+
+    fn restore_layout(&mut self, layout: FormatMapLayout) {
+        if let Some(tags) = layout.downcast::<tags::YamlTags>() {
+            tags.restore(self)
+        }
+    }
 }
 
 impl FileFormatKeyAdapter for YamlValue {
@@ -58,14 +149,14 @@ impl FileFormatKeyAdapter for YamlValue {
 impl FileFormatValueAdapter for YamlValue {
     fn decrypted_to_internal(self) -> Result<RopsTree<DecryptedMap>, FormatToInternalMapError> {
         Ok(match self {
-            // SOPS simply throws away tags, so do we for now.
-            // It can, however, deserialize manually added tags to encrypted documents,
-            // so we could in theory keep the tags somewhere without breaking SOPS compatibility.
+            YamlValue::Tagged(tagged) if literals::is_literal(&tagged) => RopsTree::Leaf(literals::to_internal(*tagged)),
+            // Tags are kept aside as a map layout and re-applied once converted back, SOPS
+            // simply throws them away but deserializes tagged values of encrypted documents.
             YamlValue::Tagged(tagged) => tagged.value.decrypted_to_internal()?,
             YamlValue::Mapping(map) => RopsTree::Map(YamlMap::decrypted_to_internal(map)?),
             YamlValue::Bool(boolean) => RopsTree::Leaf(RopsValue::Boolean(boolean)),
             YamlValue::String(string) => RopsTree::Leaf(RopsValue::String(string)),
-            YamlValue::Number(number) => RopsTree::Leaf(helpers::to_internal_number(number)?),
+            YamlValue::Number(number) => RopsTree::Leaf(literals::to_internal_number(number)),
             YamlValue::Sequence(sequence) => RopsTree::Sequence(
                 sequence
                     .into_iter()
@@ -76,13 +167,18 @@ impl FileFormatValueAdapter for YamlValue {
         })
     }
 
-    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Self {
-        match rops_tree {
-            RopsTree::Sequence(sequence) => YamlValue::Sequence(sequence.into_iter().map(Self::decrypted_from_internal).collect()),
-            RopsTree::Map(map) => YamlValue::Mapping(YamlMap::decrypted_from_internal(map)),
+    fn decrypted_from_internal(rops_tree: RopsTree<DecryptedMap>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match rops_tree {
+            RopsTree::Sequence(sequence) => YamlValue::Sequence(
+                sequence
+                    .into_iter()
+                    .map(Self::decrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => YamlValue::Mapping(YamlMap::decrypted_from_internal(map)?),
             RopsTree::Null => YamlValue::Null,
-            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value),
-        }
+            RopsTree::Leaf(decrypted_value) => helpers::from_internal_value(decrypted_value)?,
+        })
     }
 
     fn encrypted_to_internal<C: Cipher>(
@@ -90,6 +186,14 @@ impl FileFormatValueAdapter for YamlValue {
         resolved_partial_encryption: ResolvedPartialEncryption,
     ) -> Result<RopsTree<EncryptedMap<C>>, FormatToInternalMapError> {
         Ok(match self {
+            YamlValue::Tagged(tagged) if literals::is_literal(&tagged) => match resolved_partial_encryption.escape_encryption() {
+                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(literals::to_internal(*tagged))),
+                false => {
+                    return Err(FormatToInternalMapError::PlaintextWhenEncrypted(
+                        tagged.value.as_str().unwrap_or_default().to_string(),
+                    ))
+                }
+            },
             YamlValue::Tagged(tagged) => tagged.value.encrypted_to_internal(resolved_partial_encryption)?,
             YamlValue::Mapping(map) => RopsTree::Map(map.encrypted_to_internal(resolved_partial_encryption, Self::encrypted_to_internal)?),
             YamlValue::String(string) => match resolved_partial_encryption.escape_encryption() || string.is_empty() {
@@ -108,45 +212,498 @@ impl FileFormatValueAdapter for YamlValue {
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(bool.to_string())),
             },
             YamlValue::Number(number) => match resolved_partial_encryption.escape_encryption() {
-                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(helpers::to_internal_number(number)?)),
+                true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(literals::to_internal_number(number))),
                 false => return Err(FormatToInternalMapError::PlaintextWhenEncrypted(number.to_string())),
             },
         })
     }
 
-    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Self {
-        match internal_tree {
-            RopsTree::Sequence(sequence) => YamlValue::Sequence(sequence.into_iter().map(Self::encrypted_from_internal).collect()),
-            RopsTree::Map(map) => YamlValue::Mapping(YamlMap::encrypted_from_internal(map)),
+    fn encrypted_from_internal<C: Cipher>(internal_tree: RopsTree<EncryptedMap<C>>) -> Result<Self, FormatFromInternalMapError> {
+        Ok(match internal_tree {
+            RopsTree::Sequence(sequence) => YamlValue::Sequence(
+                sequence
+                    .into_iter()
+                    .map(Self::encrypted_from_internal)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            RopsTree::Map(map) => YamlValue::Mapping(YamlMap::encrypted_from_internal(map)?),
             RopsTree::Null => YamlValue::Null,
             RopsTree::Leaf(maybe_encrypted_value) => match maybe_encrypted_value {
                 RopsMapEncryptedLeaf::Encrypted(encrypted_value) => YamlValue::String(encrypted_value.to_string()),
-                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value),
+                RopsMapEncryptedLeaf::Escaped(escaped_value) => helpers::from_internal_value(escaped_value)?,
             },
+        })
+    }
+}
+
+mod tags {
+    use serde_yaml::value::{Tag, TaggedValue};
+
+    use super::*;
+
+    #[derive(Clone)]
+    enum PathSegment {
+        Key(YamlValue),
+        Index(usize),
+    }
+
+    /// Tags and the paths of the values they were attached to.
+    pub struct YamlTags(Vec<(Vec<PathSegment>, Tag)>);
+
+    impl YamlTags {
+        pub fn collect(map: &YamlMap) -> Self {
+            let mut tags = Vec::new();
+            collect_map(map, &mut Vec::new(), &mut tags);
+            Self(tags)
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        /// Paths no longer present in the map are skipped, already tagged values are retagged.
+        pub fn restore(self, map: &mut YamlMap) {
+            for (path, tag) in self.0 {
+                match get_mut(map, &path) {
+                    Some(YamlValue::Tagged(tagged)) if !literals::is_literal(tagged) => tagged.tag = tag,
+                    Some(value) => {
+                        let untagged_value = std::mem::replace(value, YamlValue::Null);
+                        *value = YamlValue::Tagged(Box::new(TaggedValue {
+                            tag,
+                            value: untagged_value,
+                        }));
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+
+    fn collect_map(map: &YamlMap, path: &mut Vec<PathSegment>, tags: &mut Vec<(Vec<PathSegment>, Tag)>) {
+        for (key, value) in map {
+            path.push(PathSegment::Key(key.clone()));
+            collect_value(value, path, tags);
+            path.pop();
         }
     }
+
+    fn collect_value(value: &YamlValue, path: &mut Vec<PathSegment>, tags: &mut Vec<(Vec<PathSegment>, Tag)>) {
+        match value {
+            YamlValue::Tagged(tagged) if literals::is_literal(tagged) => (),
+            YamlValue::Tagged(tagged) => {
+                tags.push((path.to_vec(), tagged.tag.clone()));
+                collect_value(&tagged.value, path, tags);
+            }
+            YamlValue::Mapping(map) => collect_map(map, path, tags),
+            YamlValue::Sequence(sequence) => {
+                for (index, value) in sequence.iter().enumerate() {
+                    path.push(PathSegment::Index(index));
+                    collect_value(value, path, tags);
+                    path.pop();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Looks past the tags of values along the path, but not of the value itself.
+    fn get_mut<'a>(map: &'a mut YamlMap, path: &[PathSegment]) -> Option<&'a mut YamlValue> {
+        let (PathSegment::Key(key), remaining_path) = path.split_first()? else {
+            return None;
+        };
+
+        remaining_path.iter().try_fold(map.get_mut(key)?, |mut value, segment| {
+            while let YamlValue::Tagged(tagged) = value {
+                value = &mut tagged.value;
+            }
+
+            match (value, segment) {
+                (YamlValue::Mapping(map), PathSegment::Key(key)) => map.get_mut(key),
+                (YamlValue::Sequence(sequence), PathSegment::Index(index)) => sequence.get_mut(*index),
+                _ => None,
+            }
+        })
+    }
 }
 
-mod helpers {
+mod literals {
+    use std::fmt::Formatter;
+
+    use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, IgnoredAny, MapAccess, SeqAccess, VariantAccess, Visitor};
+    use serde_yaml::value::{Tag, TaggedValue};
+
     use super::*;
 
-    pub fn to_internal_number(number: serde_yaml::Number) -> Result<RopsValue, FormatToInternalMapError> {
-        Ok(match number.is_f64() {
-            true => RopsValue::Float(number.as_f64().expect("number not a f64").into()),
-            false => RopsValue::Integer(
-                number
-                    .as_i64()
-                    .ok_or_else(|| FormatToInternalMapError::IntegerOutOfRange(number.as_u64().expect("number not an u64")))?,
-            ),
+    // Number literals which `serde_yaml` wouldn't write as is, e.g. `0x1F` or `1e10`, are held as
+    // strings under a tag that no document can contain, and written in place of plain placeholders.
+    const LITERAL_TAG: &str = "rops number literal";
+
+    #[derive(Clone, Copy, PartialEq)]
+    pub enum NumberKind {
+        Integer,
+        Float,
+    }
+
+    pub fn is_literal(tagged: &TaggedValue) -> bool {
+        tagged.tag == LITERAL_TAG
+    }
+
+    fn literal(literal: &str) -> YamlValue {
+        YamlValue::Tagged(Box::new(TaggedValue {
+            tag: Tag::new(LITERAL_TAG),
+            value: YamlValue::String(literal.to_string()),
+        }))
+    }
+
+    pub fn to_internal(tagged: TaggedValue) -> RopsValue {
+        let YamlValue::String(literal) = tagged.value else {
+            unreachable!("number literals to be held as strings")
+        };
+        to_internal_literal(literal)
+    }
+
+    // Numbers are only left as is when `serde_yaml` writes them just as they were written.
+    pub fn to_internal_number(number: serde_yaml::Number) -> RopsValue {
+        to_internal_literal(number.to_string())
+    }
+
+    fn to_internal_literal(literal: String) -> RopsValue {
+        match number_kind(&literal) {
+            Some(NumberKind::Integer) => RopsValue::Integer(literal.parse().expect("number not a valid integer literal")),
+            Some(NumberKind::Float) => RopsValue::Float(literal.parse().expect("number not a valid float literal")),
+            None => unreachable!("literal not a number: {literal}"),
+        }
+    }
+
+    /// Literals are only used if read back as the same kind of number.
+    pub fn from_internal(literal: &str, kind: NumberKind) -> Option<YamlValue> {
+        (number_kind(literal)? == kind).then(|| match literal.parse::<serde_yaml::Number>() {
+            Ok(number) if number.to_string() == literal => YamlValue::Number(number),
+            _ => self::literal(literal),
         })
     }
 
-    pub fn from_internal_value(value: RopsValue) -> YamlValue {
+    /// How an untagged plain scalar is read, if as a number at all.
+    fn number_kind(literal: &str) -> Option<NumberKind> {
+        struct NumberKindVisitor;
+
+        impl Visitor<'_> for NumberKindVisitor {
+            type Value = NumberKind;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("a number")
+            }
+
+            fn visit_i64<E: de::Error>(self, _: i64) -> Result<Self::Value, E> {
+                Ok(NumberKind::Integer)
+            }
+
+            fn visit_u64<E: de::Error>(self, _: u64) -> Result<Self::Value, E> {
+                Ok(NumberKind::Integer)
+            }
+
+            fn visit_i128<E: de::Error>(self, _: i128) -> Result<Self::Value, E> {
+                Ok(NumberKind::Integer)
+            }
+
+            fn visit_u128<E: de::Error>(self, _: u128) -> Result<Self::Value, E> {
+                Ok(NumberKind::Integer)
+            }
+
+            fn visit_f64<E: de::Error>(self, _: f64) -> Result<Self::Value, E> {
+                Ok(NumberKind::Float)
+            }
+        }
+
+        serde_yaml::Deserializer::from_str(literal).deserialize_any(NumberKindVisitor).ok()
+    }
+
+    pub fn serialize_map(mut map: YamlMap) -> Result<String, serde_yaml::Error> {
+        let placeholder_prefix = format!("rops-literal-{:016x}-", rand::random::<u64>());
+        let mut literals = Vec::new();
+        map.values_mut()
+            .for_each(|value| replace_literals(value, &placeholder_prefix, &mut literals));
+
+        let string = serde_yaml::to_string(&map)?;
+        if literals.is_empty() {
+            return Ok(string);
+        }
+
+        let placeholder_regex = regex::Regex::new(&format!("{placeholder_prefix}([0-9]+)")).expect("invalid placeholder regex");
+        Ok(placeholder_regex
+            .replace_all(&string, |captures: &regex::Captures| {
+                literals[captures[1].parse::<usize>().expect("placeholder index not an integer")].clone()
+            })
+            .into_owned())
+    }
+
+    fn replace_literals(value: &mut YamlValue, placeholder_prefix: &str, literals: &mut Vec<String>) {
         match value {
-            RopsValue::String(string) => YamlValue::String(string),
-            RopsValue::Boolean(bool) => YamlValue::Bool(bool),
-            RopsValue::Integer(integer) => YamlValue::Number(integer.into()),
-            RopsValue::Float(rops_float) => YamlValue::Number(f64::from(rops_float).into()),
+            YamlValue::Tagged(tagged) if is_literal(tagged) => {
+                let YamlValue::String(literal) = std::mem::take(&mut tagged.value) else {
+                    unreachable!("number literals to be held as strings")
+                };
+                *value = YamlValue::String(format!("{placeholder_prefix}{}", literals.len()));
+                literals.push(literal);
+            }
+            YamlValue::Tagged(tagged) => replace_literals(&mut tagged.value, placeholder_prefix, literals),
+            YamlValue::Mapping(map) => map
+                .values_mut()
+                .for_each(|value| replace_literals(value, placeholder_prefix, literals)),
+            YamlValue::Sequence(sequence) => sequence
+                .iter_mut()
+                .for_each(|value| replace_literals(value, placeholder_prefix, literals)),
+            _ => (),
+        }
+    }
+
+    /// `serde_yaml` parses numbers before handing them out, and rejects integers larger than an u64
+    /// when parsed into its values. The map is therefore first parsed by hand, and then once more
+    /// whilst reading the numbers of it as strings, which yields their source text.
+    pub fn deserialize_map(str: &str) -> Result<YamlMap, serde_yaml::Error> {
+        let mut map = serde_yaml::Deserializer::from_str(str).deserialize_map(ValueVisitor)?;
+        LiteralSeed(&mut map).deserialize(serde_yaml::Deserializer::from_str(str))?;
+
+        match map {
+            YamlValue::Mapping(map) => Ok(map),
+            _ => unreachable!("map deserialized into a non-map value"),
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct ValueVisitor;
+
+    impl<'de> DeserializeSeed<'de> for ValueVisitor {
+        type Value = YamlValue;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for ValueVisitor {
+        type Value = YamlValue;
+
+        fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+            formatter.write_str("any YAML value")
+        }
+
+        fn visit_bool<E: de::Error>(self, bool: bool) -> Result<Self::Value, E> {
+            Ok(YamlValue::Bool(bool))
+        }
+
+        fn visit_i64<E: de::Error>(self, integer: i64) -> Result<Self::Value, E> {
+            Ok(YamlValue::Number(integer.into()))
+        }
+
+        fn visit_u64<E: de::Error>(self, integer: u64) -> Result<Self::Value, E> {
+            Ok(YamlValue::Number(integer.into()))
+        }
+
+        // Replaced by their source text in the second pass.
+        fn visit_i128<E: de::Error>(self, integer: i128) -> Result<Self::Value, E> {
+            Ok(literal(&integer.to_string()))
+        }
+
+        fn visit_u128<E: de::Error>(self, integer: u128) -> Result<Self::Value, E> {
+            Ok(literal(&integer.to_string()))
+        }
+
+        fn visit_f64<E: de::Error>(self, float: f64) -> Result<Self::Value, E> {
+            Ok(YamlValue::Number(float.into()))
+        }
+
+        fn visit_str<E: de::Error>(self, str: &str) -> Result<Self::Value, E> {
+            Ok(YamlValue::String(str.to_string()))
+        }
+
+        fn visit_string<E: de::Error>(self, string: String) -> Result<Self::Value, E> {
+            Ok(YamlValue::String(string))
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(YamlValue::Null)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(YamlValue::Null)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            self.deserialize(deserializer)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq_access: A) -> Result<Self::Value, A::Error> {
+            let mut sequence = Vec::new();
+            while let Some(value) = seq_access.next_element_seed(self)? {
+                sequence.push(value);
+            }
+            Ok(YamlValue::Sequence(sequence))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map_access: A) -> Result<Self::Value, A::Error> {
+            let mut map = YamlMap::new();
+            while let Some(key) = map_access.next_key_seed(self)? {
+                if map.contains_key(&key) {
+                    return Err(de::Error::custom(match key {
+                        YamlValue::String(key) => format!("duplicate entry with key {key:?}"),
+                        _ => "duplicate entry in YAML map".to_string(),
+                    }));
+                }
+                let value = map_access.next_value_seed(self)?;
+                map.insert(key, value);
+            }
+            Ok(YamlValue::Mapping(map))
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, enum_access: A) -> Result<Self::Value, A::Error> {
+            let (tag, variant_access) = enum_access.variant::<String>()?;
+            Ok(YamlValue::Tagged(Box::new(TaggedValue {
+                tag: Tag::new(tag),
+                value: variant_access.newtype_variant_seed(self)?,
+            })))
+        }
+    }
+
+    /// Walks a value of the first pass alongside the same document.
+    struct LiteralSeed<'a>(&'a mut YamlValue);
+
+    impl<'de> DeserializeSeed<'de> for LiteralSeed<'_> {
+        type Value = ();
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            let is_literal = matches!(&*self.0, YamlValue::Tagged(tagged) if is_literal(tagged));
+
+            match self.0 {
+                YamlValue::Mapping(_) => deserializer.deserialize_map(self),
+                YamlValue::Sequence(_) => deserializer.deserialize_seq(self),
+                YamlValue::Tagged(_) if !is_literal => deserializer.deserialize_enum("", &[], self),
+                YamlValue::Number(_) | YamlValue::Tagged(_) => deserializer.deserialize_str(self),
+                _ => deserializer.deserialize_ignored_any(IgnoredAny).map(|_| ()),
+            }
+        }
+    }
+
+    impl<'de> Visitor<'de> for LiteralSeed<'_> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+            formatter.write_str("the value of the first pass")
+        }
+
+        fn visit_str<E: de::Error>(self, literal: &str) -> Result<Self::Value, E> {
+            let kind = match &*self.0 {
+                YamlValue::Number(number) if number.is_f64() => NumberKind::Float,
+                _ => NumberKind::Integer,
+            };
+
+            if let Some(value) = from_internal(literal, kind) {
+                *self.0 = value;
+            }
+            Ok(())
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq_access: A) -> Result<Self::Value, A::Error> {
+            let YamlValue::Sequence(sequence) = self.0 else {
+                unreachable!("sequence visited for a non-sequence value")
+            };
+            for value in sequence {
+                seq_access.next_element_seed(LiteralSeed(value))?;
+            }
+            Ok(())
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map_access: A) -> Result<Self::Value, A::Error> {
+            let YamlValue::Mapping(map) = self.0 else {
+                unreachable!("map visited for a non-map value")
+            };
+            for value in map.values_mut() {
+                map_access.next_key::<IgnoredAny>()?;
+                map_access.next_value_seed(LiteralSeed(value))?;
+            }
+            Ok(())
+        }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, enum_access: A) -> Result<Self::Value, A::Error> {
+            let YamlValue::Tagged(tagged) = self.0 else {
+                unreachable!("tag visited for an untagged value")
+            };
+            let (IgnoredAny, variant_access) = enum_access.variant()?;
+            variant_access.newtype_variant_seed(LiteralSeed(&mut tagged.value))
+        }
+    }
+}
+
+mod helpers {
+    use super::*;
+
+    /// Markers must be placed at the start of a line, followed by whitespace or the end of the line.
+    pub fn strip_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+        let remainder = line.strip_prefix(marker)?;
+
+        match remainder.is_empty() || remainder.starts_with([' ', '\t']) {
+            true => Some(remainder.trim()),
+            false => None,
+        }
+    }
+
+    /// Chunks of only comments or directives, e.g. a header before the first document start marker,
+    /// are kept in front of the following document, separated by a marker of their own. Those
+    /// without a following document are appended to the preceding one.
+    pub fn attach_contentless_chunks(chunks: Vec<String>) -> Vec<String> {
+        let mut documents = Vec::<String>::with_capacity(chunks.len());
+        let mut pending = String::new();
+
+        for chunk in chunks {
+            match has_content(&chunk) {
+                true => documents.push(match pending.is_empty() {
+                    true => chunk,
+                    false => format!("{}{}\n{}", std::mem::take(&mut pending), DOCUMENT_START_MARKER, chunk),
+                }),
+                false => pending.push_str(&chunk),
+            }
         }
+
+        if let Some(last_document) = documents.last_mut() {
+            last_document.push_str(&pending);
+        }
+
+        documents
+    }
+
+    /// Whether anything besides whitespace, comments and directives is present.
+    pub fn has_content(document: &str) -> bool {
+        document
+            .lines()
+            .map(str::trim_start)
+            .any(|line| !line.is_empty() && !line.starts_with(['#', '%']))
+    }
+
+    // `serde_yaml` values only hold integers fitting inside an i64 or an u64, larger ones are visited as
+    // i128s or u128s, which it then rejects as being of an invalid type.
+    pub fn explain_integer_range(error: serde_yaml::Error) -> serde_yaml::Error {
+        let message = error.to_string();
+        match message.contains("` as i128,") || message.contains("` as u128,") {
+            true => serde::de::Error::custom(format!(
+                "integer out of range, YAML integers must fit inside an i64 or an u64: {message}"
+            )),
+            false => error,
+        }
+    }
+
+    pub fn from_internal_value(mut value: RopsValue) -> Result<YamlValue, FormatFromInternalMapError> {
+        Ok(match &mut value {
+            RopsValue::String(string) => YamlValue::String(std::mem::take(string)),
+            RopsValue::Boolean(bool) => YamlValue::Bool(*bool),
+            // Literals of other formats are written by value when YAML has no notation for them, e.g. `1_000`.
+            RopsValue::Integer(integer) => literals::from_internal(integer.as_str(), literals::NumberKind::Integer)
+                .or_else(|| literals::from_internal(&integer.canonical(), literals::NumberKind::Integer))
+                .ok_or_else(|| FormatFromInternalMapError::IntegerOutOfRange {
+                    format: "YAML",
+                    literal: integer.as_str().to_string(),
+                })?,
+            RopsValue::Float(rops_float) => literals::from_internal(rops_float.as_str(), literals::NumberKind::Float)
+                .unwrap_or_else(|| YamlValue::Number(f64::from(&*rops_float).into())),
+        })
     }
 }
@@ -27,3 +27,221 @@ fn decrypted_disallows_non_string_keys() {
         FormatToInternalMapError::NonStringKey(_)
     ))
 }
+
+mod documents {
+    use crate::*;
+
+    #[test]
+    fn splits_documents() {
+        let documents = indoc::indoc! {"
+            # Leading comment
+            ---
+            a: 1
+            ---
+            b: 2
+            ...
+            --- {c: 3}
+        "};
+
+        assert_eq!(
+            vec!["# Leading comment\n---\na: 1\n", "b: 2\n", "{c: 3}\n"],
+            YamlFileFormat::split_documents(documents)
+        )
+    }
+
+    #[test]
+    fn attaches_comment_only_chunks() {
+        let documents = indoc::indoc! {"
+            %YAML 1.2
+            # Header
+            ---
+            ---
+            a: 1
+            ---
+            # Between
+            ---
+            b: 2
+            ---
+            # Trailing
+        "};
+
+        let split_documents = YamlFileFormat::split_documents(documents);
+        assert_eq!(
+            vec!["%YAML 1.2\n# Header\n---\na: 1\n", "# Between\n---\nb: 2\n# Trailing\n"],
+            split_documents
+        );
+
+        for document in split_documents {
+            serde_yaml::from_str::<serde_yaml::Mapping>(&document).unwrap();
+        }
+    }
+
+    #[test]
+    fn keeps_single_document_as_is() {
+        let document = "---\na: 1\n";
+        assert_eq!(vec![document], YamlFileFormat::split_documents(document))
+    }
+
+    #[test]
+    fn disregards_markers_not_at_line_start() {
+        let document = "a: |\n  ---\nb: '---'\n";
+        assert_eq!(vec![document], YamlFileFormat::split_documents(document))
+    }
+
+    #[test]
+    fn joins_documents() {
+        assert_eq!(
+            "a: 1\n---\nb: 2\n",
+            YamlFileFormat::join_documents(vec!["a: 1".to_string(), "b: 2\n".to_string()])
+        )
+    }
+}
+
+mod tags {
+    use crate::*;
+
+    type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>;
+
+    const PLAINTEXT: &str = indoc::indoc! {"
+        scalar: !Ref bucket
+        map: !GetAtt
+          name: !Sub ${AWS::Region}
+        sequence: !Join
+        - ','
+        - - !Ref a
+          - b
+        plain: value
+    "};
+
+    fn encrypted_file() -> EncryptedRopsFile {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        RopsFileBuilder::<YamlFileFormat>::new(PLAINTEXT)
+            .unwrap()
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt()
+            .unwrap()
+    }
+
+    #[test]
+    fn retains_tags_on_encryption() {
+        let encrypted_file_string = encrypted_file().to_string();
+
+        assert!(encrypted_file_string.starts_with("scalar: !Ref ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\nmap: !GetAtt\n  name: !Sub ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\nsequence: !Join\n- ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\n- - !Ref ENC[AES256_GCM,"));
+        assert!(encrypted_file_string.contains("\nplain: ENC[AES256_GCM,"));
+    }
+
+    #[test]
+    fn retains_tags_on_decryption() {
+        let decrypted_file = encrypted_file()
+            .to_string()
+            .parse::<EncryptedRopsFile>()
+            .unwrap()
+            .decrypt::<YamlFileFormat>()
+            .unwrap();
+
+        pretty_assertions::assert_eq!(PLAINTEXT, decrypted_file.map().to_string())
+    }
+
+    #[test]
+    fn skips_tags_of_removed_values() {
+        let mut map = PLAINTEXT
+            .parse::<RopsFileFormatMap<DecryptedMap, YamlFileFormat>>()
+            .unwrap()
+            .into_inner_map();
+        let layout = FileFormatMapAdapter::layout(&map).unwrap();
+        map.shift_remove("map");
+        map.insert("sequence".into(), "replaced".into());
+        map.restore_layout(layout);
+
+        pretty_assertions::assert_eq!(
+            "scalar: !Ref bucket\nsequence: !Join replaced\nplain: value\n",
+            serde_yaml::to_string(&map).unwrap()
+        )
+    }
+}
+
+mod numbers {
+    use crate::*;
+
+    type EncryptedRopsFile = RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>;
+
+    const PLAINTEXT: &str = indoc::indoc! {"
+        min: -9223372036854775808
+        max: 18446744073709551615
+        big: 123456789012345678901234567890
+        negative_big: -123456789012345678901234567890
+        positive: +1
+        hexadecimal: 0x1F
+        octal: 0o17
+        float: 1.0
+        trailing_zero: 1.50
+        exponent: 1e10
+        precise: 0.1
+        infinity: .Inf
+        tagged: !Ref 0x1F
+        sequence:
+        - 1E+10
+        - 2
+    "};
+
+    #[test]
+    fn retains_number_literals() {
+        let decrypted_file = encrypt(PLAINTEXT).decrypt::<YamlFileFormat>().unwrap();
+        pretty_assertions::assert_eq!(PLAINTEXT, decrypted_file.map().to_string())
+    }
+
+    #[test]
+    fn retains_unencrypted_number_literals() {
+        let encrypted_file_string = RopsFileBuilder::<YamlFileFormat>::new(
+            "exponent_unencrypted: 1e10
+",
+        )
+        .unwrap()
+        .with_partial_encryption(PartialEncryptionConfig::UnencryptedSuffix("_unencrypted".to_string()))
+        .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+        .encrypt::<AES256GCM, SHA512>()
+        .unwrap()
+        .to_string();
+
+        assert!(encrypted_file_string.starts_with("exponent_unencrypted: 1e10\n"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn writes_literals_without_json_notation_by_value() {
+        let decrypted_file = encrypt("hexadecimal: 0x1F\nexponent: 1e10\n").decrypt::<JsonFileFormat>().unwrap();
+        pretty_assertions::assert_eq!(
+            "{\n  \"hexadecimal\": 31,\n  \"exponent\": 1e10\n}",
+            decrypted_file.map().to_string()
+        )
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn disallows_non_finite_floats_in_json() {
+        match encrypt("infinity: .inf").decrypt::<JsonFileFormat>().unwrap_err() {
+            RopsFileDecryptError::FormatFromInternalMap(FormatFromInternalMapError::NonFiniteFloat { format, literal }) => {
+                assert_eq!("JSON", format);
+                assert_eq!(".inf", literal);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    fn encrypt(plaintext: &str) -> EncryptedRopsFile {
+        AgeIntegration::set_mock_private_key_env_var();
+
+        RopsFileBuilder::<YamlFileFormat>::new(plaintext)
+            .unwrap()
+            .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+            .encrypt::<AES256GCM, SHA512>()
+            .unwrap()
+            .to_string()
+            .parse()
+            .unwrap()
+    }
+}
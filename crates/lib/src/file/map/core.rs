@@ -15,13 +15,13 @@ pub enum RopsTree<S: RopsMapState> {
 }
 
 impl<C: Cipher> ToExternalMap<EncryptedMap<C>> for RopsMap<EncryptedMap<C>> {
-    fn to_external<F: FileFormat>(self) -> RopsFileFormatMap<EncryptedMap<C>, F> {
-        RopsFileFormatMap::from_inner_map(F::Map::encrypted_from_internal(self))
+    fn to_external<F: FileFormat>(self) -> Result<RopsFileFormatMap<EncryptedMap<C>, F>, FormatFromInternalMapError> {
+        F::Map::encrypted_from_internal(self).map(RopsFileFormatMap::from_inner_map)
     }
 }
 
 impl ToExternalMap<DecryptedMap> for RopsMap<DecryptedMap> {
-    fn to_external<F: FileFormat>(self) -> RopsFileFormatMap<DecryptedMap, F> {
-        RopsFileFormatMap::from_inner_map(F::Map::decrypted_from_internal(self))
+    fn to_external<F: FileFormat>(self) -> Result<RopsFileFormatMap<DecryptedMap, F>, FormatFromInternalMapError> {
+        F::Map::decrypted_from_internal(self).map(RopsFileFormatMap::from_inner_map)
     }
 }
@@ -2,74 +2,125 @@ use indexmap::IndexMap;
 
 use crate::*;
 
+type DecryptedNonces<C> = Vec<(NonceKey, Nonce<<C as Cipher>::NonceSize>)>;
+
+enum NonceKey {
+    Value(KeyPath, RopsValue, RopsValueVariant),
+    EmptyTree(KeyPath, RopsValueVariant),
+}
+
+impl NonceKey {
+    fn new(key_path: &KeyPath, decrypted_tree: &RopsTree<DecryptedMap>, value_variant: RopsValueVariant) -> Option<Self> {
+        match decrypted_tree {
+            RopsTree::Leaf(decrypted_value) => Some(Self::Value(key_path.clone(), decrypted_value.clone(), value_variant)),
+            empty_tree => empty_tree
+                .empty_variant()
+                .map(|empty_variant| Self::EmptyTree(key_path.clone(), empty_variant)),
+        }
+    }
+}
+
 impl<C: Cipher> RopsMap<EncryptedMap<C>> {
     pub fn decrypt(self, data_key: &DataKey) -> Result<RopsMap<DecryptedMap>, DecryptRopsValueError> {
-        Self::decrypt_impl(self, data_key, &mut None)
+        Self::decrypt_impl(self, data_key, false, Traversal::default()).map(|(map, _)| map)
     }
 
     pub fn decrypt_and_save_nonces(
         self,
         data_key: &DataKey,
     ) -> Result<(RopsMap<DecryptedMap>, SavedRopsMapNonces<C>), DecryptRopsValueError> {
-        let mut saved_nonces = SavedRopsMapNonces::default();
-        Self::decrypt_impl(self, data_key, &mut Some(&mut saved_nonces)).map(|tree| (tree, saved_nonces))
+        Self::decrypt_impl(self, data_key, true, Traversal::default())
     }
 
-    fn decrypt_impl<Ci: Cipher>(
-        map: RopsMap<EncryptedMap<Ci>>,
+    /// Nonces are collected per subtree and then saved in traversal order, keeping the
+    /// last-write-wins semantics of [`SavedRopsMapNonces`] independent of `traversal`.
+    pub(crate) fn decrypt_impl(
+        map: RopsMap<EncryptedMap<C>>,
         data_key: &DataKey,
-        optional_saved_nonces: &mut Option<&mut SavedRopsMapNonces<Ci>>,
-    ) -> Result<RopsMap<DecryptedMap>, DecryptRopsValueError> {
-        return decrypt_map_recursive(map, data_key, &KeyPath::default(), optional_saved_nonces);
+        save_nonces: bool,
+        traversal: Traversal,
+    ) -> Result<(RopsMap<DecryptedMap>, SavedRopsMapNonces<C>), DecryptRopsValueError> {
+        let (decrypted_map, decrypted_nonces) = decrypt_map_recursive(map, data_key, &KeyPath::default(), save_nonces, traversal)?;
+
+        let mut saved_nonces = SavedRopsMapNonces::default();
+        for (nonce_key, nonce) in decrypted_nonces {
+            match nonce_key {
+                NonceKey::Value(key_path, decrypted_value, value_variant) => {
+                    if value_variant != RopsValueVariant::from(&decrypted_value) {
+                        saved_nonces.insert_string_variant((key_path.clone(), decrypted_value.clone()), value_variant);
+                    }
+                    saved_nonces.insert((key_path, decrypted_value), nonce)
+                }
+                NonceKey::EmptyTree(key_path, empty_variant) => saved_nonces.insert_empty_tree((key_path, empty_variant), nonce),
+            }
+        }
+
+        return Ok((decrypted_map, saved_nonces));
 
-        fn decrypt_map_recursive<C: Cipher>(
-            map: RopsMap<EncryptedMap<C>>,
+        fn decrypt_map_recursive<Ci: Cipher>(
+            map: RopsMap<EncryptedMap<Ci>>,
             data_key: &DataKey,
             key_path: &KeyPath,
-            optional_saved_nonces: &mut Option<&mut SavedRopsMapNonces<C>>,
-        ) -> Result<RopsMap<DecryptedMap>, DecryptRopsValueError> {
-            let mut decrypted_map = IndexMap::with_capacity(map.len());
-
-            for (key, sub_tree) in map.0 {
+            save_nonces: bool,
+            traversal: Traversal,
+        ) -> Result<(RopsMap<DecryptedMap>, DecryptedNonces<Ci>), DecryptRopsValueError> {
+            let decrypted_entries = traversal.try_map(map.0, |(key, sub_tree)| {
                 let sub_key_path = key_path.join(&key);
-                decrypted_map.insert(
-                    key,
-                    decrypt_tree_recursive(sub_tree, data_key, &sub_key_path, optional_saved_nonces)?,
-                );
+                decrypt_tree_recursive(sub_tree, data_key, &sub_key_path, save_nonces, traversal).map(|decrypted| (key, decrypted))
+            })?;
+
+            let mut decrypted_map = IndexMap::with_capacity(decrypted_entries.len());
+            let mut decrypted_nonces = Vec::new();
+            for (key, (decrypted_sub_tree, sub_tree_nonces)) in decrypted_entries {
+                decrypted_map.insert(key, decrypted_sub_tree);
+                decrypted_nonces.extend(sub_tree_nonces);
             }
 
-            Ok(decrypted_map.into())
+            Ok((decrypted_map.into(), decrypted_nonces))
         }
 
-        fn decrypt_tree_recursive<C: Cipher>(
-            tree: RopsTree<EncryptedMap<C>>,
+        fn decrypt_tree_recursive<Ci: Cipher>(
+            tree: RopsTree<EncryptedMap<Ci>>,
             data_key: &DataKey,
             key_path: &KeyPath,
-            optional_saved_nonces: &mut Option<&mut SavedRopsMapNonces<C>>,
-        ) -> Result<RopsTree<DecryptedMap>, DecryptRopsValueError> {
+            save_nonces: bool,
+            traversal: Traversal,
+        ) -> Result<(RopsTree<DecryptedMap>, DecryptedNonces<Ci>), DecryptRopsValueError> {
             Ok(match tree {
-                RopsTree::Sequence(sequence) => sequence
-                    .into_iter()
-                    .map(|sub_tree| decrypt_tree_recursive(sub_tree, data_key, key_path, optional_saved_nonces))
-                    .collect::<Result<Vec<_>, _>>()
-                    .map(RopsTree::Sequence)?,
+                RopsTree::Sequence(sequence) => {
+                    let decrypted_elements = traversal.try_map(sequence, |sub_tree| {
+                        decrypt_tree_recursive(sub_tree, data_key, key_path, save_nonces, traversal)
+                    })?;
+
+                    let mut decrypted_sequence = Vec::with_capacity(decrypted_elements.len());
+                    let mut decrypted_nonces = Vec::new();
+                    for (decrypted_sub_tree, sub_tree_nonces) in decrypted_elements {
+                        decrypted_sequence.push(decrypted_sub_tree);
+                        decrypted_nonces.extend(sub_tree_nonces);
+                    }
+
+                    (RopsTree::Sequence(decrypted_sequence), decrypted_nonces)
+                }
                 RopsTree::Map(encrypted_map) => {
-                    RopsTree::Map(decrypt_map_recursive(encrypted_map, data_key, key_path, optional_saved_nonces)?)
+                    let (decrypted_map, decrypted_nonces) =
+                        decrypt_map_recursive(encrypted_map, data_key, key_path, save_nonces, traversal)?;
+                    (RopsTree::Map(decrypted_map), decrypted_nonces)
                 }
-
-                RopsTree::Null => RopsTree::Null,
-                RopsTree::Leaf(maybe_encrypted_value) => RopsTree::Leaf(match maybe_encrypted_value {
-                    RopsMapEncryptedLeaf::Encrypted(encrypted_value) => match optional_saved_nonces {
-                        Some(saved_nonces) => {
+                RopsTree::Null => (RopsTree::Null, Vec::new()),
+                RopsTree::Leaf(maybe_encrypted_value) => match maybe_encrypted_value {
+                    RopsMapEncryptedLeaf::Encrypted(encrypted_value) => match save_nonces {
+                        true => {
                             let nonce = encrypted_value.nonce.clone();
-                            let decrypted_value = encrypted_value.decrypt(data_key, key_path)?;
-                            saved_nonces.insert((key_path.clone(), decrypted_value.clone()), nonce);
-                            decrypted_value
+                            let value_variant = encrypted_value.value_variant;
+                            let decrypted_tree = encrypted_value.decrypt_tree(data_key, key_path)?;
+                            let decrypted_nonces =
+                                NonceKey::new(key_path, &decrypted_tree, value_variant).map(|nonce_key| (nonce_key, nonce));
+                            (decrypted_tree, decrypted_nonces.into_iter().collect())
                         }
-                        None => encrypted_value.decrypt(data_key, key_path)?,
+                        false => (encrypted_value.decrypt_tree(data_key, key_path)?, Vec::new()),
                     },
-                    RopsMapEncryptedLeaf::Escaped(escaped_value) => escaped_value,
-                }),
+                    RopsMapEncryptedLeaf::Escaped(escaped_value) => (RopsTree::Leaf(escaped_value), Vec::new()),
+                },
             })
         }
     }
@@ -98,5 +149,19 @@ mod tests {
                     .unwrap()
             )
         }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn parallel_decryption_matches_sequential() {
+            let decrypt =
+                |traversal| RopsMap::<EncryptedMap<AES256GCM>>::decrypt_impl(RopsMap::mock(), &DataKey::mock(), true, traversal).unwrap();
+
+            let (parallel_map, parallel_nonces) = decrypt(Traversal::Parallel);
+            pretty_assertions::assert_eq!(
+                (RopsMap::<DecryptedMap>::mock(), SavedRopsMapNonces::mock()),
+                (parallel_map, parallel_nonces)
+            );
+            pretty_assertions::assert_eq!(decrypt(Traversal::Sequential), decrypt(Traversal::Parallel));
+        }
     }
 }
@@ -0,0 +1,181 @@
+use indexmap::IndexMap;
+
+use crate::*;
+
+#[derive(Debug, PartialEq)]
+pub enum KeyPathDiff {
+    Added {
+        key_path: String,
+        value: String,
+    },
+    Removed {
+        key_path: String,
+        value: String,
+    },
+    Changed {
+        key_path: String,
+        old_value: String,
+        new_value: String,
+    },
+}
+
+impl RopsMap<DecryptedMap> {
+    /// Compares the leaf values of each key path, sequence elements are compared by index.
+    ///
+    /// Removed and changed key paths are listed in the order of `self`, followed by the
+    /// added key paths in the order of `other`.
+    pub fn diff(&self, other: &Self) -> Vec<KeyPathDiff> {
+        let old_leaves = flatten(self);
+        let new_leaves = flatten(other);
+
+        let mut diffs = Vec::new();
+
+        for (key_path, old_leaf) in &old_leaves {
+            match new_leaves.get(key_path) {
+                None => diffs.push(KeyPathDiff::Removed {
+                    key_path: key_path.clone(),
+                    value: old_leaf.to_string(),
+                }),
+                Some(new_leaf) if new_leaf != old_leaf => diffs.push(KeyPathDiff::Changed {
+                    key_path: key_path.clone(),
+                    old_value: old_leaf.to_string(),
+                    new_value: new_leaf.to_string(),
+                }),
+                Some(_) => (),
+            }
+        }
+
+        for (key_path, new_leaf) in new_leaves {
+            if !old_leaves.contains_key(&key_path) {
+                diffs.push(KeyPathDiff::Added {
+                    value: new_leaf.to_string(),
+                    key_path,
+                });
+            }
+        }
+
+        diffs
+    }
+}
+
+#[derive(PartialEq)]
+pub(super) enum Leaf<'a> {
+    Value(&'a RopsValue),
+    Null,
+    EmptyMap,
+    EmptySequence,
+}
+
+impl std::fmt::Display for Leaf<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Leaf::Value(RopsValue::String(string)) => write!(f, "{string}"),
+            Leaf::Value(RopsValue::Boolean(boolean)) => write!(f, "{boolean}"),
+            Leaf::Value(RopsValue::Integer(integer)) => write!(f, "{}", integer.as_str()),
+            Leaf::Value(RopsValue::Float(float)) => write!(f, "{}", float.as_str()),
+            Leaf::Null => write!(f, "null"),
+            Leaf::EmptyMap => write!(f, "{{}}"),
+            Leaf::EmptySequence => write!(f, "[]"),
+        }
+    }
+}
+
+/// Key path segments are joined with '.', sequence indices are appended within brackets.
+fn flatten(map: &RopsMap<DecryptedMap>) -> IndexMap<String, Leaf<'_>> {
+    let mut leaves = IndexMap::new();
+    flatten_map(map, "", &mut leaves);
+    return leaves;
+
+    fn flatten_map<'a>(map: &'a RopsMap<DecryptedMap>, key_path: &str, leaves: &mut IndexMap<String, Leaf<'a>>) {
+        if map.is_empty() && !key_path.is_empty() {
+            leaves.insert(key_path.to_string(), Leaf::EmptyMap);
+        }
+
+        for (key, tree) in map.iter() {
+            let key_path = match key_path.is_empty() {
+                true => key.clone(),
+                false => format!("{key_path}.{key}"),
+            };
+
+            flatten_tree(tree, key_path, leaves);
+        }
+    }
+
+    fn flatten_tree<'a>(tree: &'a RopsTree<DecryptedMap>, key_path: String, leaves: &mut IndexMap<String, Leaf<'a>>) {
+        match tree {
+            RopsTree::Map(map) => flatten_map(map, &key_path, leaves),
+            RopsTree::Sequence(sequence) if sequence.is_empty() => {
+                leaves.insert(key_path, Leaf::EmptySequence);
+            }
+            RopsTree::Sequence(sequence) => {
+                for (index, tree) in sequence.iter().enumerate() {
+                    flatten_tree(tree, format!("{key_path}[{index}]"), leaves);
+                }
+            }
+            RopsTree::Null => {
+                leaves.insert(key_path, Leaf::Null);
+            }
+            RopsTree::Leaf(value) => {
+                leaves.insert(key_path, Leaf::Value(value));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    fn leaf(value: RopsValue) -> RopsTree<DecryptedMap> {
+        RopsTree::Leaf(value)
+    }
+
+    #[test]
+    fn diffs_identical_maps() {
+        assert!(RopsMap::mock().diff(&RopsMap::mock()).is_empty())
+    }
+
+    #[test]
+    fn diffs_key_paths() {
+        let old_map = RopsMap::from(indexmap! {
+            "removed".to_string() => leaf(RopsValue::Integer(1.into())),
+            "nested".to_string() => RopsTree::Map(RopsMap::from(indexmap! {
+                "changed".to_string() => leaf(RopsValue::Boolean(true)),
+            })),
+            "sequence".to_string() => RopsTree::Sequence(vec![leaf(RopsValue::String("a".to_string()))]),
+        });
+
+        let new_map = RopsMap::from(indexmap! {
+            "nested".to_string() => RopsTree::Map(RopsMap::from(indexmap! {
+                "changed".to_string() => leaf(RopsValue::Boolean(false)),
+            })),
+            "sequence".to_string() => RopsTree::Sequence(vec![leaf(RopsValue::String("a".to_string())), RopsTree::Null]),
+            "added".to_string() => RopsTree::Map(RopsMap::from(IndexMap::new())),
+        });
+
+        pretty_assertions::assert_eq!(
+            vec![
+                KeyPathDiff::Removed {
+                    key_path: "removed".to_string(),
+                    value: "1".to_string()
+                },
+                KeyPathDiff::Changed {
+                    key_path: "nested.changed".to_string(),
+                    old_value: "true".to_string(),
+                    new_value: "false".to_string()
+                },
+                KeyPathDiff::Added {
+                    key_path: "sequence[1]".to_string(),
+                    value: "null".to_string()
+                },
+                KeyPathDiff::Added {
+                    key_path: "added".to_string(),
+                    value: "{}".to_string()
+                },
+            ],
+            old_map.diff(&new_map)
+        )
+    }
+}
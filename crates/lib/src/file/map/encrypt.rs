@@ -7,90 +7,129 @@ impl RopsMap<DecryptedMap> {
         self,
         data_key: &DataKey,
         optional_partial_encryption: Option<&PartialEncryptionConfig>,
+        encrypt_empty_values: bool,
     ) -> Result<RopsMap<EncryptedMap<C>>, C::Error> {
-        Self::encrypt_recursive_impl(self, data_key, optional_partial_encryption, &None)
+        Self::encrypt_recursive_impl(
+            self,
+            data_key,
+            optional_partial_encryption,
+            encrypt_empty_values,
+            None,
+            Traversal::default(),
+        )
     }
 
     pub fn encrypt_with_saved_nonces<C: Cipher>(
         self,
         data_key: &DataKey,
         optional_partial_encryption: Option<&PartialEncryptionConfig>,
+        encrypt_empty_values: bool,
         saved_nonces: &SavedRopsMapNonces<C>,
     ) -> Result<RopsMap<EncryptedMap<C>>, C::Error> {
-        Self::encrypt_recursive_impl(self, data_key, optional_partial_encryption, &Some(saved_nonces))
+        Self::encrypt_recursive_impl(
+            self,
+            data_key,
+            optional_partial_encryption,
+            encrypt_empty_values,
+            Some(saved_nonces),
+            Traversal::default(),
+        )
     }
 
-    fn encrypt_recursive_impl<C: Cipher>(
+    pub(crate) fn encrypt_recursive_impl<C: Cipher>(
         self,
         data_key: &DataKey,
         optional_partial_encryption: Option<&PartialEncryptionConfig>,
-        saved_nonces: &Option<&SavedRopsMapNonces<C>>,
+        encrypt_empty_values: bool,
+        saved_nonces: Option<&SavedRopsMapNonces<C>>,
+        traversal: Traversal,
     ) -> Result<RopsMap<EncryptedMap<C>>, C::Error> {
         return encrypt_map_recursive(
             self,
             data_key,
             optional_partial_encryption.into(),
+            encrypt_empty_values,
             &KeyPath::default(),
             saved_nonces,
+            traversal,
         );
 
         fn encrypt_map_recursive<Ci: Cipher>(
             decrypted_map: RopsMap<DecryptedMap>,
             data_key: &DataKey,
             resolved_partial_encryption: ResolvedPartialEncryption,
+            encrypt_empty_values: bool,
             key_path: &KeyPath,
-            optional_saved_nonces: &Option<&SavedRopsMapNonces<Ci>>,
+            optional_saved_nonces: Option<&SavedRopsMapNonces<Ci>>,
+            traversal: Traversal,
         ) -> Result<RopsMap<EncryptedMap<Ci>>, Ci::Error> {
-            let mut encrypted_map = RopsMap(IndexMap::with_capacity(decrypted_map.len()));
-            for (key, decrypted_sub_tree) in decrypted_map.0 {
-                let key_path = key_path.join(&key);
-                let mut resolved_partial_encryption = resolved_partial_encryption;
+            traversal
+                .try_map(decrypted_map.0, |(key, decrypted_sub_tree)| {
+                    let key_path = key_path.join(&key);
+                    let mut resolved_partial_encryption = resolved_partial_encryption;
 
-                if let ResolvedPartialEncryption::No(partial_encryption_config) = resolved_partial_encryption {
-                    resolved_partial_encryption = partial_encryption_config.resolve(key_path.last())
-                }
+                    if let ResolvedPartialEncryption::No(partial_encryption_config) = resolved_partial_encryption {
+                        resolved_partial_encryption = partial_encryption_config.resolve(key_path.last())
+                    }
 
-                encrypted_map.insert(
-                    key,
                     encrypt_tree_recursive(
                         decrypted_sub_tree,
                         data_key,
                         resolved_partial_encryption,
+                        encrypt_empty_values,
                         &key_path,
                         optional_saved_nonces,
-                    )?,
-                );
-            }
-
-            Ok(encrypted_map)
+                        traversal,
+                    )
+                    .map(|encrypted_sub_tree| (key, encrypted_sub_tree))
+                })
+                .map(|entries| RopsMap(IndexMap::from_iter(entries)))
         }
 
         fn encrypt_tree_recursive<Ci: Cipher>(
             decrypted_tree: RopsTree<DecryptedMap>,
             data_key: &DataKey,
             resolved_partial_encryption: ResolvedPartialEncryption,
+            encrypt_empty_values: bool,
             key_path: &KeyPath,
-            optional_saved_nonces: &Option<&SavedRopsMapNonces<Ci>>,
+            optional_saved_nonces: Option<&SavedRopsMapNonces<Ci>>,
+            traversal: Traversal,
         ) -> Result<RopsTree<EncryptedMap<Ci>>, Ci::Error> {
+            if encrypt_empty_values && !resolved_partial_encryption.escape_encryption() {
+                if let Some(empty_variant) = decrypted_tree.empty_variant() {
+                    let nonce = optional_saved_nonces
+                        .and_then(|saved_nonces| saved_nonces.get_empty_tree((key_path, empty_variant)).cloned())
+                        .unwrap_or_else(Nonce::new);
+
+                    let encrypted_value = EncryptedRopsValue::encrypt_empty_tree(empty_variant, nonce, data_key, key_path)?;
+                    return Ok(RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(encrypted_value)));
+                }
+            }
+
             Ok(match decrypted_tree {
-                RopsTree::Sequence(sequence) => RopsTree::Sequence(
-                    sequence
-                        .into_iter()
-                        .map(|sub_tree| {
-                            encrypt_tree_recursive(sub_tree, data_key, resolved_partial_encryption, key_path, optional_saved_nonces)
-                        })
-                        .collect::<Result<Vec<_>, _>>()?,
-                ),
+                RopsTree::Sequence(sequence) => RopsTree::Sequence(traversal.try_map(sequence, |sub_tree| {
+                    encrypt_tree_recursive(
+                        sub_tree,
+                        data_key,
+                        resolved_partial_encryption,
+                        encrypt_empty_values,
+                        key_path,
+                        optional_saved_nonces,
+                        traversal,
+                    )
+                })?),
                 RopsTree::Map(decrypted_map) => RopsTree::Map(encrypt_map_recursive(
                     decrypted_map,
                     data_key,
                     resolved_partial_encryption,
+                    encrypt_empty_values,
                     key_path,
                     optional_saved_nonces,
+                    traversal,
                 )?),
                 RopsTree::Null => RopsTree::Null,
                 RopsTree::Leaf(value) => {
-                    let empty_string = matches!(&value, RopsValue::String(str) if str.is_empty());
+                    let empty_string = !encrypt_empty_values && matches!(&value, RopsValue::String(str) if str.is_empty());
 
                     match resolved_partial_encryption.escape_encryption() || empty_string {
                         true => RopsTree::Leaf(RopsMapEncryptedLeaf::Escaped(value)),
@@ -99,7 +138,18 @@ impl RopsMap<DecryptedMap> {
                                 .and_then(|saved_nonces| saved_nonces.get((key_path, &value)).cloned())
                                 .unwrap_or_else(Nonce::new);
 
-                            RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(value.encrypt(nonce, data_key, key_path)?))
+                            // Saved `bytes` and `comment` types are kept, which would otherwise be re-encrypted as `str`.
+                            let value_variant = optional_saved_nonces
+                                .and_then(|saved_nonces| saved_nonces.get_string_variant((key_path, &value)))
+                                .unwrap_or_else(|| RopsValueVariant::from(&value));
+
+                            RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(EncryptedRopsValue::encrypt_plaintext(
+                                value.as_bytes().into_owned(),
+                                value_variant,
+                                nonce,
+                                data_key,
+                                key_path,
+                            )?))
                         }
                     }
                 }
@@ -112,6 +162,8 @@ impl RopsMap<DecryptedMap> {
 mod tests {
     #[cfg(feature = "aes-gcm")]
     mod aes_gcm {
+        use indexmap::IndexMap;
+
         use crate::*;
 
         #[test]
@@ -119,7 +171,7 @@ mod tests {
             pretty_assertions::assert_eq!(
                 RopsMap::<EncryptedMap<AES256GCM>>::mock(),
                 RopsMap::<DecryptedMap>::mock()
-                    .encrypt_with_saved_nonces(&DataKey::mock(), MockTestUtil::mock(), &SavedRopsMapNonces::mock())
+                    .encrypt_with_saved_nonces(&DataKey::mock(), MockTestUtil::mock(), false, &SavedRopsMapNonces::mock())
                     .unwrap()
             )
         }
@@ -128,10 +180,74 @@ mod tests {
         fn encrypts_map_without_saving_nonces() {
             let decrypted_map = RopsMap::<DecryptedMap>::mock();
             let data_key = DataKey::mock();
-            let encrypted_map = decrypted_map.encrypt(&data_key, None).unwrap();
+            let encrypted_map = decrypted_map.encrypt(&data_key, None, false).unwrap();
 
             pretty_assertions::assert_ne!(RopsMap::<EncryptedMap<AES256GCM>>::mock(), encrypted_map);
             pretty_assertions::assert_eq!(RopsMap::mock(), encrypted_map.decrypt(&data_key).unwrap())
         }
+
+        #[test]
+        fn encrypts_empty_values_when_opted_into() {
+            let decrypted_map = || {
+                RopsMap::<DecryptedMap>(indexmap::indexmap! {
+                    "null".to_string() => RopsTree::Null,
+                    "empty_string".to_string() => RopsTree::Leaf(RopsValue::String(String::new())),
+                    "empty_map".to_string() => RopsTree::Map(RopsMap(IndexMap::new())),
+                    "empty_sequence".to_string() => RopsTree::Sequence(Vec::new()),
+                })
+            };
+
+            let data_key = DataKey::mock();
+            let encrypted_map = decrypted_map().encrypt::<AES256GCM>(&data_key, None, true).unwrap();
+
+            assert!(encrypted_map
+                .values()
+                .all(|tree| matches!(tree, RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(_)))));
+
+            let (redecrypted_map, saved_nonces) = encrypted_map.decrypt_and_save_nonces(&data_key).unwrap();
+            pretty_assertions::assert_eq!(decrypted_map(), redecrypted_map);
+
+            pretty_assertions::assert_eq!(
+                decrypted_map()
+                    .encrypt_with_saved_nonces(&data_key, None, true, &saved_nonces)
+                    .unwrap(),
+                decrypted_map()
+                    .encrypt_with_saved_nonces(&data_key, None, true, &saved_nonces)
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn leaves_empty_values_by_default() {
+            let encrypted_map = RopsMap::<DecryptedMap>(indexmap::indexmap! {
+                "null".to_string() => RopsTree::Null,
+                "empty_string".to_string() => RopsTree::Leaf(RopsValue::String(String::new())),
+            })
+            .encrypt::<AES256GCM>(&DataKey::mock(), None, false)
+            .unwrap();
+
+            assert!(encrypted_map
+                .values()
+                .all(|tree| !matches!(tree, RopsTree::Leaf(RopsMapEncryptedLeaf::Encrypted(_)))));
+        }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn parallel_encryption_matches_sequential() {
+            let encrypt = |traversal| {
+                RopsMap::<DecryptedMap>::mock()
+                    .encrypt_recursive_impl::<AES256GCM>(
+                        &DataKey::mock(),
+                        MockTestUtil::mock(),
+                        false,
+                        Some(&SavedRopsMapNonces::mock()),
+                        traversal,
+                    )
+                    .unwrap()
+            };
+
+            pretty_assertions::assert_eq!(RopsMap::mock(), encrypt(Traversal::Parallel));
+            pretty_assertions::assert_eq!(encrypt(Traversal::Sequential), encrypt(Traversal::Parallel));
+        }
     }
 }
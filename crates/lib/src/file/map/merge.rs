@@ -0,0 +1,202 @@
+use indexmap::IndexMap;
+
+use super::diff::Leaf;
+use crate::*;
+
+/// A key path changed differently by both sides of a merge, `None` if removed or never present.
+#[derive(Debug, PartialEq)]
+pub struct KeyPathConflict {
+    pub key_path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+impl RopsMap<DecryptedMap> {
+    /// Three-way merge of `self` and `theirs`, with `base` being their common ancestor.
+    ///
+    /// Key paths changed by only one side take the value of that side. Maps are merged key by
+    /// key, whereas sequences are merged as whole values. Conflicting key paths keep the value
+    /// of `self`.
+    pub fn merge(self, base: &Self, theirs: Self) -> (Self, Vec<KeyPathConflict>) {
+        let mut conflicts = Vec::new();
+        let merged_map = merge_maps(Some(base), self, theirs, "", &mut conflicts);
+        (merged_map, conflicts)
+    }
+}
+
+fn merge_maps(
+    base: Option<&RopsMap<DecryptedMap>>,
+    mut ours: RopsMap<DecryptedMap>,
+    mut theirs: RopsMap<DecryptedMap>,
+    key_path: &str,
+    conflicts: &mut Vec<KeyPathConflict>,
+) -> RopsMap<DecryptedMap> {
+    let keys = ours
+        .keys()
+        .chain(theirs.keys().filter(|key| !ours.contains_key(*key)))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut merged_map = IndexMap::new();
+
+    for key in keys {
+        let child_key_path = match key_path.is_empty() {
+            true => key.clone(),
+            false => format!("{key_path}.{key}"),
+        };
+
+        let base_tree = base.and_then(|base| base.get(&key));
+        let ours_tree = ours.shift_remove(&key);
+        let theirs_tree = theirs.shift_remove(&key);
+
+        if let Some(merged_tree) = merge_trees(base_tree, ours_tree, theirs_tree, child_key_path, conflicts) {
+            merged_map.insert(key, merged_tree);
+        }
+    }
+
+    RopsMap(merged_map)
+}
+
+fn merge_trees(
+    base: Option<&RopsTree<DecryptedMap>>,
+    ours: Option<RopsTree<DecryptedMap>>,
+    theirs: Option<RopsTree<DecryptedMap>>,
+    key_path: String,
+    conflicts: &mut Vec<KeyPathConflict>,
+) -> Option<RopsTree<DecryptedMap>> {
+    if ours == theirs || base == theirs.as_ref() {
+        return ours;
+    }
+
+    if base == ours.as_ref() {
+        return theirs;
+    }
+
+    match (ours, theirs) {
+        (Some(RopsTree::Map(ours_map)), Some(RopsTree::Map(theirs_map))) => {
+            let base_map = match base {
+                Some(RopsTree::Map(base_map)) => Some(base_map),
+                _ => None,
+            };
+
+            Some(RopsTree::Map(merge_maps(base_map, ours_map, theirs_map, &key_path, conflicts)))
+        }
+        (ours, theirs) => {
+            conflicts.push(KeyPathConflict {
+                key_path,
+                base: base.map(display_tree),
+                ours: ours.as_ref().map(display_tree),
+                theirs: theirs.as_ref().map(display_tree),
+            });
+
+            ours
+        }
+    }
+}
+
+/// Flow style rendering of a (sub)tree.
+fn display_tree(tree: &RopsTree<DecryptedMap>) -> String {
+    match tree {
+        RopsTree::Sequence(sequence) => format!("[{}]", sequence.iter().map(display_tree).collect::<Vec<_>>().join(", ")),
+        RopsTree::Map(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, tree)| format!("{key}: {}", display_tree(tree)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        RopsTree::Null => Leaf::Null.to_string(),
+        RopsTree::Leaf(value) => Leaf::Value(value).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    fn leaf(str: &str) -> RopsTree<DecryptedMap> {
+        RopsTree::Leaf(RopsValue::String(str.to_string()))
+    }
+
+    fn map(map: IndexMap<&str, RopsTree<DecryptedMap>>) -> RopsMap<DecryptedMap> {
+        RopsMap(map.into_iter().map(|(key, tree)| (key.to_string(), tree)).collect())
+    }
+
+    #[test]
+    fn merges_unchanged_maps() {
+        let (merged_map, conflicts) = RopsMap::mock().merge(&RopsMap::mock(), RopsMap::mock());
+        assert!(conflicts.is_empty());
+        pretty_assertions::assert_eq!(RopsMap::mock(), merged_map)
+    }
+
+    #[test]
+    fn merges_changes_of_both_sides() {
+        let base = map(indexmap! {
+            "changed_by_ours" => leaf("a"),
+            "removed_by_theirs" => leaf("b"),
+            "nested" => RopsTree::Map(map(indexmap! { "changed_by_theirs" => leaf("c") })),
+        });
+        let ours = map(indexmap! {
+            "changed_by_ours" => leaf("A"),
+            "removed_by_theirs" => leaf("b"),
+            "nested" => RopsTree::Map(map(indexmap! { "changed_by_theirs" => leaf("c"), "added_by_ours" => leaf("d") })),
+        });
+        let theirs = map(indexmap! {
+            "changed_by_ours" => leaf("a"),
+            "nested" => RopsTree::Map(map(indexmap! { "changed_by_theirs" => leaf("C") })),
+            "added_by_theirs" => leaf("e"),
+        });
+
+        let (merged_map, conflicts) = ours.merge(&base, theirs);
+
+        assert!(conflicts.is_empty());
+        pretty_assertions::assert_eq!(
+            map(indexmap! {
+                "changed_by_ours" => leaf("A"),
+                "nested" => RopsTree::Map(map(indexmap! { "changed_by_theirs" => leaf("C"), "added_by_ours" => leaf("d") })),
+                "added_by_theirs" => leaf("e"),
+            }),
+            merged_map
+        )
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        let base = map(indexmap! { "changed" => leaf("a"), "removed" => leaf("b"), "sequence" => RopsTree::Sequence(vec![]) });
+        let ours = map(indexmap! { "changed" => leaf("A"), "removed" => leaf("B"), "sequence" => RopsTree::Sequence(vec![leaf("x")]) });
+        let theirs = map(indexmap! { "changed" => leaf("AA"), "sequence" => RopsTree::Sequence(vec![leaf("y"), RopsTree::Null]) });
+
+        let (merged_map, conflicts) = ours.merge(&base, theirs);
+
+        pretty_assertions::assert_eq!(
+            map(indexmap! { "changed" => leaf("A"), "removed" => leaf("B"), "sequence" => RopsTree::Sequence(vec![leaf("x")]) }),
+            merged_map
+        );
+        pretty_assertions::assert_eq!(
+            vec![
+                KeyPathConflict {
+                    key_path: "changed".to_string(),
+                    base: Some("a".to_string()),
+                    ours: Some("A".to_string()),
+                    theirs: Some("AA".to_string()),
+                },
+                KeyPathConflict {
+                    key_path: "removed".to_string(),
+                    base: Some("b".to_string()),
+                    ours: Some("B".to_string()),
+                    theirs: None,
+                },
+                KeyPathConflict {
+                    key_path: "sequence".to_string(),
+                    base: Some("[]".to_string()),
+                    ours: Some("[x]".to_string()),
+                    theirs: Some("[y, null]".to_string()),
+                },
+            ],
+            conflicts
+        )
+    }
+}
@@ -12,7 +12,7 @@ impl MockTestUtil for RopsMap<DecryptedMap> {
                         RopsTree::Leaf(RopsValue::String("string".to_string())),
                         RopsTree::Map(Self(indexmap! {
                             "nested_map_in_array".to_string() => RopsTree::Map(Self(indexmap!{
-                                "integer".to_string() => RopsTree::Leaf(RopsValue::Integer(1234))
+                                "integer".to_string() => RopsTree::Leaf(RopsValue::Integer(1234.into()))
                             })),
                         })),
                         RopsTree::Map(Self(indexmap!{
@@ -18,7 +18,16 @@ pub(crate) use saved_nonces::SavedRopsMapNonces;
 
 mod decrypt;
 
+mod diff;
+pub use diff::KeyPathDiff;
+
 mod encrypt;
 
+mod merge;
+pub use merge::KeyPathConflict;
+
+mod traversal;
+pub(crate) use traversal::Traversal;
+
 #[cfg(feature = "test-utils")]
 mod mock;
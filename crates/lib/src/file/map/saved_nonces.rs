@@ -7,14 +7,36 @@ use crate::*;
 #[impl_tools::autoimpl(PartialEq, Default)]
 #[allow(clippy::complexity)]
 // WORKAROUND: Non-cow tuple key doesn't allow saved_nounces.get((&key, &value))
-pub struct SavedRopsMapNonces<C: Cipher>(HashMap<(Cow<'static, KeyPath>, Cow<'static, RopsValue>), Nonce<C::NonceSize>>);
+pub struct SavedRopsMapNonces<C: Cipher> {
+    values: HashMap<(Cow<'static, KeyPath>, Cow<'static, RopsValue>), Nonce<C::NonceSize>>,
+    empty_trees: HashMap<(Cow<'static, KeyPath>, RopsValueVariant), Nonce<C::NonceSize>>,
+    string_variants: HashMap<(Cow<'static, KeyPath>, Cow<'static, RopsValue>), RopsValueVariant>,
+}
 
 impl<C: Cipher> SavedRopsMapNonces<C> {
     pub fn insert(&mut self, key: (KeyPath, RopsValue), value: Nonce<C::NonceSize>) {
-        self.0.insert((Cow::Owned(key.0), Cow::Owned(key.1)), value);
+        self.values.insert((Cow::Owned(key.0), Cow::Owned(key.1)), value);
     }
 
     pub fn get<'a>(&'a self, key: (&'a KeyPath, &'a RopsValue)) -> Option<&'a Nonce<C::NonceSize>> {
-        self.0.get(&(Cow::Borrowed(key.0), Cow::Borrowed(key.1)))
+        self.values.get(&(Cow::Borrowed(key.0), Cow::Borrowed(key.1)))
+    }
+
+    /// Saves the nonce of an encrypted null or empty collection, see [`RopsTree::empty_variant`].
+    pub fn insert_empty_tree(&mut self, key: (KeyPath, RopsValueVariant), value: Nonce<C::NonceSize>) {
+        self.empty_trees.insert((Cow::Owned(key.0), key.1), value);
+    }
+
+    pub fn get_empty_tree<'a>(&'a self, key: (&'a KeyPath, RopsValueVariant)) -> Option<&'a Nonce<C::NonceSize>> {
+        self.empty_trees.get(&(Cow::Borrowed(key.0), key.1))
+    }
+
+    /// Saves the `bytes` or `comment` type of a decrypted string value.
+    pub fn insert_string_variant(&mut self, key: (KeyPath, RopsValue), value: RopsValueVariant) {
+        self.string_variants.insert((Cow::Owned(key.0), Cow::Owned(key.1)), value);
+    }
+
+    pub fn get_string_variant<'a>(&'a self, key: (&'a KeyPath, &'a RopsValue)) -> Option<RopsValueVariant> {
+        self.string_variants.get(&(Cow::Borrowed(key.0), Cow::Borrowed(key.1))).copied()
     }
 }
@@ -1,5 +1,5 @@
 use crate::*;
 
 pub trait ToExternalMap<S: RopsMapState> {
-    fn to_external<F: FileFormat>(self) -> RopsFileFormatMap<S, F>;
+    fn to_external<F: FileFormat>(self) -> Result<RopsFileFormatMap<S, F>, FormatFromInternalMapError>;
 }
@@ -0,0 +1,42 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// How the sibling subtrees of a map or sequence are visited when encrypting or decrypting.
+///
+/// Results are always collected in their original order, so the resulting map, its saved
+/// nonces, and in turn its MAC, do not depend on the traversal used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Traversal {
+    // Only constructed by tests and benchmarks once `parallel` is enabled.
+    #[cfg_attr(feature = "parallel", allow(dead_code))]
+    Sequential,
+    #[cfg(feature = "parallel")]
+    Parallel,
+}
+
+impl Default for Traversal {
+    fn default() -> Self {
+        #[cfg(feature = "parallel")]
+        return Self::Parallel;
+        #[cfg(not(feature = "parallel"))]
+        return Self::Sequential;
+    }
+}
+
+impl Traversal {
+    /// Fallible ordered map, short circuiting on the first encountered error.
+    pub fn try_map<I, U, E, F>(self, items: I, f: F) -> Result<Vec<U>, E>
+    where
+        I: IntoIterator,
+        I::Item: Send,
+        U: Send,
+        E: Send,
+        F: Fn(I::Item) -> Result<U, E> + Send + Sync,
+    {
+        match self {
+            Traversal::Sequential => items.into_iter().map(f).collect(),
+            #[cfg(feature = "parallel")]
+            Traversal::Parallel => items.into_iter().collect::<Vec<_>>().into_par_iter().map(f).collect(),
+        }
+    }
+}
@@ -1,38 +1,238 @@
 use std::borrow::Cow;
 
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 use crate::*;
 
+// Decrypted values are zeroized on drop, which also means that their contents can't be moved out of
+// them, use `std::mem::take` or similar instead.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RopsValue {
     String(String),
     Boolean(bool),
-    Integer(i64),
+    Integer(RopsInteger),
     Float(RopsFloat),
 }
 
+impl Drop for RopsValue {
+    fn drop(&mut self) {
+        match self {
+            RopsValue::String(string) => string.zeroize(),
+            RopsValue::Boolean(boolean) => boolean.zeroize(),
+            RopsValue::Integer(integer) => integer.zeroize(),
+            RopsValue::Float(float) => float.zeroize(),
+        }
+    }
+}
+
+impl ZeroizeOnDrop for RopsValue {}
+
+pub use rops_integer::{RopsInteger, RopsIntegerFromStrError};
+mod rops_integer {
+    use std::{num::ParseIntError, str::FromStr};
+
+    use zeroize::Zeroize;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    // Stored as its literal to support integers of any size, and to keep how it was written, e.g. `0x1F`
+    pub struct RopsInteger(String);
+
+    impl Zeroize for RopsInteger {
+        fn zeroize(&mut self) {
+            self.0.zeroize()
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("invalid integer literal: '{0}'")]
+    pub struct RopsIntegerFromStrError(String);
+
+    // Literals accepted by any of the supported formats: an optional sign followed by either decimal
+    // digits without leading zeros, or by `0x`, `0o` or `0b` prefixed digits. Digits may be separated
+    // by single underscores, e.g. `1_000`.
+    impl FromStr for RopsInteger {
+        type Err = RopsIntegerFromStrError;
+
+        fn from_str(str: &str) -> Result<Self, Self::Err> {
+            match split_literal(str) {
+                Some(_) => Ok(Self(str.to_string())),
+                None => Err(RopsIntegerFromStrError(str.to_string())),
+            }
+        }
+    }
+
+    /// Sign, radix and digits, the latter without separators.
+    fn split_literal(literal: &str) -> Option<(bool, u32, String)> {
+        let (is_negative, unsigned) = match literal.as_bytes().first()? {
+            b'-' => (true, &literal[1..]),
+            b'+' => (false, &literal[1..]),
+            _ => (false, literal),
+        };
+
+        let (radix, digits) = match unsigned.get(..2) {
+            Some("0x") => (16, &unsigned[2..]),
+            Some("0o") => (8, &unsigned[2..]),
+            Some("0b") => (2, &unsigned[2..]),
+            _ => (10, unsigned),
+        };
+
+        let is_separated = digits.split('_').all(|group| !group.is_empty());
+        let digits = digits.replace('_', "");
+        let is_valid = is_separated && digits.chars().all(|char| char.is_digit(radix));
+        let has_leading_zero = radix == 10 && digits.len() > 1 && digits.starts_with('0');
+
+        (is_valid && !has_leading_zero).then_some((is_negative, radix, digits))
+    }
+
+    macro_rules! impl_from_primitive {
+        ($($primitive:ty),*) => {
+            $(
+                impl From<$primitive> for RopsInteger {
+                    fn from(integer: $primitive) -> Self {
+                        Self(integer.to_string())
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_from_primitive!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+    macro_rules! impl_try_into_primitive {
+        ($($primitive:ty),*) => {
+            $(
+                impl TryFrom<&RopsInteger> for $primitive {
+                    type Error = ParseIntError;
+
+                    fn try_from(rops_integer: &RopsInteger) -> Result<Self, Self::Error> {
+                        rops_integer.canonical().parse()
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_try_into_primitive!(i64, i128, u64, u128);
+
+    impl RopsInteger {
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+
+        pub fn as_bytes(&self) -> &[u8] {
+            self.0.as_bytes()
+        }
+
+        /// Plain decimal notation, the one used by SOPS, and in turn by MACs.
+        pub fn canonical(&self) -> String {
+            let (is_negative, radix, digits) = split_literal(&self.0).expect("inner string not a valid integer literal");
+
+            // Little endian decimal digits, converted one digit of the source radix at a time.
+            let mut decimal_digits = vec![0u32];
+            for digit in digits.chars().filter_map(|char| char.to_digit(radix)) {
+                let mut carry = digit;
+                for decimal_digit in decimal_digits.iter_mut() {
+                    let product = *decimal_digit * radix + carry;
+                    *decimal_digit = product % 10;
+                    carry = product / 10;
+                }
+                while carry > 0 {
+                    decimal_digits.push(carry % 10);
+                    carry /= 10;
+                }
+            }
+
+            while decimal_digits.len() > 1 && decimal_digits.last() == Some(&0) {
+                decimal_digits.pop();
+            }
+
+            let is_zero = decimal_digits == [0];
+            let sign = (is_negative && !is_zero).then_some('-');
+
+            sign.into_iter()
+                .chain(decimal_digits.iter().rev().filter_map(|digit| char::from_digit(*digit, 10)))
+                .collect()
+        }
+    }
+}
+
 pub use rops_float::RopsFloat;
 mod rops_float {
+    use std::{num::ParseFloatError, str::FromStr};
+
+    use zeroize::Zeroize;
 
     #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-    // Stored as String to enable hashing
+    // Stored as String to enable hashing, and to keep the literal it was parsed from
     pub struct RopsFloat(String);
 
+    impl Zeroize for RopsFloat {
+        fn zeroize(&mut self) {
+            self.0.zeroize()
+        }
+    }
+
     impl From<f64> for RopsFloat {
         fn from(float: f64) -> Self {
             Self(float.to_string())
         }
     }
 
+    // Besides Rust float literals, digits separated by underscores, e.g. `1_000.0`, and YAML's
+    // `.inf` and `.nan` are accepted too.
+    impl FromStr for RopsFloat {
+        type Err = ParseFloatError;
+
+        fn from_str(str: &str) -> Result<Self, Self::Err> {
+            parse_literal(str).map(|_| Self(str.to_string()))
+        }
+    }
+
+    fn parse_literal(literal: &str) -> Result<f64, ParseFloatError> {
+        let (sign, unsigned) = literal.split_at(literal.len() - literal.trim_start_matches(['-', '+']).len());
+        if let Some(special @ ("inf" | "Inf" | "INF" | "nan" | "NaN" | "NAN")) = unsigned.strip_prefix('.') {
+            return format!("{sign}{special}").parse();
+        }
+
+        let bytes = literal.as_bytes();
+        let is_separated = bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'_')
+            .all(|(index, _)| index > 0 && bytes[index - 1].is_ascii_digit() && bytes.get(index + 1).is_some_and(u8::is_ascii_digit));
+
+        match is_separated {
+            true => literal.replace('_', "").parse(),
+            // Yields the error of the literal as is.
+            false => literal.parse(),
+        }
+    }
+
     impl From<RopsFloat> for f64 {
         fn from(rops_float: RopsFloat) -> Self {
-            rops_float.0.parse().expect("inner string not a valid f64 string")
+            Self::from(&rops_float)
+        }
+    }
+
+    impl From<&RopsFloat> for f64 {
+        fn from(rops_float: &RopsFloat) -> Self {
+            parse_literal(&rops_float.0).expect("inner string not a valid float literal")
         }
     }
 
     impl RopsFloat {
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+
         pub fn as_bytes(&self) -> &[u8] {
             self.0.as_bytes()
         }
+
+        /// Shortest decimal notation, the one used by SOPS, and in turn by MACs.
+        pub fn canonical(&self) -> String {
+            f64::from(self).to_string()
+        }
     }
 }
 
@@ -46,16 +246,7 @@ impl RopsValue {
         data_key: &DataKey,
         key_path: &KeyPath,
     ) -> Result<EncryptedRopsValue<C>, C::Error> {
-        let mut in_place_buffer = self.as_bytes().to_vec();
-
-        let authorization_tag = C::encrypt(&nonce, data_key, &mut in_place_buffer, key_path.as_ref())?;
-
-        Ok(EncryptedRopsValue {
-            data: in_place_buffer.into(),
-            authorization_tag,
-            nonce,
-            value_variant: self.into(),
-        })
+        EncryptedRopsValue::encrypt_plaintext(self.as_bytes().into_owned(), self.into(), nonce, data_key, key_path)
     }
 
     pub fn as_bytes(&self) -> Cow<'_, [u8]> {
@@ -65,21 +256,36 @@ impl RopsValue {
                 true => Self::BOOLEAN_TRUE_BYTES,
                 false => Self::BOOLEAN_FALSE_BYTES,
             }),
-            RopsValue::Integer(integer) => Cow::Owned(integer.to_string().into_bytes()),
+            RopsValue::Integer(integer) => Cow::Borrowed(integer.as_bytes()),
             RopsValue::Float(float_string) => Cow::Borrowed(float_string.as_bytes()),
         }
     }
 
-    pub fn from_bytes(bytes: Vec<u8>, variant: RopsValueVariant) -> Result<Self, RopsValueFromBytesError> {
+    // Numbers are encrypted with their literal, but hashed in their canonical form, keeping MACs
+    // compatible with SOPS, which only retains the parsed value.
+    pub(crate) fn as_mac_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            RopsValue::Integer(integer) => Cow::Owned(integer.canonical().into_bytes()),
+            RopsValue::Float(float) => Cow::Owned(float.canonical().into_bytes()),
+            _ => self.as_bytes(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8], variant: RopsValueVariant) -> Result<Self, RopsValueFromBytesError> {
         Ok(match variant {
-            RopsValueVariant::String => Self::String(std::str::from_utf8(&bytes)?.to_string()),
-            RopsValueVariant::Boolean => Self::Boolean(match bytes.as_slice() {
+            RopsValueVariant::String | RopsValueVariant::Bytes | RopsValueVariant::Comment => {
+                Self::String(std::str::from_utf8(bytes)?.to_string())
+            }
+            RopsValueVariant::Boolean => Self::Boolean(match bytes {
                 Self::BOOLEAN_TRUE_BYTES => true,
                 Self::BOOLEAN_FALSE_BYTES => false,
-                _ => return Err(RopsValueFromBytesError::Boolean(bytes)),
+                _ => return Err(RopsValueFromBytesError::Boolean(bytes.to_vec())),
             }),
-            RopsValueVariant::Integer => Self::Integer(std::str::from_utf8(&bytes)?.parse()?),
-            RopsValueVariant::Float => Self::Float(std::str::from_utf8(&bytes)?.parse::<f64>()?.into()),
+            RopsValueVariant::Integer => Self::Integer(std::str::from_utf8(bytes)?.parse()?),
+            RopsValueVariant::Float => Self::Float(std::str::from_utf8(bytes)?.parse()?),
+            RopsValueVariant::Null | RopsValueVariant::Map | RopsValueVariant::Sequence => {
+                return Err(RopsValueFromBytesError::EmptyTree(variant));
+            }
         })
     }
 }
@@ -91,9 +297,11 @@ pub enum RopsValueFromBytesError {
     #[error("invalid byte representation for boolean values: {0:?}")]
     Boolean(Vec<u8>),
     #[error("integer parse error: {0}")]
-    Integer(#[from] std::num::ParseIntError),
+    Integer(#[from] RopsIntegerFromStrError),
     #[error("float parse error: {0}")]
     Float(#[from] std::num::ParseFloatError),
+    #[error("'{}' values are empty trees, not leaf values", .0.as_ref())]
+    EmptyTree(RopsValueVariant),
 }
 
 impl From<&RopsValue> for RopsValueVariant {
@@ -120,6 +328,97 @@ mod mock {
 
 #[cfg(test)]
 mod tests {
+    use crate::*;
+
+    #[test]
+    fn zeroizes_string_on_drop() {
+        let rops_value = RopsValue::String("secret".to_string());
+        let RopsValue::String(string) = &rops_value else { unreachable!() };
+        let (ptr, len) = (string.as_ptr(), string.len());
+        assert!(zeroed_on_drop(rops_value, ptr, len))
+    }
+
+    #[test]
+    fn parses_integer_literals_of_any_size() {
+        for literal in [
+            "0",
+            "-0",
+            "1234",
+            "-9223372036854775809",
+            "18446744073709551616",
+            "123456789012345678901234567890",
+        ] {
+            assert_eq!(literal, literal.parse::<RopsInteger>().unwrap().as_str())
+        }
+    }
+
+    #[test]
+    fn keeps_integer_literals() {
+        for literal in ["+1", "0x1F", "-0x1f", "0o17", "0b101", "1_000", "0xdead_beef"] {
+            let rops_value = RopsValue::from_bytes(literal.as_bytes(), RopsValueVariant::Integer).unwrap();
+            assert_eq!(literal.as_bytes(), rops_value.as_bytes().as_ref())
+        }
+    }
+
+    #[test]
+    fn disallows_invalid_integer_literals() {
+        for literal in ["", "-", "+-1", "01", "1.0", "1e3", "0x", "0xG", "0o8", "_1", "1_", "1__0", "0x_1"] {
+            assert!(literal.parse::<RopsInteger>().is_err(), "{literal}")
+        }
+    }
+
+    #[test]
+    fn converts_integers_to_canonical_form() {
+        for (literal, canonical) in [
+            ("1234", "1234"),
+            ("+1", "1"),
+            ("-0", "0"),
+            ("0x1F", "31"),
+            ("-0x1f", "-31"),
+            ("0o17", "15"),
+            ("0b101", "5"),
+            ("1_000", "1000"),
+            ("0xffffffffffffffffffffffffffffffffff", "87112285931760246646623899502532662132735"),
+        ] {
+            assert_eq!(canonical, literal.parse::<RopsInteger>().unwrap().canonical())
+        }
+    }
+
+    #[test]
+    fn keeps_float_literals() {
+        for literal in [
+            "1.0",
+            "1.50",
+            "1e10",
+            "1E+10",
+            "0.10000000000000000001",
+            "-0.0",
+            "1_000.0",
+            ".inf",
+            "-.Inf",
+            ".NaN",
+        ] {
+            let rops_value = RopsValue::from_bytes(literal.as_bytes(), RopsValueVariant::Float).unwrap();
+            assert_eq!(literal.as_bytes(), rops_value.as_bytes().as_ref())
+        }
+    }
+
+    #[test]
+    fn disallows_invalid_float_literals() {
+        for literal in ["", "1._0", "_1.0", "1.0_", "1__0.0", ".Nan", "..inf"] {
+            assert!(literal.parse::<RopsFloat>().is_err(), "{literal}")
+        }
+    }
+
+    #[test]
+    fn hashes_numbers_in_canonical_form() {
+        let rops_value = RopsValue::Integer("0x1F".parse().unwrap());
+        assert_eq!(b"31", rops_value.as_mac_bytes().as_ref());
+
+        let rops_value = RopsValue::Float("1e10".parse().unwrap());
+        assert_eq!(b"10000000000", rops_value.as_mac_bytes().as_ref())
+    }
+
     #[cfg(feature = "aes-gcm")]
     mod aes_gcm {
         use crate::*;
@@ -167,7 +466,7 @@ mod tests {
             assert_encrypts_value(
                 "ENC[AES256_GCM,data:lDJCrw==,iv:P8EXxNCPeYp5VBL0mCAxjQjGtvywbBFoQKWye2IK1Gc=,tag:56HP04AzkYfj+pmYIbijSA==,type:int]",
                 "example_integer:",
-                RopsValue::Integer(1234),
+                RopsValue::Integer(1234.into()),
             );
         }
         #[test]
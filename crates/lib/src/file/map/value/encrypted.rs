@@ -33,20 +33,76 @@ pub enum EncryptedRopsValueFromStrError {
     Base64Decode(#[from] Base64DecodeError),
 }
 
+/// Cipher name of an `ENC[...]` string, be it that of an encrypted value or of an encrypted MAC.
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub(crate) fn encrypted_cipher_name(encrypted_str: &str) -> Option<&str> {
+    encrypted_str.strip_prefix("ENC[")?.split(',').next()
+}
+
 impl<C: Cipher> EncryptedRopsValue<C> {
+    pub(crate) fn encrypt_plaintext(
+        plaintext: Vec<u8>,
+        value_variant: RopsValueVariant,
+        nonce: Nonce<C::NonceSize>,
+        data_key: &DataKey,
+        key_path: &KeyPath,
+    ) -> Result<Self, C::Error> {
+        let mut in_place_buffer = SecretBuffer::new(plaintext);
+
+        let authorization_tag = C::encrypt(&nonce, data_key, &mut in_place_buffer, key_path.as_ref())?;
+
+        Ok(EncryptedRopsValue {
+            data: in_place_buffer.to_vec().into(),
+            authorization_tag,
+            nonce,
+            value_variant,
+        })
+    }
+
+    /// Encrypts a null or an empty collection, see [`RopsTree::empty_variant`].
+    pub(crate) fn encrypt_empty_tree(
+        value_variant: RopsValueVariant,
+        nonce: Nonce<C::NonceSize>,
+        data_key: &DataKey,
+        key_path: &KeyPath,
+    ) -> Result<Self, C::Error> {
+        let plaintext = value_variant.empty_tree_bytes().expect("variant of an empty tree");
+        Self::encrypt_plaintext(plaintext.to_vec(), value_variant, nonce, data_key, key_path)
+    }
+
     pub fn decrypt(self, data_key: &DataKey, key_path: &KeyPath) -> Result<RopsValue, DecryptRopsValueError> {
-        let mut in_place_buffer = self.data;
+        let (plaintext, value_variant) = self.decrypt_plaintext(data_key, key_path)?;
+        RopsValue::from_bytes(&plaintext, value_variant).map_err(Into::into)
+    }
+
+    /// Like [`EncryptedRopsValue::decrypt`], but also accepts encrypted nulls and empty collections.
+    pub fn decrypt_tree(self, data_key: &DataKey, key_path: &KeyPath) -> Result<RopsTree<DecryptedMap>, DecryptRopsValueError> {
+        let (plaintext, value_variant) = self.decrypt_plaintext(data_key, key_path)?;
+
+        match value_variant.empty_tree() {
+            Some(empty_tree) => match value_variant.empty_tree_bytes() == Some(&plaintext) {
+                true => Ok(empty_tree),
+                false => Err(RopsValueFromBytesError::EmptyTree(value_variant).into()),
+            },
+            None => RopsValue::from_bytes(&plaintext, value_variant)
+                .map(RopsTree::Leaf)
+                .map_err(Into::into),
+        }
+    }
+
+    fn decrypt_plaintext(self, data_key: &DataKey, key_path: &KeyPath) -> Result<(SecretBuffer, RopsValueVariant), DecryptRopsValueError> {
+        let mut in_place_buffer = SecretBuffer::new(self.data.into());
 
         C::decrypt(
             &self.nonce,
             data_key,
-            in_place_buffer.as_mut(),
+            &mut in_place_buffer,
             key_path.as_ref(),
             &self.authorization_tag,
         )
         .map_err(|error| DecryptRopsValueError::Cipher(error.into()))?;
 
-        RopsValue::from_bytes(in_place_buffer.into(), self.value_variant).map_err(Into::into)
+        Ok((in_place_buffer, self.value_variant))
     }
 }
 
@@ -168,6 +224,16 @@ mod tests {
             assert!(matches!(parse_error, EncryptedRopsValueFromStrError::InvalidCipher(_, _)))
         }
 
+        #[cfg(any(feature = "sha2", feature = "blake3"))]
+        #[test]
+        fn reads_cipher_name() {
+            assert_eq!(
+                Some(AES256GCM::NAME),
+                encrypted_cipher_name(&EncryptedRopsValue::<AES256GCM>::mock_display())
+            );
+            assert_eq!(None, encrypted_cipher_name("plaintext"));
+        }
+
         #[test]
         fn displays_value() {
             DisplayTestUtils::assert_display::<EncryptedRopsValue<AES256GCM>>()
@@ -187,5 +253,53 @@ mod tests {
                     .unwrap()
             )
         }
+
+        #[test]
+        fn decrypts_empty_trees() {
+            for empty_variant in [RopsValueVariant::Null, RopsValueVariant::Map, RopsValueVariant::Sequence] {
+                let encrypted_value = EncryptedRopsValue::<AES256GCM>::encrypt_empty_tree(
+                    empty_variant,
+                    Nonce::new(),
+                    &MockTestUtil::mock(),
+                    &MockTestUtil::mock(),
+                )
+                .unwrap();
+
+                let decrypted_tree = encrypted_value.decrypt_tree(&MockTestUtil::mock(), &MockTestUtil::mock()).unwrap();
+                assert_eq!(Some(empty_variant), decrypted_tree.empty_variant())
+            }
+        }
+
+        #[test]
+        fn disallows_empty_tree_as_value() {
+            let encrypted_value = EncryptedRopsValue::<AES256GCM>::encrypt_empty_tree(
+                RopsValueVariant::Null,
+                Nonce::new(),
+                &MockTestUtil::mock(),
+                &MockTestUtil::mock(),
+            )
+            .unwrap();
+
+            assert!(matches!(
+                encrypted_value.decrypt(&MockTestUtil::mock(), &MockTestUtil::mock()),
+                Err(DecryptRopsValueError::FromBytes(RopsValueFromBytesError::EmptyTree(
+                    RopsValueVariant::Null
+                )))
+            ))
+        }
+
+        #[test]
+        fn disallows_non_empty_tree_plaintext() {
+            let encrypted_value = EncryptedRopsValue::<AES256GCM>::encrypt_plaintext(
+                b"secret".to_vec(),
+                RopsValueVariant::Map,
+                Nonce::new(),
+                &MockTestUtil::mock(),
+                &MockTestUtil::mock(),
+            )
+            .unwrap();
+
+            assert!(encrypted_value.decrypt_tree(&MockTestUtil::mock(), &MockTestUtil::mock()).is_err())
+        }
     }
 }
@@ -2,7 +2,11 @@ mod variant;
 pub(crate) use variant::RopsValueVariant;
 
 mod core;
-pub(crate) use core::{RopsValue, RopsValueFromBytesError};
+#[cfg(feature = "toml-edit")]
+pub(crate) use core::RopsFloat;
+pub(crate) use core::{RopsInteger, RopsValue, RopsValueFromBytesError};
 
 mod encrypted;
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub(crate) use encrypted::encrypted_cipher_name;
 pub(crate) use encrypted::{DecryptRopsValueError, EncryptedRopsValue, EncryptedRopsValueFromStrError};
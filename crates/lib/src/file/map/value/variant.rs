@@ -1,6 +1,11 @@
 use strum::{AsRefStr, EnumString};
 
-#[derive(Debug, PartialEq, AsRefStr, EnumString)]
+use crate::*;
+
+// Covers every type tag emitted by SOPS, along with the rops specific `null`, `map` and `seq`
+// tags. The latter are only used for empty values, encrypted when opting into it with
+// `encrypt_empty_values`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, EnumString)]
 pub enum RopsValueVariant {
     #[strum(serialize = "str")]
     String,
@@ -10,12 +15,64 @@ pub enum RopsValueVariant {
     Integer,
     #[strum(serialize = "float")]
     Float,
+    #[strum(serialize = "bytes")]
+    Bytes,
+    #[strum(serialize = "comment")]
+    Comment,
+    #[strum(serialize = "null")]
+    Null,
+    #[strum(serialize = "map")]
+    Map,
+    #[strum(serialize = "seq")]
+    Sequence,
+}
+
+impl RopsValueVariant {
+    /// Plaintext of the variants representing empty trees rather than values.
+    pub(crate) fn empty_tree_bytes(&self) -> Option<&'static [u8]> {
+        match self {
+            RopsValueVariant::Null => Some(b"null"),
+            RopsValueVariant::Map => Some(b"{}"),
+            RopsValueVariant::Sequence => Some(b"[]"),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn empty_tree(&self) -> Option<RopsTree<DecryptedMap>> {
+        match self {
+            RopsValueVariant::Null => Some(RopsTree::Null),
+            RopsValueVariant::Map => Some(RopsTree::Map(RopsMap(Default::default()))),
+            RopsValueVariant::Sequence => Some(RopsTree::Sequence(Vec::new())),
+            _ => None,
+        }
+    }
+}
+
+impl RopsTree<DecryptedMap> {
+    /// Variant of nulls and empty collections, for when these are encrypted.
+    pub(crate) fn empty_variant(&self) -> Option<RopsValueVariant> {
+        match self {
+            RopsTree::Null => Some(RopsValueVariant::Null),
+            RopsTree::Map(map) if map.is_empty() => Some(RopsValueVariant::Map),
+            RopsTree::Sequence(sequence) if sequence.is_empty() => Some(RopsValueVariant::Sequence),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SOPS_TYPE_TAGS: &[(&str, RopsValueVariant)] = &[
+        ("str", RopsValueVariant::String),
+        ("bool", RopsValueVariant::Boolean),
+        ("int", RopsValueVariant::Integer),
+        ("float", RopsValueVariant::Float),
+        ("bytes", RopsValueVariant::Bytes),
+        ("comment", RopsValueVariant::Comment),
+    ];
+
     #[test]
     fn displays_string_type() {
         assert_eq!("str", RopsValueVariant::String.as_ref())
@@ -25,4 +82,20 @@ mod tests {
     fn parses_string_type() {
         assert_eq!(RopsValueVariant::String, "str".parse::<RopsValueVariant>().unwrap())
     }
+
+    #[test]
+    fn parses_every_sops_type() {
+        for (type_tag, variant) in SOPS_TYPE_TAGS {
+            assert_eq!(*variant, type_tag.parse::<RopsValueVariant>().unwrap());
+            assert_eq!(*type_tag, variant.as_ref());
+        }
+    }
+
+    #[test]
+    fn empty_trees_round_trip() {
+        for variant in [RopsValueVariant::Null, RopsValueVariant::Map, RopsValueVariant::Sequence] {
+            assert_eq!(Some(variant), variant.empty_tree().unwrap().empty_variant());
+            assert!(variant.empty_tree_bytes().is_some())
+        }
+    }
 }
@@ -22,6 +22,11 @@ where
     #[serde(flatten)]
     pub partial_encryption: Option<PartialEncryptionConfig>,
     pub mac_only_encrypted: Option<bool>,
+    /// Whether nulls, empty strings and empty collections are encrypted, which SOPS leaves as is.
+    /// Files of which nulls or empty collections are encrypted can't be decrypted by SOPS.
+    pub encrypt_empty_values: Option<bool>,
+    #[serde(skip_serializing_if = "MacHasher::is_sops_compatible")]
+    pub mac_hasher: MacHasher<S::Hasher>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -57,7 +62,7 @@ impl<C: Cipher, H: Hasher> RopsFileMetadata<EncryptedMetadata<C, H>> {
         let data_key = self.retrieve_data_key()?;
 
         #[rustfmt::skip]
-        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted } = self;
+        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted, encrypt_empty_values, mac_hasher } = self;
 
         let decrypted_map = mac
             .decrypt(&data_key, &last_modified)
@@ -69,6 +74,8 @@ impl<C: Cipher, H: Hasher> RopsFileMetadata<EncryptedMetadata<C, H>> {
             mac: decrypted_map,
             partial_encryption,
             mac_only_encrypted,
+            encrypt_empty_values,
+            mac_hasher,
         };
 
         Ok((decrypted_metadata, data_key))
@@ -81,7 +88,7 @@ impl<C: Cipher, H: Hasher> RopsFileMetadata<EncryptedMetadata<C, H>> {
         let data_key = self.retrieve_data_key()?;
 
         #[rustfmt::skip]
-        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted } = self;
+        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted, encrypt_empty_values, mac_hasher } = self;
 
         let (decrypted_map, saved_mac_nonce) = mac
             .decrypt_and_save_nonce(&data_key, &last_modified)
@@ -93,6 +100,8 @@ impl<C: Cipher, H: Hasher> RopsFileMetadata<EncryptedMetadata<C, H>> {
             mac: decrypted_map,
             partial_encryption,
             mac_only_encrypted,
+            encrypt_empty_values,
+            mac_hasher,
         };
 
         Ok((decrypted_metadata, data_key, saved_mac_nonce))
@@ -138,7 +147,7 @@ impl<H: Hasher> RopsFileMetadata<DecryptedMetadata<H>> {
 
     pub fn encrypt<C: Cipher>(self, data_key: &DataKey) -> Result<RopsFileMetadata<EncryptedMetadata<C, H>>, C::Error> {
         #[rustfmt::skip]
-        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted } = self;
+        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted, encrypt_empty_values, mac_hasher } = self;
 
         Ok(RopsFileMetadata {
             intregation,
@@ -146,6 +155,8 @@ impl<H: Hasher> RopsFileMetadata<DecryptedMetadata<H>> {
             last_modified,
             partial_encryption,
             mac_only_encrypted,
+            encrypt_empty_values,
+            mac_hasher,
         })
     }
 
@@ -155,7 +166,7 @@ impl<H: Hasher> RopsFileMetadata<DecryptedMetadata<H>> {
         saved_mac_nonce: SavedMacNonce<C, H>,
     ) -> Result<RopsFileMetadata<EncryptedMetadata<C, H>>, C::Error> {
         #[rustfmt::skip]
-        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted } = self;
+        let RopsFileMetadata { intregation, last_modified, mac, partial_encryption, mac_only_encrypted, encrypt_empty_values, mac_hasher } = self;
 
         Ok(RopsFileMetadata {
             intregation,
@@ -163,6 +174,8 @@ impl<H: Hasher> RopsFileMetadata<DecryptedMetadata<H>> {
             last_modified,
             partial_encryption,
             mac_only_encrypted,
+            encrypt_empty_values,
+            mac_hasher,
         })
     }
 }
@@ -183,6 +196,8 @@ mod mock {
                 mac: MockTestUtil::mock(),
                 partial_encryption: Some(MockTestUtil::mock()),
                 mac_only_encrypted: None,
+                encrypt_empty_values: None,
+                mac_hasher: MacHasher::default(),
             }
         }
     }
@@ -0,0 +1,136 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::*;
+
+#[derive(Debug, PartialEq)]
+pub enum MetadataDiff {
+    IntegrationKeyAdded {
+        integration: &'static str,
+        key_id: String,
+    },
+    IntegrationKeyRemoved {
+        integration: &'static str,
+        key_id: String,
+    },
+    PartialEncryption {
+        old: Option<PartialEncryptionConfig>,
+        new: Option<PartialEncryptionConfig>,
+    },
+    MacOnlyEncrypted {
+        old: Option<bool>,
+        new: Option<bool>,
+    },
+    EncryptEmptyValues {
+        old: Option<bool>,
+        new: Option<bool>,
+    },
+}
+
+impl<S: RopsMetadataState> RopsFileMetadata<S>
+where
+    <S::Mac as FromStr>::Err: Display,
+{
+    /// Reports settings which affect how the map is encrypted, MACs and timestamps are ignored.
+    pub fn diff<So: RopsMetadataState>(&self, other: &RopsFileMetadata<So>) -> Vec<MetadataDiff>
+    where
+        <So::Mac as FromStr>::Err: Display,
+    {
+        let mut diffs = Vec::new();
+
+        #[cfg(feature = "age")]
+        diff_integration_keys(&self.intregation.age, &other.intregation.age, &mut diffs);
+
+        #[cfg(feature = "aws-kms")]
+        diff_integration_keys(&self.intregation.kms, &other.intregation.kms, &mut diffs);
+
+        if self.partial_encryption != other.partial_encryption {
+            diffs.push(MetadataDiff::PartialEncryption {
+                old: self.partial_encryption.clone(),
+                new: other.partial_encryption.clone(),
+            })
+        }
+
+        if self.mac_only_encrypted != other.mac_only_encrypted {
+            diffs.push(MetadataDiff::MacOnlyEncrypted {
+                old: self.mac_only_encrypted,
+                new: other.mac_only_encrypted,
+            })
+        }
+
+        if self.encrypt_empty_values != other.encrypt_empty_values {
+            diffs.push(MetadataDiff::EncryptEmptyValues {
+                old: self.encrypt_empty_values,
+                new: other.encrypt_empty_values,
+            })
+        }
+
+        diffs
+    }
+}
+
+#[cfg(any(feature = "age", feature = "aws-kms"))]
+fn diff_integration_keys<I: Integration>(
+    old_units: &IntegrationMetadataUnits<I>,
+    new_units: &IntegrationMetadataUnits<I>,
+    diffs: &mut Vec<MetadataDiff>,
+) where
+    I::KeyId: Display,
+{
+    for key_id in old_units.keys().filter(|key_id| !new_units.contains_key(*key_id)) {
+        diffs.push(MetadataDiff::IntegrationKeyRemoved {
+            integration: I::NAME,
+            key_id: key_id.to_string(),
+        })
+    }
+
+    for key_id in new_units.keys().filter(|key_id| !old_units.contains_key(*key_id)) {
+        diffs.push(MetadataDiff::IntegrationKeyAdded {
+            integration: I::NAME,
+            key_id: key_id.to_string(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "age"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_identical_metadata() {
+        let metadata = RopsFileMetadata::<DecryptedMetadata<SHA512>>::mock();
+        assert!(metadata.diff(&RopsFileMetadata::<DecryptedMetadata<SHA512>>::mock()).is_empty())
+    }
+
+    #[test]
+    fn diffs_metadata() {
+        let old_metadata = RopsFileMetadata::<DecryptedMetadata<SHA512>>::mock();
+
+        let mut new_metadata = RopsFileMetadata::<DecryptedMetadata<SHA512>>::mock();
+        new_metadata.intregation.age = IntegrationMetadataUnits::default();
+        new_metadata.partial_encryption = None;
+        new_metadata.mac_only_encrypted = Some(true);
+        new_metadata.encrypt_empty_values = Some(true);
+
+        pretty_assertions::assert_eq!(
+            vec![
+                MetadataDiff::IntegrationKeyRemoved {
+                    integration: AgeIntegration::NAME,
+                    key_id: <AgeIntegration as Integration>::KeyId::mock_display()
+                },
+                MetadataDiff::PartialEncryption {
+                    old: old_metadata.partial_encryption.clone(),
+                    new: None
+                },
+                MetadataDiff::MacOnlyEncrypted {
+                    old: old_metadata.mac_only_encrypted,
+                    new: Some(true)
+                },
+                MetadataDiff::EncryptEmptyValues {
+                    old: old_metadata.encrypt_empty_values,
+                    new: Some(true)
+                },
+            ],
+            old_metadata.diff(&new_metadata)
+        )
+    }
+}
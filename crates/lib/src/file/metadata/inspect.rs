@@ -0,0 +1,104 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// An integration metadata unit, minus its encrypted data key.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IntegrationKeyInfo {
+    pub integration: &'static str,
+    pub key_id: String,
+    pub created_at: Option<String>,
+}
+
+impl<S: RopsMetadataState> RopsFileMetadata<S>
+where
+    <S::Mac as FromStr>::Err: Display,
+{
+    pub fn integration_keys(&self) -> Vec<IntegrationKeyInfo> {
+        #[cfg_attr(not(any(feature = "age", feature = "aws-kms")), allow(unused_mut))]
+        let mut key_infos = Vec::new();
+
+        #[cfg(feature = "age")]
+        collect_integration_keys(&self.intregation.age, &mut key_infos);
+
+        #[cfg(feature = "aws-kms")]
+        collect_integration_keys(&self.intregation.kms, &mut key_infos);
+
+        key_infos
+    }
+}
+
+#[cfg(any(feature = "age", feature = "aws-kms"))]
+fn collect_integration_keys<I: Integration>(units: &IntegrationMetadataUnits<I>, key_infos: &mut Vec<IntegrationKeyInfo>)
+where
+    I::KeyId: Display,
+{
+    for (key_id, unit) in units.iter() {
+        key_infos.push(IntegrationKeyInfo {
+            integration: I::NAME,
+            key_id: key_id.to_string(),
+            created_at: unit.created_at.as_ref().map(ToString::to_string),
+        })
+    }
+}
+
+impl<C: Cipher, H: Hasher> RopsFileMetadata<EncryptedMetadata<C, H>> {
+    /// Deserializes the metadata of an encrypted rops file whilst skipping its map, no private key
+    /// is therefore needed.
+    pub fn from_rops_file_str<F: FileFormat>(rops_file_str: &str) -> Result<Self, F::DeserializeError> {
+        #[derive(Deserialize)]
+        #[serde(bound = "")]
+        struct MetadataOnly<C: Cipher, H: Hasher> {
+            sops: RopsFileMetadata<EncryptedMetadata<C, H>>,
+        }
+
+        F::deserialize_from_str::<MetadataOnly<C, H>>(rops_file_str).map(|metadata_only| metadata_only.sops)
+    }
+
+    pub fn cipher_name(&self) -> &'static str {
+        C::NAME
+    }
+
+    pub fn hasher_name(&self) -> &'static str {
+        H::NAME
+    }
+}
+
+#[cfg(all(test, feature = "yaml", feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_integration_keys() {
+        #[allow(unused_mut)]
+        let mut expected_key_infos = vec![IntegrationKeyInfo {
+            integration: AgeIntegration::NAME,
+            key_id: <AgeIntegration as Integration>::KeyId::mock_display(),
+            created_at: None,
+        }];
+
+        #[cfg(feature = "aws-kms")]
+        expected_key_infos.push(IntegrationKeyInfo {
+            integration: AwsKmsIntegration::NAME,
+            key_id: <AwsKmsIntegration as Integration>::KeyId::mock_display(),
+            created_at: Some(IntegrationCreatedAt::mock_display()),
+        });
+
+        pretty_assertions::assert_eq!(
+            expected_key_infos,
+            RopsFileMetadata::<DecryptedMetadata<SHA512>>::mock().integration_keys()
+        )
+    }
+
+    #[test]
+    fn deserializes_metadata_only() {
+        let rops_file_string = RopsFile::<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>::mock().to_string();
+
+        pretty_assertions::assert_eq!(
+            RopsFileMetadata::mock(),
+            RopsFileMetadata::<EncryptedMetadata<AES256GCM, SHA512>>::from_rops_file_str::<YamlFileFormat>(&rops_file_string).unwrap()
+        )
+    }
+}
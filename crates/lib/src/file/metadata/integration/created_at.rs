@@ -1,8 +1,9 @@
+use derive_more::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::*;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Display)]
 #[serde(transparent)]
 pub struct IntegrationCreatedAt(Timestamp);
 
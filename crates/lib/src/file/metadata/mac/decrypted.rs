@@ -1,6 +1,7 @@
 use std::{fmt::Display, marker::PhantomData, str::FromStr};
 
 use hex::FromHexError;
+use subtle::{Choice, ConstantTimeEq};
 
 use crate::*;
 
@@ -17,11 +18,23 @@ const MAC_ENCRYPTED_ONLY_INIT_BYTES: [u8; 32] = [
 // But because where clauses aren't inferred in any function signature containing Mac<H>, a Vec is
 // used instead. https://github.com/rust-lang/rust/issues/20671
 //
-// NOTE: Non-constant equality checking is currently protected against timing attacks
-// by requiring that the MAC must be decrypted before being compared.
-#[impl_tools::autoimpl(Debug, Clone, PartialEq)]
+// NOTE: Equality checking is done in constant time so that comparing a computed MAC against
+// a stored one doesn't leak how many leading bytes matched.
+#[impl_tools::autoimpl(Debug, Clone)]
 pub struct Mac<H: Hasher>(pub(crate) Vec<u8>, pub(crate) PhantomData<H>);
 
+impl<H: Hasher> ConstantTimeEq for Mac<H> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.as_slice().ct_eq(other.0.as_slice())
+    }
+}
+
+impl<H: Hasher> PartialEq for Mac<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
 impl<H: Hasher> FromStr for Mac<H> {
     type Err = FromHexError;
 
@@ -42,15 +55,11 @@ impl<H: Hasher> Display for Mac<H> {
 
 impl<H: Hasher> Mac<H> {
     pub fn compute(mac_only_encrypted_config: MacOnlyEncryptedConfig<'_>, decrypted_map: &RopsMap<DecryptedMap>) -> Self {
-        let mut hasher = H::new();
-
-        if mac_only_encrypted_config.mac_only_encrypted {
-            hasher.update(MAC_ENCRYPTED_ONLY_INIT_BYTES);
-        }
+        let mut hasher = Self::new_hasher(mac_only_encrypted_config.mac_only_encrypted);
 
         traverse_map(&mut hasher, mac_only_encrypted_config, decrypted_map);
 
-        return Mac(hex::encode_upper(hasher.finalize()).into_bytes(), PhantomData);
+        return Self::from_hasher(hasher);
 
         fn traverse_map<Ha: Hasher>(hasher: &mut Ha, mac_only_encrypted_config: MacOnlyEncryptedConfig<'_>, map: &RopsMap<DecryptedMap>) {
             traverse_map_recursive(hasher, mac_only_encrypted_config, map);
@@ -77,25 +86,49 @@ impl<H: Hasher> Mac<H> {
                 mac_only_encrypted_config: MacOnlyEncryptedConfig<'_>,
                 tree: &RopsTree<DecryptedMap>,
             ) {
+                if mac_only_encrypted_config.encrypt_empty_values {
+                    if let Some(empty_tree_bytes) = tree.empty_variant().and_then(|empty_variant| empty_variant.empty_tree_bytes()) {
+                        return update_leaf(hasher, mac_only_encrypted_config, empty_tree_bytes);
+                    }
+                }
+
                 match tree {
                     RopsTree::Sequence(sequence) => sequence
                         .iter()
                         .for_each(|sub_tree| traverse_tree_recursive(hasher, mac_only_encrypted_config, sub_tree)),
                     RopsTree::Map(map) => traverse_map_recursive(hasher, mac_only_encrypted_config, map),
                     RopsTree::Null => (),
-                    RopsTree::Leaf(value) => {
-                        #[rustfmt::skip]
-                    let MacOnlyEncryptedConfig { mac_only_encrypted, resolved_partial_encryption } = mac_only_encrypted_config;
+                    RopsTree::Leaf(value) => update_leaf(hasher, mac_only_encrypted_config, &value.as_mac_bytes()),
+                }
+            }
 
-                        if !(resolved_partial_encryption.escape_encryption() && mac_only_encrypted) {
-                            hasher.update(value.as_bytes())
-                        }
-                    }
+            fn update_leaf<H: Hasher>(hasher: &mut H, mac_only_encrypted_config: MacOnlyEncryptedConfig<'_>, bytes: &[u8]) {
+                #[rustfmt::skip]
+                let MacOnlyEncryptedConfig { mac_only_encrypted, resolved_partial_encryption, .. } = mac_only_encrypted_config;
+
+                if !(resolved_partial_encryption.escape_encryption() && mac_only_encrypted) {
+                    hasher.update(bytes)
                 }
             }
         }
     }
 
+    /// Hasher to be updated with the bytes of each value in traversal order, for when the MAC is
+    /// computed without having the whole map at hand. See [`Mac::from_hasher`].
+    pub(crate) fn new_hasher(mac_only_encrypted: bool) -> H {
+        let mut hasher = H::new();
+
+        if mac_only_encrypted {
+            hasher.update(MAC_ENCRYPTED_ONLY_INIT_BYTES);
+        }
+
+        hasher
+    }
+
+    pub(crate) fn from_hasher(hasher: H) -> Self {
+        Mac(hex::encode_upper(hasher.finalize()).into_bytes(), PhantomData)
+    }
+
     pub fn encrypt<C: Cipher>(
         self,
         data_key: &DataKey,
@@ -0,0 +1,42 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::*;
+
+/// The only hasher supported by SOPS, and thus the one assumed when none is recorded.
+pub const SOPS_MAC_HASHER_NAME: &str = "SHA512";
+
+/// Records the name of the hasher used to compute the MAC. Omitted for SHA512 to keep files
+/// readable by SOPS, other hashers are rejected when the name found differs from `H::NAME`.
+#[impl_tools::autoimpl(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MacHasher<H: Hasher>(PhantomData<H>);
+
+impl<H: Hasher> MacHasher<H> {
+    pub fn is_sops_compatible(&self) -> bool {
+        H::NAME == SOPS_MAC_HASHER_NAME
+    }
+}
+
+impl<H: Hasher> Serialize for MacHasher<H> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(H::NAME)
+    }
+}
+
+// Missing fields are deserialized as `None` when going through `Option`.
+impl<'de, H: Hasher> Deserialize<'de> for MacHasher<H> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hasher_name = Option::<String>::deserialize(deserializer)?;
+        let hasher_name = hasher_name.as_deref().unwrap_or(SOPS_MAC_HASHER_NAME);
+
+        match hasher_name == H::NAME {
+            true => Ok(Self(PhantomData)),
+            false => Err(serde::de::Error::custom(format!(
+                "invalid MAC hasher, expected {}, found {}",
+                H::NAME,
+                hasher_name
+            ))),
+        }
+    }
+}
@@ -4,16 +4,19 @@ use crate::*;
 pub struct MacOnlyEncryptedConfig<'a> {
     pub mac_only_encrypted: bool,
     pub resolved_partial_encryption: ResolvedPartialEncryption<'a>,
+    pub encrypt_empty_values: bool,
 }
 
 impl MacOnlyEncryptedConfig<'_> {
     pub fn new<'a>(
         mac_only_encrypted: Option<bool>,
         partial_encryption: Option<&'a PartialEncryptionConfig>,
+        encrypt_empty_values: Option<bool>,
     ) -> MacOnlyEncryptedConfig<'a> {
         MacOnlyEncryptedConfig::<'a> {
             mac_only_encrypted: mac_only_encrypted.unwrap_or_default(),
             resolved_partial_encryption: partial_encryption.into(),
+            encrypt_empty_values: encrypt_empty_values.unwrap_or_default(),
         }
     }
 }
@@ -27,6 +30,7 @@ mod mock {
             Self {
                 mac_only_encrypted: false,
                 resolved_partial_encryption: ResolvedPartialEncryption::mock(),
+                encrypt_empty_values: false,
             }
         }
     }
@@ -7,6 +7,9 @@ pub(crate) use mac_only_encrypted::MacOnlyEncryptedConfig;
 mod encrypted_mac;
 pub(crate) use encrypted_mac::EncryptedMac;
 
+mod mac_hasher;
+pub use mac_hasher::{MacHasher, SOPS_MAC_HASHER_NAME};
+
 mod saved_mac_nonce;
 pub(crate) use saved_mac_nonce::SavedMacNonce;
 
@@ -17,7 +17,7 @@ mod sha2 {
         assert_ne!(mac_from_collection(&[1, 2, 3]), mac_from_collection(&[3, 2, 1]));
 
         fn mac_from_collection(ints: &[i64]) -> Mac<SHA512> {
-            let collection = ints.iter().map(|int| RopsTree::Leaf(RopsValue::Integer(*int))).collect();
+            let collection = ints.iter().map(|int| RopsTree::Leaf(RopsValue::Integer((*int).into()))).collect();
 
             let map = RopsMap(indexmap! {
                 "collection".to_string() => RopsTree::Sequence(collection)
@@ -27,6 +27,37 @@ mod sha2 {
         }
     }
 
+    #[test]
+    fn disregards_float_literals() {
+        let mac_from_float = |literal: &str| {
+            let map = RopsMap(indexmap! {
+                "float".to_string() => RopsTree::Leaf(RopsValue::Float(literal.parse().unwrap()))
+            });
+
+            Mac::<SHA512>::compute(MacOnlyEncryptedConfig::mock(), &map)
+        };
+
+        assert_eq!(mac_from_float("1"), mac_from_float("1.0"));
+        assert_eq!(mac_from_float("10000000000"), mac_from_float("1e10"));
+    }
+
+    #[test]
+    fn includes_empty_values_when_encrypted() {
+        let map_with_null = RopsMap(indexmap! {
+            "null".to_string() => RopsTree::Null,
+        });
+        let empty_map = RopsMap(indexmap! {});
+
+        let compute = |map: &RopsMap<DecryptedMap>, encrypt_empty_values: bool| {
+            let mut mac_only_encrypted_config = MacOnlyEncryptedConfig::mock();
+            mac_only_encrypted_config.encrypt_empty_values = encrypt_empty_values;
+            Mac::<SHA512>::compute(mac_only_encrypted_config, map)
+        };
+
+        assert_eq!(compute(&map_with_null, false), compute(&empty_map, false));
+        assert_ne!(compute(&map_with_null, true), compute(&empty_map, true));
+    }
+
     #[cfg(feature = "aes-gcm")]
     mod aes_gcm {
         use super::*;
@@ -8,11 +8,18 @@ pub(crate) use state::{DecryptedMetadata, EncryptedMetadata, RopsMetadataState};
 pub mod integration;
 pub(crate) use integration::*;
 
+mod diff;
+pub use diff::MetadataDiff;
+
+mod inspect;
+pub use inspect::IntegrationKeyInfo;
+
 mod last_modified;
 pub(crate) use last_modified::LastModifiedDateTime;
 
 mod mac;
 pub(crate) use mac::*;
+pub use mac::{MacHasher, SOPS_MAC_HASHER_NAME};
 
 mod partial_encryption;
 pub use partial_encryption::PartialEncryptionConfig;
@@ -1,7 +1,7 @@
 use derive_more::AsRef;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PartialEncryptionConfig {
     // Limit
@@ -65,7 +65,7 @@ mod regex {
     use regex::Regex;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Debug, Serialize, Deserialize, Deref, From, Into)]
+    #[derive(Debug, Clone, Serialize, Deserialize, Deref, From, Into)]
     #[serde(transparent)]
     pub struct RopsRegex(#[serde(with = "serde_regex")] Regex);
 
@@ -9,6 +9,7 @@ mod core {
 
     pub trait RopsMetadataState: private::SealedRopsMetadataState {
         type Mac: Debug + PartialEq + FromStr + Display;
+        type Hasher: Hasher;
     }
 
     mod private {
@@ -30,6 +31,7 @@ mod encrypted {
 
     impl<C: Cipher, H: Hasher> RopsMetadataState for EncryptedMetadata<C, H> {
         type Mac = EncryptedMac<C, H>;
+        type Hasher = H;
     }
 }
 
@@ -42,5 +44,6 @@ mod decrypted {
 
     impl<H: Hasher> RopsMetadataState for DecryptedMetadata<H> {
         type Mac = Mac<H>;
+        type Hasher = H;
     }
 }
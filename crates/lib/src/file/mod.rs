@@ -1,8 +1,20 @@
 mod core;
 pub use core::{RopsFile, RopsFileFromStrError};
 
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+mod any;
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub use any::{AnyHasherRopsFile, AnyRopsFile, AnyRopsFileFromStrError, RopsFileVisitor, SUPPORTED_CIPHER_NAMES, SUPPORTED_HASHER_NAMES};
+
+mod diff;
+pub use diff::RopsFileDiff;
+
+mod status;
+pub use status::RopsFileStatus;
+
 mod error;
-pub(crate) use error::{RopsFileAddKeyError, RopsFileDecryptError, RopsFileEncryptError};
+pub use error::RopsFileConvertError;
+pub(crate) use error::{RopsFileAddKeyError, RopsFileDecryptError, RopsFileEncryptError, RopsFileMergeError, RopsFileSetMapError};
 
 pub mod state;
 pub(crate) use state::{DecryptedFile, EncryptedFile, RopsFileState};
@@ -19,6 +31,13 @@ pub(crate) use builder::*;
 pub mod format;
 pub(crate) use format::*;
 
+pub mod de;
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub(crate) use de::*;
+
+pub mod stream;
+pub(crate) use stream::*;
+
 mod saved_parameters;
 pub(crate) use saved_parameters::SavedParameters;
 
@@ -0,0 +1,157 @@
+use derive_more::Display;
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::*;
+
+/// Whether a string holds an encrypted rops file, detectable without any private key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum RopsFileStatus {
+    /// Valid metadata is present and every value which should be encrypted is.
+    #[display("encrypted")]
+    Encrypted,
+    /// Valid metadata is present, but some values which should be encrypted are in plaintext.
+    /// Also used for multi-document files mixing encrypted and plaintext documents.
+    #[display("partially encrypted")]
+    PartiallyEncrypted,
+    /// A map without any metadata.
+    #[display("plaintext")]
+    Plaintext,
+    /// Unparsable in the file format, or containing invalid metadata.
+    #[display("malformed")]
+    Malformed,
+}
+
+impl RopsFileStatus {
+    /// Each document of a multi-document string is inspected separately.
+    pub fn detect<C: Cipher, H: Hasher, F: FileFormat>(str: &str) -> Self {
+        Self::detect_documents::<F>(str, |document_str| match document_str.parse::<RopsFile<EncryptedFile<C, H>, F>>() {
+            Ok(rops_file) => Self::detect_encrypted(rops_file),
+            Err(_) => Self::Malformed,
+        })
+    }
+
+    /// Like [`RopsFileStatus::detect`], but with the cipher of each document determined by its MAC.
+    #[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+    pub fn detect_any<F: FileFormat>(str: &str) -> Self {
+        return Self::detect_documents::<F>(str, |document_str| match document_str.parse::<AnyRopsFile<F>>() {
+            Ok(any_rops_file) => any_rops_file.visit(DetectEncryptedVisitor),
+            Err(_) => Self::Malformed,
+        });
+
+        struct DetectEncryptedVisitor;
+
+        impl<F: FileFormat> RopsFileVisitor<F> for DetectEncryptedVisitor {
+            type Output = RopsFileStatus;
+
+            fn visit<C: Cipher, H: Hasher>(self, rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self::Output {
+                RopsFileStatus::detect_encrypted(rops_file)
+            }
+        }
+    }
+
+    fn detect_documents<F: FileFormat>(str: &str, parse_encrypted: impl Fn(&str) -> Self) -> Self {
+        F::split_documents(str)
+            .iter()
+            .map(|document_str| Self::detect_document::<F>(document_str, &parse_encrypted))
+            .reduce(|combined_status, document_status| match (combined_status, document_status) {
+                (Self::Malformed, _) | (_, Self::Malformed) => Self::Malformed,
+                (combined_status, document_status) if combined_status == document_status => combined_status,
+                _ => Self::PartiallyEncrypted,
+            })
+            .unwrap_or(Self::Malformed)
+    }
+
+    fn detect_document<F: FileFormat>(document_str: &str, parse_encrypted: impl Fn(&str) -> Self) -> Self {
+        #[derive(Deserialize)]
+        struct MaybeMetadata {
+            sops: Option<IgnoredAny>,
+        }
+
+        let Ok(maybe_metadata) = F::deserialize_from_str::<MaybeMetadata>(document_str) else {
+            return Self::Malformed;
+        };
+
+        if maybe_metadata.sops.is_none() {
+            return match document_str
+                .parse::<RopsFileFormatMap<DecryptedMap, F>>()
+                .map(|format_map| format_map.to_internal())
+            {
+                Ok(Ok(_)) => Self::Plaintext,
+                _ => Self::Malformed,
+            };
+        }
+
+        parse_encrypted(document_str)
+    }
+
+    fn detect_encrypted<C: Cipher, H: Hasher, F: FileFormat>(rops_file: RopsFile<EncryptedFile<C, H>, F>) -> Self {
+        match rops_file.convert::<F>() {
+            Ok(_) => Self::Encrypted,
+            Err(RopsFileConvertError::FormatToIntenrnalMap(
+                FormatToInternalMapError::PlaintextWhenEncrypted(_) | FormatToInternalMapError::EncryptedRopsValue(_),
+            )) => Self::PartiallyEncrypted,
+            Err(_) => Self::Malformed,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "yaml", feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod tests {
+    use super::*;
+
+    fn detect(str: &str) -> RopsFileStatus {
+        RopsFileStatus::detect::<AES256GCM, SHA512, YamlFileFormat>(str)
+    }
+
+    fn encrypted_string() -> String {
+        RopsFile::<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>::mock().to_string()
+    }
+
+    #[test]
+    fn detects_encrypted() {
+        assert_eq!(RopsFileStatus::Encrypted, detect(&encrypted_string()))
+    }
+
+    #[test]
+    fn detects_partially_encrypted() {
+        let partially_encrypted_string = encrypted_string()
+            .lines()
+            .map(|line| match line.starts_with("hello:") {
+                true => "hello: world!",
+                false => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(RopsFileStatus::PartiallyEncrypted, detect(&partially_encrypted_string))
+    }
+
+    #[test]
+    fn detects_mixed_documents_as_partially_encrypted() {
+        let mixed_string = format!("{}---\nhello: world!\n", encrypted_string());
+        assert_eq!(RopsFileStatus::PartiallyEncrypted, detect(&mixed_string))
+    }
+
+    #[test]
+    fn detects_plaintext() {
+        assert_eq!(RopsFileStatus::Plaintext, detect("hello: world!\n"))
+    }
+
+    #[test]
+    fn detects_malformed() {
+        assert_eq!(RopsFileStatus::Malformed, detect("hello: [world!\n"));
+        assert_eq!(RopsFileStatus::Malformed, detect("hello: world!\nsops: invalid\n"))
+    }
+
+    #[test]
+    fn detects_any_cipher() {
+        assert_eq!(
+            RopsFileStatus::Encrypted,
+            RopsFileStatus::detect_any::<YamlFileFormat>(&encrypted_string())
+        );
+        assert_eq!(
+            RopsFileStatus::Malformed,
+            RopsFileStatus::detect_any::<YamlFileFormat>(&encrypted_string().replace(AES256GCM::NAME, "GIBBERISH"))
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    io::{BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+};
+
+use serde::{Deserializer, Serializer};
+
+use crate::*;
+
+/// File formats of which plaintext maps can be read and encrypted incrementally, see
+/// [`RopsFileStreamEncryptor`]. Only implemented for JSON, `serde_yaml` reads YAML input as a
+/// whole and parses it into events up front, which leaves streaming it with bounded memory out of
+/// scope.
+pub trait StreamFileFormat: FileFormat {
+    /// Hands a deserializer reading from `reader` and a serializer writing to `writer` over to
+    /// `transcoder`. The serializer is expected to be configured as the one used by
+    /// [`FileFormat::serialize_rops_file`], and trailing input to be rejected.
+    fn transcode_stream(reader: impl Read, writer: impl Write, transcoder: impl StreamTranscoder) -> Result<(), Self::SerializeError>;
+}
+
+/// Transcodes a value from a format deserializer directly into a format serializer.
+pub trait StreamTranscoder {
+    /// `source_literal` maps number literals handed out by the deserializer back to how they were
+    /// written, for deserializers which normalize them.
+    fn transcode<'de, D: Deserializer<'de>, S: Serializer>(
+        self,
+        deserializer: D,
+        serializer: S,
+        source_literal: &dyn Fn(String) -> String,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RopsFileStreamEncryptError {
+    #[error(transparent)]
+    Encrypt(#[from] RopsFileEncryptError),
+    #[error(transparent)]
+    IntegrationMetaData(#[from] IntegrationMetadataBuilderError),
+    #[error("duplicate key: {0}")]
+    DuplicateKey(String),
+    #[error("unable to transcode plaintext map: {0}")]
+    Transcode(anyhow::Error),
+    #[error("unable to write encrypted file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Encrypts a plaintext map read from a reader, writing the resulting rops file to a writer as
+/// values are encrypted. The MAC is computed as values pass by, so neither the format map, the
+/// internal map nor the serialized file is built.
+///
+/// Configured just as [`RopsFileBuilder`], and given the same data key, nonces and last modified
+/// timestamp, the written file is byte-identical to that of [`RopsFileBuilder::encrypt`]. Only
+/// difference being that duplicate keys are rejected rather than overwritten.
+pub struct RopsFileStreamEncryptor<F: StreamFileFormat> {
+    partial_encryption: Option<PartialEncryptionConfig>,
+    mac_only_encrypted: Option<bool>,
+    integration_metadata_builder: IntegrationMetadataBuilder,
+    format: PhantomData<F>,
+}
+
+impl<F: StreamFileFormat> Default for RopsFileStreamEncryptor<F> {
+    fn default() -> Self {
+        Self {
+            partial_encryption: None,
+            mac_only_encrypted: None,
+            integration_metadata_builder: Default::default(),
+            format: PhantomData,
+        }
+    }
+}
+
+impl<F: StreamFileFormat> RopsFileStreamEncryptor<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_partial_encryption(mut self, partial_encryption: PartialEncryptionConfig) -> Self {
+        self.partial_encryption = Some(partial_encryption);
+        self
+    }
+
+    pub fn mac_only_encrypted(mut self) -> Self {
+        self.mac_only_encrypted = Some(true);
+        self
+    }
+
+    pub fn add_integration_key<I: Integration>(mut self, key_id: I::KeyId) -> Self {
+        key_id.append_to_metadata_builder(&mut self.integration_metadata_builder);
+        self
+    }
+
+    pub fn add_integration_keys<I: Integration>(self, key_ids: impl IntoIterator<Item = I::KeyId>) -> Self {
+        key_ids.into_iter().fold(self, |mut encryptor, key_id| {
+            encryptor = encryptor.add_integration_key::<I>(key_id);
+            encryptor
+        })
+    }
+
+    pub fn encrypt<C: Cipher, H: Hasher>(self, reader: impl Read, writer: impl Write) -> Result<(), RopsFileStreamEncryptError> {
+        #[rustfmt::skip]
+        let Self { partial_encryption, mac_only_encrypted, integration_metadata_builder, .. } = self;
+
+        let data_key = DataKey::new();
+        // Built before anything is written, integration errors shouldn't leave behind a partially written file.
+        let integration_metadata = integration_metadata_builder.into_integration_metadata(&data_key)?;
+
+        let transcoder = EncryptingTranscoder::<C, H>::new(&data_key, partial_encryption, mac_only_encrypted, integration_metadata);
+
+        let mut buffered_writer = BufWriter::new(writer);
+        let transcode_result = F::transcode_stream(BufReader::new(reader), &mut buffered_writer, &transcoder);
+        transcoder.finish(transcode_result)?;
+
+        buffered_writer.flush().map_err(Into::into)
+    }
+}
@@ -0,0 +1,8 @@
+mod core;
+pub use core::{RopsFileStreamEncryptError, RopsFileStreamEncryptor, StreamFileFormat, StreamTranscoder};
+
+mod transcoder;
+pub(crate) use transcoder::EncryptingTranscoder;
+
+#[cfg(all(test, feature = "json", feature = "age", feature = "aes-gcm", feature = "sha2"))]
+mod tests;
@@ -0,0 +1,90 @@
+use crate::*;
+
+type MockRopsFile<F> = RopsFile<EncryptedFile<AES256GCM, SHA512>, F>;
+
+fn stream_encrypt<F: StreamFileFormat>(
+    encryptor: RopsFileStreamEncryptor<F>,
+    plaintext_map: &str,
+) -> Result<String, RopsFileStreamEncryptError> {
+    AgeIntegration::set_mock_private_key_env_var();
+
+    let mut encrypted_file = Vec::new();
+    encryptor
+        .add_integration_key::<AgeIntegration>(MockTestUtil::mock())
+        .encrypt::<AES256GCM, SHA512>(plaintext_map.as_bytes(), &mut encrypted_file)?;
+
+    Ok(String::from_utf8(encrypted_file).unwrap())
+}
+
+/// Re-encrypts the streamed file through `RopsFile` with the same data key, nonces and last
+/// modified timestamp. Decryption also verifies the MAC computed whilst streaming.
+fn assert_byte_identical<F: StreamFileFormat>(encryptor: RopsFileStreamEncryptor<F>, plaintext_map: &str)
+where
+    F::Map: PartialEq + std::fmt::Debug,
+{
+    let streamed_file = stream_encrypt(encryptor, plaintext_map).unwrap();
+
+    let (decrypted_file, saved_parameters) = streamed_file
+        .parse::<MockRopsFile<F>>()
+        .unwrap()
+        .decrypt_and_save_parameters::<F>()
+        .unwrap();
+
+    assert_eq!(
+        &plaintext_map.parse::<RopsFileFormatMap<DecryptedMap, F>>().unwrap(),
+        decrypted_file.map()
+    );
+
+    let reencrypted_file = decrypted_file
+        .encrypt_with_saved_parameters::<AES256GCM, F>(saved_parameters)
+        .unwrap()
+        .to_string();
+
+    pretty_assertions::assert_eq!(reencrypted_file, streamed_file);
+}
+
+#[test]
+fn streams_json() {
+    assert_byte_identical(
+        RopsFileStreamEncryptor::<JsonFileFormat>::new(),
+        &RopsFileFormatMap::<DecryptedMap, JsonFileFormat>::mock_format_display(),
+    )
+}
+
+#[test]
+fn streams_with_partial_encryption() {
+    assert_byte_identical(
+        RopsFileStreamEncryptor::<JsonFileFormat>::new()
+            .with_partial_encryption(MockTestUtil::mock())
+            .mac_only_encrypted(),
+        &RopsFileFormatMap::<DecryptedMap, JsonFileFormat>::mock_format_display(),
+    )
+}
+
+#[test]
+fn disallows_duplicate_keys() {
+    let error = stream_encrypt(RopsFileStreamEncryptor::<JsonFileFormat>::new(), r#"{ "a": 1, "a": 2 }"#).unwrap_err();
+    assert!(matches!(error, RopsFileStreamEncryptError::DuplicateKey(key) if key == "a"))
+}
+
+#[test]
+fn streams_json_number_literals() {
+    assert_byte_identical(
+        RopsFileStreamEncryptor::<JsonFileFormat>::new(),
+        r#"{ "big": 123456789012345678901234567890, "max": 18446744073709551615, "float": 1.50, "exponent": 1E10 }"#,
+    )
+}
+
+#[test]
+fn streams_unencrypted_json_number_literals() {
+    assert_byte_identical(
+        RopsFileStreamEncryptor::<JsonFileFormat>::new().with_partial_encryption(MockTestUtil::mock()),
+        r#"{ "big_unencrypted": 123456789012345678901234567890, "exponent_unencrypted": 1E10, "sequence_unencrypted": [1e-3] }"#,
+    )
+}
+
+#[test]
+fn disallows_trailing_input() {
+    let error = stream_encrypt(RopsFileStreamEncryptor::<JsonFileFormat>::new(), r#"{ "a": 1 } {"#).unwrap_err();
+    assert!(matches!(error, RopsFileStreamEncryptError::Transcode(_)))
+}
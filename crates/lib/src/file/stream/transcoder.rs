@@ -0,0 +1,364 @@
+use std::{cell::RefCell, collections::HashSet, fmt::Formatter, marker::PhantomData};
+
+use serde::{
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap, SerializeSeq},
+    Deserializer, Serialize, Serializer,
+};
+
+use crate::*;
+
+const METADATA_KEY: &str = "sops";
+
+/// Mirrors `RopsMap::encrypt` and `Mac::compute` for maps that are visited only once, in order.
+pub struct EncryptingTranscoder<'a, C: Cipher, H: Hasher> {
+    data_key: &'a DataKey,
+    partial_encryption: Option<PartialEncryptionConfig>,
+    mac_only_encrypted: Option<bool>,
+    integration_metadata: RefCell<Option<IntegrationMetadata>>,
+    hasher: RefCell<Option<H>>,
+    // Errors of the transcoder itself, as serde only lets them through as strings.
+    error: RefCell<Option<RopsFileStreamEncryptError>>,
+    cipher: PhantomData<C>,
+}
+
+impl<'a, C: Cipher, H: Hasher> EncryptingTranscoder<'a, C, H> {
+    pub fn new(
+        data_key: &'a DataKey,
+        partial_encryption: Option<PartialEncryptionConfig>,
+        mac_only_encrypted: Option<bool>,
+        integration_metadata: IntegrationMetadata,
+    ) -> Self {
+        Self {
+            data_key,
+            partial_encryption,
+            mac_only_encrypted,
+            integration_metadata: RefCell::new(Some(integration_metadata)),
+            hasher: RefCell::new(Some(Mac::<H>::new_hasher(mac_only_encrypted.unwrap_or_default()))),
+            error: RefCell::new(None),
+            cipher: PhantomData,
+        }
+    }
+
+    /// Errors raised by the transcoder take precedence over the format errors they caused.
+    pub fn finish<E: std::error::Error + Send + Sync + 'static>(
+        self,
+        transcode_result: Result<(), E>,
+    ) -> Result<(), RopsFileStreamEncryptError> {
+        match (self.error.into_inner(), transcode_result) {
+            (Some(error), _) => Err(error),
+            (None, transcode_result) => transcode_result.map_err(|error| RopsFileStreamEncryptError::Transcode(error.into())),
+        }
+    }
+
+    fn fail<E: de::Error>(&self, error: impl Into<RopsFileStreamEncryptError>) -> E {
+        let error = error.into();
+        let de_error = E::custom(&error);
+        self.error.borrow_mut().get_or_insert(error);
+        de_error
+    }
+
+    fn encrypted_metadata(&self) -> Result<RopsFileMetadata<EncryptedMetadata<C, H>>, RopsFileEncryptError> {
+        let hasher = self.hasher.borrow_mut().take().expect("metadata encrypted more than once");
+
+        RopsFileMetadata {
+            intregation: self
+                .integration_metadata
+                .borrow_mut()
+                .take()
+                .expect("metadata encrypted more than once"),
+            last_modified: LastModifiedDateTime::now(),
+            mac: Mac::from_hasher(hasher),
+            partial_encryption: self.partial_encryption.clone(),
+            mac_only_encrypted: self.mac_only_encrypted,
+            encrypt_empty_values: None,
+            mac_hasher: MacHasher::default(),
+        }
+        .encrypt::<C>(self.data_key)
+        .map_err(|error| RopsFileEncryptError::MetadataEncryption(error.into()))
+    }
+}
+
+impl<C: Cipher, H: Hasher> StreamTranscoder for &EncryptingTranscoder<'_, C, H> {
+    fn transcode<'de, D: Deserializer<'de>, S: Serializer>(
+        self,
+        deserializer: D,
+        serializer: S,
+        source_literal: &dyn Fn(String) -> String,
+    ) -> Result<S::Ok, S::Error> {
+        let position = Position {
+            transcoder: self,
+            key_path: KeyPath::default(),
+            resolved_partial_encryption: self.partial_encryption.as_ref().into(),
+            source_literal,
+        };
+
+        return deserializer
+            .deserialize_map(RootVisitor { position, serializer })
+            .map_err(ser::Error::custom);
+
+        struct RootVisitor<'t, 'a, C: Cipher, H: Hasher, S: Serializer> {
+            position: Position<'t, 'a, C, H>,
+            serializer: S,
+        }
+
+        impl<'de, C: Cipher, H: Hasher, S: Serializer> Visitor<'de> for RootVisitor<'_, '_, C, H, S> {
+            type Value = S::Ok;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("a plaintext map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map_access: A) -> Result<Self::Value, A::Error> {
+                let first_key = map_access.next_key::<String>()?;
+                let mut serialize_map = self.serializer.serialize_map(None).map_err(de::Error::custom)?;
+                transcode_entries(&self.position, first_key, &mut map_access, &mut serialize_map)?;
+
+                let transcoder = self.position.transcoder;
+                let encrypted_metadata = transcoder.encrypted_metadata().map_err(|error| transcoder.fail(error))?;
+
+                serialize_map
+                    .serialize_entry(METADATA_KEY, &encrypted_metadata)
+                    .map_err(de::Error::custom)?;
+                serialize_map.end().map_err(de::Error::custom)
+            }
+        }
+    }
+}
+
+#[impl_tools::autoimpl(Clone)]
+struct Position<'t, 'a, C: Cipher, H: Hasher> {
+    transcoder: &'t EncryptingTranscoder<'a, C, H>,
+    key_path: KeyPath,
+    resolved_partial_encryption: ResolvedPartialEncryption<'t>,
+    source_literal: &'t dyn Fn(String) -> String,
+}
+
+impl<C: Cipher, H: Hasher> Position<'_, '_, C, H> {
+    fn join(&self, key: &str) -> Self {
+        let mut resolved_partial_encryption = self.resolved_partial_encryption;
+
+        if let ResolvedPartialEncryption::No(partial_encryption_config) = resolved_partial_encryption {
+            resolved_partial_encryption = partial_encryption_config.resolve(key)
+        }
+
+        Self {
+            transcoder: self.transcoder,
+            key_path: self.key_path.join(key),
+            resolved_partial_encryption,
+            source_literal: self.source_literal,
+        }
+    }
+
+    fn transcode_leaf<S: Serializer, E: de::Error>(&self, serializer: S, value: RopsValue) -> Result<S::Ok, E> {
+        self.transcode_leaf_with(serializer, value, |serializer, value| match value {
+            RopsValue::String(string) => serializer.serialize_str(string),
+            RopsValue::Boolean(boolean) => serializer.serialize_bool(*boolean),
+            RopsValue::Integer(integer) => match i64::try_from(integer) {
+                Ok(integer) => serializer.serialize_i64(integer),
+                Err(_) => serializer.serialize_u64(u64::try_from(integer).expect("visited integer not an i64 nor an u64")),
+            },
+            RopsValue::Float(float) => serializer.serialize_f64(float.into()),
+        })
+    }
+
+    #[cfg(feature = "json")]
+    fn transcode_json_number<S: Serializer, E: de::Error>(&self, serializer: S, literal: String) -> Result<S::Ok, E> {
+        let literal = (self.source_literal)(literal);
+        let number = json_literals::number(&literal).ok_or_else(|| E::custom(format!("invalid number literal: {literal}")))?;
+
+        self.transcode_leaf_with(serializer, json_literals::to_internal_number(&literal), |serializer, _| {
+            number.serialize(serializer)
+        })
+    }
+
+    /// `serialize_plaintext` is used for values which are left unencrypted.
+    fn transcode_leaf_with<S: Serializer, E: de::Error>(
+        &self,
+        serializer: S,
+        value: RopsValue,
+        serialize_plaintext: impl FnOnce(S, &RopsValue) -> Result<S::Ok, S::Error>,
+    ) -> Result<S::Ok, E> {
+        let transcoder = self.transcoder;
+        let escape_encryption = self.resolved_partial_encryption.escape_encryption();
+
+        if !(escape_encryption && transcoder.mac_only_encrypted.unwrap_or_default()) {
+            if let Some(hasher) = transcoder.hasher.borrow_mut().as_mut() {
+                hasher.update(value.as_mac_bytes())
+            }
+        }
+
+        let empty_string = matches!(&value, RopsValue::String(str) if str.is_empty());
+
+        let serialize_result = match escape_encryption || empty_string {
+            true => serialize_plaintext(serializer, &value),
+            false => {
+                let encrypted_value = value
+                    .encrypt::<C>(Nonce::new(), transcoder.data_key, &self.key_path)
+                    .map_err(|error| transcoder.fail::<E>(RopsFileEncryptError::MapEncryption(error.into())))?;
+
+                serializer.serialize_str(&encrypted_value.to_string())
+            }
+        };
+
+        serialize_result.map_err(de::Error::custom)
+    }
+}
+
+/// `first_key` has already been taken out of `map_access`.
+fn transcode_entries<'de, C: Cipher, H: Hasher, A: MapAccess<'de>, M: SerializeMap>(
+    position: &Position<'_, '_, C, H>,
+    first_key: Option<String>,
+    map_access: &mut A,
+    serialize_map: &mut M,
+) -> Result<(), A::Error> {
+    let mut visited_keys = HashSet::new();
+    let mut next_key = first_key;
+
+    while let Some(key) = next_key {
+        if !visited_keys.insert(key.clone()) {
+            return Err(position.transcoder.fail(RopsFileStreamEncryptError::DuplicateKey(key)));
+        }
+
+        serialize_map.serialize_key(&key).map_err(de::Error::custom)?;
+        map_access.next_value_seed(ValueSeed {
+            position: position.join(&key),
+            serialize_map: &mut *serialize_map,
+        })?;
+
+        next_key = map_access.next_key::<String>()?;
+    }
+
+    Ok(())
+}
+
+struct ValueSeed<'m, 't, 'a, C: Cipher, H: Hasher, M: SerializeMap> {
+    position: Position<'t, 'a, C, H>,
+    serialize_map: &'m mut M,
+}
+
+impl<'de, C: Cipher, H: Hasher, M: SerializeMap> DeserializeSeed<'de> for ValueSeed<'_, '_, '_, C, H, M> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.serialize_map
+            .serialize_value(&TreeTranscoder::new(self.position, deserializer))
+            .map_err(de::Error::custom)
+    }
+}
+
+struct ElementSeed<'s, 't, 'a, C: Cipher, H: Hasher, Q: SerializeSeq> {
+    position: Position<'t, 'a, C, H>,
+    serialize_seq: &'s mut Q,
+}
+
+impl<'de, C: Cipher, H: Hasher, Q: SerializeSeq> DeserializeSeed<'de> for ElementSeed<'_, '_, '_, C, H, Q> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.serialize_seq
+            .serialize_element(&TreeTranscoder::new(self.position, deserializer))
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serializes a tree by deserializing it, `Serialize` only lends out the serializer by `&self`.
+struct TreeTranscoder<'t, 'a, C: Cipher, H: Hasher, D> {
+    position: Position<'t, 'a, C, H>,
+    deserializer: RefCell<Option<D>>,
+}
+
+impl<'t, 'a, C: Cipher, H: Hasher, D> TreeTranscoder<'t, 'a, C, H, D> {
+    fn new(position: Position<'t, 'a, C, H>, deserializer: D) -> Self {
+        Self {
+            position,
+            deserializer: RefCell::new(Some(deserializer)),
+        }
+    }
+}
+
+impl<'de, C: Cipher, H: Hasher, D: Deserializer<'de>> Serialize for TreeTranscoder<'_, '_, C, H, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let deserializer = self.deserializer.borrow_mut().take().expect("tree transcoded more than once");
+
+        deserializer
+            .deserialize_any(TreeVisitor {
+                position: self.position.clone(),
+                serializer,
+            })
+            .map_err(ser::Error::custom)
+    }
+}
+
+struct TreeVisitor<'t, 'a, C: Cipher, H: Hasher, S: Serializer> {
+    position: Position<'t, 'a, C, H>,
+    serializer: S,
+}
+
+impl<'de, C: Cipher, H: Hasher, S: Serializer> Visitor<'de> for TreeVisitor<'_, '_, C, H, S> {
+    type Value = S::Ok;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a plaintext map value")
+    }
+
+    fn visit_bool<E: de::Error>(self, boolean: bool) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::Boolean(boolean))
+    }
+
+    fn visit_i64<E: de::Error>(self, integer: i64) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::Integer(integer.into()))
+    }
+
+    fn visit_u64<E: de::Error>(self, integer: u64) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::Integer(integer.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, float: f64) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::Float(float.into()))
+    }
+
+    fn visit_str<E: de::Error>(self, str: &str) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::String(str.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, string: String) -> Result<Self::Value, E> {
+        self.position.transcode_leaf(self.serializer, RopsValue::String(string))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.serializer.serialize_unit().map_err(de::Error::custom)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visit_unit()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq_access: A) -> Result<Self::Value, A::Error> {
+        let mut serialize_seq = self.serializer.serialize_seq(None).map_err(de::Error::custom)?;
+
+        while seq_access
+            .next_element_seed(ElementSeed {
+                position: self.position.clone(),
+                serialize_seq: &mut serialize_seq,
+            })?
+            .is_some()
+        {}
+
+        serialize_seq.end().map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map_access: A) -> Result<Self::Value, A::Error> {
+        let first_key = map_access.next_key::<String>()?;
+
+        #[cfg(feature = "json")]
+        if first_key.as_deref() == Some(json_literals::NUMBER_KEY) {
+            let literal = map_access.next_value::<String>()?;
+            return self.position.transcode_json_number(self.serializer, literal);
+        }
+
+        let mut serialize_map = self.serializer.serialize_map(None).map_err(de::Error::custom)?;
+        transcode_entries(&self.position, first_key, &mut map_access, &mut serialize_map)?;
+        serialize_map.end().map_err(de::Error::custom)
+    }
+}
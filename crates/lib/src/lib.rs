@@ -5,6 +5,8 @@ pub mod cryptography;
 pub(crate) use cryptography::*;
 
 pub mod file;
+#[cfg(all(any(feature = "aes-gcm", feature = "chacha20-poly1305"), any(feature = "sha2", feature = "blake3")))]
+pub use file::de::{from_path, from_reader, from_str};
 pub(crate) use file::*;
 
 pub mod integration;
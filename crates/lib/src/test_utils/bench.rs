@@ -0,0 +1,56 @@
+use crate::*;
+
+/// Internal decrypted map, exposed so that the benchmarks under `benches/` can measure the
+/// `FileFormat` adapters, MAC computation and map traversal in isolation.
+pub struct BenchMap(RopsMap<DecryptedMap>);
+
+impl BenchMap {
+    pub fn from_format_map<F: FileFormat>(format_map: F::Map) -> Result<Self, FormatToInternalMapError> {
+        RopsFileFormatMap::<DecryptedMap, F>::from_inner_map(format_map)
+            .to_internal()
+            .map(Self)
+    }
+
+    pub fn into_format_map<F: FileFormat>(self) -> Result<F::Map, FormatFromInternalMapError> {
+        self.0.to_external::<F>().map(RopsFileFormatMap::into_inner_map)
+    }
+
+    pub fn compute_mac<H: Hasher>(&self, mac_only_encrypted: bool) -> Mac<H> {
+        Mac::compute(MacOnlyEncryptedConfig::new(Some(mac_only_encrypted), None, None), &self.0)
+    }
+}
+
+/// Internal map encrypted with the mock data key, see [`BenchMap::encrypt`].
+#[cfg(all(feature = "parallel", feature = "aes-gcm"))]
+pub struct BenchEncryptedMap(RopsMap<EncryptedMap<AES256GCM>>);
+
+#[cfg(all(feature = "parallel", feature = "aes-gcm"))]
+impl BenchMap {
+    /// Encrypts with the mock data key, visiting sibling subtrees either in parallel or sequentially.
+    pub fn encrypt(self, parallel: bool) -> BenchEncryptedMap {
+        BenchEncryptedMap(
+            self.0
+                .encrypt_recursive_impl(&DataKey::mock(), None, false, None, traversal(parallel))
+                .unwrap(),
+        )
+    }
+}
+
+#[cfg(all(feature = "parallel", feature = "aes-gcm"))]
+impl BenchEncryptedMap {
+    pub fn decrypt(self, parallel: bool) -> BenchMap {
+        BenchMap(
+            RopsMap::decrypt_impl(self.0, &DataKey::mock(), false, traversal(parallel))
+                .unwrap()
+                .0,
+        )
+    }
+}
+
+#[cfg(all(feature = "parallel", feature = "aes-gcm"))]
+fn traversal(parallel: bool) -> Traversal {
+    match parallel {
+        true => Traversal::Parallel,
+        false => Traversal::Sequential,
+    }
+}
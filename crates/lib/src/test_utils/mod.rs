@@ -6,3 +6,8 @@ pub use display::{DisplayTestUtils, MockDisplayTestUtil};
 
 mod from_str;
 pub use from_str::FromStrTestUtils;
+
+mod bench;
+#[cfg(all(feature = "parallel", feature = "aes-gcm"))]
+pub use bench::BenchEncryptedMap;
+pub use bench::BenchMap;
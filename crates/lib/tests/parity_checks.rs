@@ -10,6 +10,97 @@ mod age_yaml_aes_gcm_sha2 {
     age_parity_check!(unencrypted_regex);
     age_parity_check!(mac_only_encrypted);
 
+    #[test]
+    fn decrypts_every_sops_string_type() -> anyhow::Result<()> {
+        use rops::{
+            cryptography::{cipher::AES256GCM, hasher::SHA512},
+            file::{format::*, state::*, RopsFile},
+            integration::*,
+        };
+
+        AgeIntegration::set_mock_private_key_env_var();
+
+        // SOPS doesn't authenticate type tags, re-tagging its string ciphertexts is therefore valid.
+        for type_tag in ["str", "bytes", "comment"] {
+            // Only values are re-tagged, the MAC remains of type `str`.
+            let (sops_map_str, sops_metadata_str) = include_str!("sops_references/age_example.yaml").split_once("sops:").unwrap();
+            let sops_file = format!(
+                "{}sops:{sops_metadata_str}",
+                sops_map_str.replace(",type:str]", &format!(",type:{type_tag}]"))
+            );
+
+            let (decrypted_rops_file, saved_parameters) = sops_file
+                .parse::<RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>>()?
+                .decrypt_and_save_parameters::<YamlFileFormat>()?;
+
+            pretty_assertions::assert_eq!(
+                include_str!("sops_references/age_example_plaintext.yaml"),
+                decrypted_rops_file.map().to_string()
+            );
+
+            // Type tags are kept when re-encrypting.
+            pretty_assertions::assert_eq!(
+                sops_file,
+                decrypted_rops_file
+                    .encrypt_with_saved_parameters::<AES256GCM, YamlFileFormat>(saved_parameters)?
+                    .to_string()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn encrypts_empty_values_when_opted_into() -> anyhow::Result<()> {
+        use rops::{
+            cryptography::{cipher::AES256GCM, hasher::SHA512},
+            file::{builder::RopsFileBuilder, format::*, state::*, RopsFile},
+            integration::*,
+            test_utils::MockTestUtil,
+        };
+
+        AgeIntegration::set_mock_private_key_env_var();
+
+        let plaintext = include_str!("sops_references/age_empty_value_plaintext.yaml");
+
+        let encrypted_rops_file = RopsFileBuilder::<YamlFileFormat>::new(plaintext)?
+            .add_integration_key::<AgeIntegration>(<AgeIntegration as Integration>::KeyId::mock())
+            .encrypt_empty_values()
+            .encrypt::<AES256GCM, SHA512>()?;
+
+        let encrypted_map_str = encrypted_rops_file.map().to_string();
+        assert!(!encrypted_map_str.contains("''") && !encrypted_map_str.contains("null\n"));
+        assert_eq!(Some(true), encrypted_rops_file.metadata().encrypt_empty_values);
+
+        // SOPS leaves the very same values as is, see its reference. Empty strings are encrypted with
+        // its `str` type, whereas nulls are given the rops specific `null` type, unreadable by SOPS.
+        let sops_map_str = include_str!("sops_references/age_empty_value.yaml")
+            .parse::<RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>>()?
+            .map()
+            .to_string();
+        pretty_assertions::assert_eq!(plaintext, sops_map_str);
+        assert_eq!(sops_map_str.matches("''").count(), encrypted_map_str.matches(",type:str]").count());
+        assert_eq!(
+            sops_map_str.matches(" null\n").count(),
+            encrypted_map_str.matches(",type:null]").count()
+        );
+
+        let encrypted_rops_file_str = encrypted_rops_file.to_string();
+        let (decrypted_rops_file, saved_parameters) = encrypted_rops_file_str
+            .parse::<RopsFile<EncryptedFile<AES256GCM, SHA512>, YamlFileFormat>>()?
+            .decrypt_and_save_parameters::<YamlFileFormat>()?;
+
+        pretty_assertions::assert_eq!(plaintext, decrypted_rops_file.map().to_string());
+        pretty_assertions::assert_eq!(
+            encrypted_rops_file_str,
+            decrypted_rops_file
+                .encrypt_with_saved_parameters::<AES256GCM, YamlFileFormat>(saved_parameters)?
+                .to_string()
+        );
+
+        Ok(())
+    }
+
     #[macro_export]
     macro_rules! age_parity_check {
         ($name:tt) => {